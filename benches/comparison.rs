@@ -1,6 +1,6 @@
 use arc_swap::ArcSwap;
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
-use smr_swap::SmrSwap;
+use smr_swap::{SmrCopy, SmrSwap};
 use std::hint::black_box;
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
@@ -856,6 +856,183 @@ fn bench_swmr_read_write_ratio(c: &mut Criterion) {
     group.finish();
 }
 
+// ============================================================================
+// 基准测试 10: 小型 Copy 值 (SmrCopy 双缓冲 vs SmrSwap 堆分配)
+// ============================================================================
+fn bench_copy_value(c: &mut Criterion) {
+    let mut group = c.benchmark_group("copy_value");
+    group.sample_size(50);
+
+    // 1. 单线程读取
+    group.bench_function("smr_swap_read", |b| {
+        let swap = SmrSwap::new(0u64);
+        let local = swap.local();
+        b.iter(|| black_box(*local.load()));
+    });
+
+    group.bench_function("smr_copy_read", |b| {
+        let swap = SmrCopy::new(0u64);
+        let local = swap.local();
+        b.iter(|| black_box(*local.load()));
+    });
+
+    // 2. 单线程写入
+    group.bench_function("smr_swap_write", |b| {
+        let mut swap = SmrSwap::new(0u64);
+        let mut counter = 0u64;
+        b.iter(|| {
+            counter += 1;
+            swap.store(black_box(counter));
+        });
+    });
+
+    group.bench_function("smr_copy_write", |b| {
+        let mut swap = SmrCopy::new(0u64);
+        let mut counter = 0u64;
+        b.iter(|| {
+            counter += 1;
+            swap.store(black_box(counter));
+        });
+    });
+
+    // 3. 混合读写 (1个写入者 + N个读取者)
+    for num_readers in [2, 4] {
+        group.bench_with_input(
+            BenchmarkId::new("smr_swap_mixed", num_readers),
+            &num_readers,
+            |b, &num_readers| {
+                b.iter_custom(|iters| {
+                    let mut swap = SmrSwap::new(0u64);
+                    let readers: Vec<_> = (0..num_readers).map(|_| swap.local()).collect();
+
+                    let start = Instant::now();
+                    thread::scope(|s| {
+                        s.spawn(|| {
+                            for i in 0..iters {
+                                swap.store(i);
+                            }
+                        });
+
+                        for reader in readers {
+                            s.spawn(move || {
+                                for _ in 0..iters {
+                                    black_box(*reader.load());
+                                }
+                            });
+                        }
+                    });
+                    start.elapsed()
+                });
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("smr_copy_mixed", num_readers),
+            &num_readers,
+            |b, &num_readers| {
+                b.iter_custom(|iters| {
+                    let mut swap = SmrCopy::new(0u64);
+                    let readers: Vec<_> = (0..num_readers).map(|_| swap.local()).collect();
+
+                    let start = Instant::now();
+                    thread::scope(|s| {
+                        s.spawn(|| {
+                            for i in 0..iters {
+                                swap.store(i);
+                            }
+                        });
+
+                        for reader in readers {
+                            s.spawn(move || {
+                                for _ in 0..iters {
+                                    black_box(*reader.load());
+                                }
+                            });
+                        }
+                    });
+                    start.elapsed()
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// 基准测试 11: 分片读者槽 (每个读者一个槽 vs 共享分片)
+// ============================================================================
+fn bench_reader_shards(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reader_shards");
+    group.sample_size(50);
+
+    fn build(shards: usize) -> SmrSwap<Vec<u32>> {
+        SmrSwap::builder()
+            .reader_shards(shards)
+            .auto_reclaim_threshold(None)
+            .build(create_data(DATA_SIZE))
+    }
+
+    // 1. 大量读者存活时的回收开销
+    for num_readers in [64, 1024] {
+        for (name, shards) in [("registered", 0), ("sharded", BATCH_THREADS)] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{name}_collect"), num_readers),
+                &num_readers,
+                |b, &num_readers| {
+                    let mut swap = build(shards);
+                    let _readers: Vec<_> = (0..num_readers).map(|_| swap.local()).collect();
+                    let mut counter = 0u64;
+                    b.iter(|| {
+                        counter += 1;
+                        swap.store(create_indexed_data(counter, DATA_SIZE));
+                        swap.collect();
+                    });
+                },
+            );
+        }
+    }
+
+    // 2. 多线程中短生命周期的读者 (创建读者 + 读取)
+    for num_threads in [2, 4, 8] {
+        for (name, shards) in [("registered", 0), ("sharded", num_threads)] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{name}_short_lived"), num_threads),
+                &num_threads,
+                |b, &num_threads| {
+                    b.iter_custom(|iters| {
+                        let mut swap = build(shards);
+                        let reader = swap.reader();
+
+                        let start = Instant::now();
+                        thread::scope(|s| {
+                            s.spawn(|| {
+                                for i in 0..iters / 16 {
+                                    swap.store(create_indexed_data(i, DATA_SIZE));
+                                    swap.collect();
+                                }
+                            });
+
+                            for _ in 0..num_threads {
+                                let reader = reader.clone();
+                                s.spawn(move || {
+                                    for _ in 0..iters {
+                                        let local = reader.local();
+                                        black_box(local.load()[0]);
+                                    }
+                                });
+                            }
+                        });
+                        start.elapsed()
+                    });
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_handle_ops,
@@ -870,6 +1047,8 @@ criterion_group!(
     bench_read_latency_with_held_guard,
     bench_read_under_memory_pressure,
     bench_swmr_read_write_ratio,
+    bench_copy_value,
+    bench_reader_shards,
 );
 
 criterion_main!(benches);
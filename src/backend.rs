@@ -0,0 +1,936 @@
+//! Pluggable reclamation backends for [`SmrSwap`](crate::SmrSwap).
+//!
+//! A backend decides how readers protect the value they read and when the writer may
+//! reclaim retired values. [`SmrSwap`](crate::SmrSwap), [`LocalReader`](crate::LocalReader)
+//! and [`ReadGuard`](crate::ReadGuard) take the backend as their last type parameter, which
+//! defaults to [`Versioned`], so call sites stay the same whichever backend a container uses:
+//!
+//! | Backend | Read cost | Retired values kept |
+//! |---------|-----------|---------------------|
+//! | [`Versioned`] | one version store and a barrier | everything at or after the oldest pinned version |
+//! | [`HazardPointers`] | one pointer store, a fence and a re-check | only the values readers point to |
+//! | [`RefCounted`] | a lock and a reference count | only the values readers count |
+//!
+//! With every backend the writer keeps the two most recently retired values, so
+//! [`SmrSwap::previous`](crate::SmrSwap::previous) behaves the same way.
+//!
+//! A backend is a [`Reclaimer`] whose associated types implement [`RawWriter`],
+//! [`RawReader`], [`RawShared`] and [`RawGuard`]. These traits are public so that other
+//! schemes can be plugged in, but applications only name the marker types.
+//!
+//! 用于 [`SmrSwap`](crate::SmrSwap) 的可插拔回收后端。
+//!
+//! 后端决定读者如何保护其读取的值，以及写者何时可以回收已退休的值。
+//! [`SmrSwap`](crate::SmrSwap)、[`LocalReader`](crate::LocalReader) 和
+//! [`ReadGuard`](crate::ReadGuard) 将后端作为最后一个类型参数，默认为 [`Versioned`]，
+//! 因此无论容器使用哪种后端，调用处都保持不变：
+//!
+//! | 后端 | 读取开销 | 保留的已退休值 |
+//! |------|----------|----------------|
+//! | [`Versioned`] | 一次版本存储和一个屏障 | 最旧的被 pin 版本及之后的所有值 |
+//! | [`HazardPointers`] | 一次指针存储、一个 fence 和一次重新检查 | 仅读者指向的值 |
+//! | [`RefCounted`] | 一次加锁和一次引用计数 | 仅读者计数的值 |
+//!
+//! 对于每种后端，写者都会保留最近退休的两个值，因此
+//! [`SmrSwap::previous`](crate::SmrSwap::previous) 的行为相同。
+//!
+//! 后端是一个 [`Reclaimer`]，其关联类型实现了 [`RawWriter`]、[`RawReader`]、[`RawShared`]
+//! 和 [`RawGuard`]。这些 trait 是公开的，以便接入其他方案，但应用程序只需使用标记类型。
+//!
+//! # Example
+//!
+//! ```rust
+//! use smr_swap::{HazardPointers, LocalReader, SmrSwap};
+//!
+//! fn read_len<R: smr_swap::Reclaimer>(reader: &LocalReader<String, R>) -> usize {
+//!     reader.load().len()
+//! }
+//!
+//! let mut swap = SmrSwap::builder()
+//!     .reclaimer::<HazardPointers>()
+//!     .build(String::from("hello"));
+//! let reader = swap.local();
+//! swap.store(String::from("hello, world"));
+//! assert_eq!(read_len(&reader), 12);
+//! ```
+
+use alloc::borrow::Cow;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+use allocator_api2::alloc::Allocator;
+use allocator_api2::vec::{self as alloc_vec, Vec as AllocVec};
+
+use crate::Strategy;
+use crate::observe;
+use crate::shim::{Arc, AtomicBool, AtomicUsize, Mutex, Ordering, Vec};
+
+pub use crate::cell::{VersionedGuard, VersionedReader, VersionedShared, VersionedWriter};
+pub use crate::counted::{CountedGuard, CountedReader, CountedShared, CountedWriter};
+pub use crate::hazard::{HazardGuard, HazardReader, HazardShared, HazardWriter};
+
+/// A reclamation scheme, named by a marker type such as [`Versioned`].
+///
+/// 一种回收方案，由 [`Versioned`] 等标记类型命名。
+pub trait Reclaimer: 'static {
+    /// Writer side of a container.
+    ///
+    /// 容器的写者端。
+    type Writer<T: 'static, A: Allocator + Clone + Send + Sync + 'static>: RawWriter<T, A, Reader = Self::Reader<T>, Shared = Self::Shared<T>>;
+
+    /// Thread-local reader of a container.
+    ///
+    /// 容器的线程本地读者。
+    type Reader<T: 'static>: RawReader<T, Shared = Self::Shared<T>> + 'static;
+
+    /// `Sync` handle that creates readers.
+    ///
+    /// 用于创建读者的 `Sync` 句柄。
+    type Shared<T: 'static>: RawShared<T, Reader = Self::Reader<T>> + 'static;
+}
+
+/// Writer side of a backend.
+///
+/// Values are allocated with `A`. Retired values are reported as `(version, value)`,
+/// where `version` is the last version in which the value was current.
+///
+/// 后端的写者端。
+///
+/// 值使用 `A` 分配。已退休的值以 `(version, value)` 的形式报告，
+/// 其中 `version` 是该值作为当前值的最后一个版本。
+pub trait RawWriter<T: 'static, A>: Sized {
+    /// Reader type created by [`local`](RawWriter::local).
+    ///
+    /// 由 [`local`](RawWriter::local) 创建的读者类型。
+    type Reader: RawReader<T>;
+
+    /// Handle type created by [`shared`](RawWriter::shared).
+    ///
+    /// 由 [`shared`](RawWriter::shared) 创建的句柄类型。
+    type Shared: RawShared<T>;
+
+    /// Iterator returned by [`drain_reclaimed`](RawWriter::drain_reclaimed).
+    ///
+    /// [`drain_reclaimed`](RawWriter::drain_reclaimed) 返回的迭代器。
+    type Drain<'a>: ExactSizeIterator<Item = (usize, T)>
+    where
+        Self: 'a;
+
+    /// Create the writer with its initial value.
+    ///
+    /// 使用初始值创建写者。
+    fn new(data: T, config: Config, alloc: A) -> Self;
+
+    /// Publish a new value and retire the current one.
+    ///
+    /// 发布新值并使当前值退休。
+    fn store(&mut self, data: T);
+
+    /// Publish the value built by `f` from a recycled value, if any.
+    ///
+    /// 发布由 `f` 基于回收的值（如果有）构建的新值。
+    fn store_with<F>(&mut self, f: F)
+    where
+        F: FnOnce(Option<T>) -> T;
+
+    /// Take a value out of the recycle pool.
+    ///
+    /// 从回收池中取出一个值。
+    fn take_recycled(&mut self) -> Option<T>;
+
+    /// Number of values in the recycle pool.
+    ///
+    /// 回收池中值的数量。
+    fn recycled_count(&self) -> usize;
+
+    /// Reclaim every retired value that no reader can reach, except the two newest.
+    ///
+    /// 回收所有读者都无法访问的已退休值（最新的两个除外）。
+    fn collect(&mut self);
+
+    /// Reclaim retired values as [`collect`](RawWriter::collect) does, stopping once
+    /// `budget` is spent, and report what is left.
+    ///
+    /// 像 [`collect`](RawWriter::collect) 一样回收已退休的值，在 `budget` 用完时停止，
+    /// 并报告剩余的部分。
+    fn collect_budget(&mut self, budget: ReclaimBudget) -> Collected;
+
+    /// Collect and move the reclaimed values into `sink`.
+    ///
+    /// 执行回收，并将回收的值移动到 `sink` 中。
+    fn collect_into<E: Extend<T>>(&mut self, sink: &mut E);
+
+    /// Collect and iterate over the reclaimed values, oldest first.
+    ///
+    /// 执行回收，并按从旧到新的顺序迭代回收的值。
+    fn drain_reclaimed(&mut self) -> Self::Drain<'_>;
+
+    /// Create a reader.
+    ///
+    /// 创建一个读者。
+    fn local(&self) -> Self::Reader;
+
+    /// Create a reader, or fail if the backend bounds its readers and all are in use.
+    ///
+    /// 创建一个读者；如果后端限制了读者数量且都已被使用，则失败。
+    #[inline]
+    fn try_local(&self) -> Result<Self::Reader, CapacityError> {
+        Ok(self.local())
+    }
+
+    /// Create a `Sync` handle that creates readers.
+    ///
+    /// 创建一个用于创建读者的 `Sync` 句柄。
+    fn shared(&self) -> Self::Shared;
+
+    /// The current value.
+    ///
+    /// 当前值。
+    fn get(&self) -> &T;
+
+    /// The most recently retired value, while it is kept.
+    ///
+    /// 最近退休的值（在其被保留期间）。
+    fn previous(&self) -> Option<&T>;
+
+    /// The current version, incremented by every store.
+    ///
+    /// 当前版本，每次存储都会递增。
+    fn version(&self) -> usize;
+
+    /// Number of retired values not yet reclaimed.
+    ///
+    /// 尚未回收的已退休值的数量。
+    fn garbage_count(&self) -> usize;
+
+    /// The configured memory barrier strategy.
+    ///
+    /// 配置的内存屏障策略。
+    fn strategy(&self) -> Strategy;
+
+    /// The memory barrier algorithm in use.
+    ///
+    /// 正在使用的内存屏障算法。
+    fn active_strategy(&self) -> Strategy {
+        self.strategy()
+    }
+
+    /// Number of live readers, including the writer's own, and how many of them are pinned.
+    ///
+    /// 存活读者的数量（包括写者自己的读者），以及其中被 pin 的数量。
+    fn live_readers(&self) -> (usize, usize);
+
+    /// Count the reader slots the backend allocated and those held by live readers.
+    ///
+    /// 统计后端分配的读者槽，以及被存活读者持有的槽。
+    fn reader_slots(&self) -> ReaderSlots;
+}
+
+/// Thread-local reader of a backend.
+///
+/// 后端的线程本地读者。
+pub trait RawReader<T>: Clone {
+    /// Handle type created by [`share`](RawReader::share).
+    ///
+    /// 由 [`share`](RawReader::share) 创建的句柄类型。
+    type Shared: RawShared<T>;
+
+    /// Guard returned by [`pin`](RawReader::pin).
+    ///
+    /// [`pin`](RawReader::pin) 返回的守卫。
+    type Guard<'a>: RawGuard<T>
+    where
+        Self: 'a;
+
+    /// Protect the current value until the guard is dropped.
+    ///
+    /// Nested pins return the newest value, and report the version of the outermost pin.
+    ///
+    /// 保护当前值，直到守卫被 drop。
+    ///
+    /// 嵌套的 pin 返回最新的值，并报告最外层 pin 的版本。
+    fn pin(&self) -> Self::Guard<'_>;
+
+    /// Whether a guard of this reader is alive.
+    ///
+    /// 此读者是否有存活的守卫。
+    fn is_pinned(&self) -> bool;
+
+    /// The current version of the container.
+    ///
+    /// 容器的当前版本。
+    fn version(&self) -> usize;
+
+    /// Create a `Sync` handle that creates readers.
+    ///
+    /// 创建一个用于创建读者的 `Sync` 句柄。
+    fn share(&self) -> Self::Shared;
+}
+
+/// `Sync` handle of a backend that creates readers.
+///
+/// 后端用于创建读者的 `Sync` 句柄。
+pub trait RawShared<T>: Clone {
+    /// Reader type created by [`local`](RawShared::local).
+    ///
+    /// 由 [`local`](RawShared::local) 创建的读者类型。
+    type Reader: RawReader<T>;
+
+    /// Create a reader.
+    ///
+    /// 创建一个读者。
+    fn local(&self) -> Self::Reader;
+
+    /// Create a reader, or fail if the backend bounds its readers and all are in use.
+    ///
+    /// 创建一个读者；如果后端限制了读者数量且都已被使用，则失败。
+    #[inline]
+    fn try_local(&self) -> Result<Self::Reader, CapacityError> {
+        Ok(self.local())
+    }
+}
+
+/// Guard of a backend, dereferencing to the protected value.
+///
+/// 后端的守卫，解引用为受保护的值。
+pub trait RawGuard<T>: Deref<Target = T> + Clone {
+    /// The version the guard is pinned to.
+    ///
+    /// 守卫被 pin 到的版本。
+    fn version(&self) -> usize;
+}
+
+/// The version-based scheme: readers announce a version, and the writer reclaims values
+/// older than every announced version.
+///
+/// The fastest reads, and the only backend that honours every [`Strategy`]. A reader that
+/// stays pinned keeps every value retired after its version.
+///
+/// 基于版本的方案：读者公布一个版本，写者回收比所有已公布版本都旧的值。
+///
+/// 读取最快，并且是唯一遵循每种 [`Strategy`] 的后端。保持 pin 的读者会保留其版本之后退休的所有值。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Versioned;
+
+/// The hazard-pointer scheme: readers announce the pointer they read, and the writer
+/// reclaims every retired value that no reader points to.
+///
+/// Garbage is bounded by the number of live guards plus two, however long a reader stays
+/// pinned. Readers always use `SeqCst` fences, and report [`Strategy::WritePreferred`].
+///
+/// 风险指针方案：读者公布其读取的指针，写者回收没有任何读者指向的所有已退休值。
+///
+/// 无论读者保持 pin 多久，垃圾数量都以存活守卫的数量加二为上限。
+/// 读者始终使用 `SeqCst` fence，并报告 [`Strategy::WritePreferred`]。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HazardPointers;
+
+/// The reference-counting scheme: readers take a counted reference under a lock, and the
+/// writer reclaims retired values whose count dropped to zero.
+///
+/// Slow, but simple enough to rule out the reclamation scheme when debugging. Reports
+/// [`Strategy::WritePreferred`].
+///
+/// 引用计数方案：读者在锁内获取一个计数引用，写者回收计数降为零的已退休值。
+///
+/// 速度较慢，但足够简单，可在调试时排除回收方案的问题。报告 [`Strategy::WritePreferred`]。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RefCounted;
+
+impl Reclaimer for Versioned {
+    type Writer<T: 'static, A: Allocator + Clone + Send + Sync + 'static> = VersionedWriter<T, A>;
+    type Reader<T: 'static> = VersionedReader<T>;
+    type Shared<T: 'static> = VersionedShared<T>;
+}
+
+impl Reclaimer for HazardPointers {
+    type Writer<T: 'static, A: Allocator + Clone + Send + Sync + 'static> = HazardWriter<T, A>;
+    type Reader<T: 'static> = HazardReader<T>;
+    type Shared<T: 'static> = HazardShared<T>;
+}
+
+impl Reclaimer for RefCounted {
+    type Writer<T: 'static, A: Allocator + Clone + Send + Sync + 'static> = CountedWriter<T, A>;
+    type Reader<T: 'static> = CountedReader<T>;
+    type Shared<T: 'static> = CountedShared<T>;
+}
+
+/// Construction options of a container, set through [`SmrSwapBuilder`](crate::SmrSwapBuilder).
+///
+/// 容器的构造选项，通过 [`SmrSwapBuilder`](crate::SmrSwapBuilder) 设置。
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub(crate) auto_reclaim_threshold: Option<usize>,
+    pub(crate) auto_reclaim_budget: Option<ReclaimBudget>,
+    pub(crate) recycle_capacity: usize,
+    pub(crate) initial_version: usize,
+    pub(crate) name: Option<Cow<'static, str>>,
+    pub(crate) strategy: Strategy,
+    pub(crate) reader_shards: usize,
+    pub(crate) max_readers: Option<usize>,
+}
+
+impl Config {
+    /// Number of retired values that triggers an automatic collection, if any.
+    ///
+    /// 触发自动回收的已退休值数量（如果有）。
+    #[inline]
+    pub fn auto_reclaim_threshold(&self) -> Option<usize> {
+        self.auto_reclaim_threshold
+    }
+
+    /// Budget of each automatic collection, or `None` to reclaim everything reclaimable.
+    ///
+    /// 每次自动回收的预算，`None` 表示回收所有可回收的值。
+    #[inline]
+    pub fn auto_reclaim_budget(&self) -> Option<ReclaimBudget> {
+        self.auto_reclaim_budget
+    }
+
+    /// Number of reclaimed values kept for reuse.
+    ///
+    /// 保留以供复用的已回收值的数量。
+    #[inline]
+    pub fn recycle_capacity(&self) -> usize {
+        self.recycle_capacity
+    }
+
+    /// Version of the initial value.
+    ///
+    /// 初始值的版本。
+    #[inline]
+    pub fn initial_version(&self) -> usize {
+        self.initial_version
+    }
+
+    /// Name of the container, if any.
+    ///
+    /// 容器的名称（如果有）。
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Memory barrier strategy.
+    ///
+    /// 内存屏障策略。
+    #[inline]
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    /// Number of shards readers announce their versions in, or 0 for one slot per reader.
+    ///
+    /// Only the [`Versioned`] backend shards its readers; the others ignore this setting.
+    ///
+    /// 读者公布版本所用的分片数量，0 表示每个读者一个槽。
+    ///
+    /// 只有 [`Versioned`] 后端会对读者分片；其他后端忽略此设置。
+    #[inline]
+    pub fn reader_shards(&self) -> usize {
+        self.reader_shards
+    }
+
+    /// Maximum number of readers besides the writer's own, if bounded.
+    ///
+    /// Only the [`Versioned`] backend bounds its readers; the others ignore this setting.
+    ///
+    /// 除写者自己的读者之外的最大读者数量（如果有上限）。
+    ///
+    /// 只有 [`Versioned`] 后端会限制读者数量；其他后端忽略此设置。
+    #[inline]
+    pub fn max_readers(&self) -> Option<usize> {
+        self.max_readers
+    }
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            auto_reclaim_threshold: Some(4),
+            auto_reclaim_budget: None,
+            recycle_capacity: 0,
+            initial_version: 0,
+            name: None,
+            strategy: Strategy::default(),
+            reader_shards: 0,
+            max_readers: None,
+        }
+    }
+}
+
+/// How much a single collection may reclaim before it stops.
+///
+/// Values a budgeted collection leaves behind stay retired, oldest first, and are
+/// reclaimed by a later collection.
+///
+/// 单次回收在停止之前最多可以回收多少。
+///
+/// 受预算限制的回收留下的值仍处于退休状态（最旧的在前），并由之后的回收来回收。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReclaimBudget {
+    /// Reclaim at most this many values.
+    ///
+    /// 最多回收这么多个值。
+    Items(usize),
+    /// Stop once this much time has passed. The time is checked between values, so one
+    /// expensive drop can overrun it, and at least one value is reclaimed if any can be.
+    ///
+    /// 经过这么长时间后停止。时间在值与值之间检查，因此一次昂贵的 drop 可能超出预算；
+    /// 只要有可回收的值，至少会回收一个。
+    #[cfg(feature = "std")]
+    Time(Duration),
+}
+
+/// Outcome of a budgeted collection, returned by
+/// [`SmrSwap::collect_budget`](crate::SmrSwap::collect_budget).
+///
+/// 受预算限制的回收的结果，由 [`SmrSwap::collect_budget`](crate::SmrSwap::collect_budget) 返回。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Collected {
+    pub(crate) reclaimed: usize,
+    pub(crate) pending: usize,
+    pub(crate) remaining: usize,
+}
+
+impl Collected {
+    /// Number of values reclaimed by this collection.
+    ///
+    /// 本次回收所回收的值的数量。
+    #[inline]
+    pub fn reclaimed(&self) -> usize {
+        self.reclaimed
+    }
+
+    /// Number of values no reader can reach that were left for a later collection
+    /// because the budget ran out.
+    ///
+    /// 因预算用完而留给之后回收的、读者已无法访问的值的数量。
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Number of retired values still waiting, including the pending ones.
+    ///
+    /// 仍在等待的已退休值的数量，包括待回收的值。
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Whether the collection reclaimed everything it could, so another one right away
+    /// would find nothing more.
+    ///
+    /// 本次回收是否已回收所有能回收的值，即立即再回收一次也不会有更多收获。
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.pending == 0
+    }
+}
+
+/// Error returned when a reader is requested but every reader slot is in use.
+///
+/// 请求读者但所有读者槽都已被使用时返回的错误。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityError {
+    pub(crate) max_readers: usize,
+}
+
+impl CapacityError {
+    /// Maximum number of readers of the container.
+    ///
+    /// 容器的最大读者数量。
+    #[inline]
+    pub fn max_readers(&self) -> usize {
+        self.max_readers
+    }
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "all {} reader slots are in use", self.max_readers)
+    }
+}
+
+impl core::error::Error for CapacityError {}
+
+/// Number of reader slots a container has allocated, and how many readers hold one.
+///
+/// A dropped reader frees its slot for the next reader instead of releasing its memory,
+/// so `allocated` only grows when more readers are alive at once than ever before. A
+/// count that keeps growing while the number of live readers is stable points at readers
+/// that are leaked rather than dropped.
+///
+/// 容器已分配的读者槽数量，以及其中被读者持有的数量。
+///
+/// 被 drop 的读者会将其槽留给下一个读者，而不是释放其内存，因此只有当同时存活的读者比以往
+/// 任何时候都多时，`allocated` 才会增长。在存活读者数量稳定时持续增长的计数，
+/// 说明有读者被泄漏而不是被 drop。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ReaderSlots {
+    pub(crate) allocated: usize,
+    pub(crate) in_use: usize,
+}
+
+impl ReaderSlots {
+    /// Number of slots allocated since the container was created.
+    ///
+    /// 自容器创建以来分配的槽数量。
+    #[inline]
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    /// Number of slots held by a live reader.
+    ///
+    /// 被存活读者持有的槽数量。
+    #[inline]
+    pub fn in_use(&self) -> usize {
+        self.in_use
+    }
+
+    /// Number of slots waiting for the next reader.
+    ///
+    /// 等待下一个读者的槽数量。
+    #[inline]
+    pub fn free(&self) -> usize {
+        self.allocated - self.in_use
+    }
+}
+
+// ============================================================================
+// Nodes shared by every backend
+// 所有后端共用的节点
+// ============================================================================
+
+/// Heap node holding one value, allocated by the writer's allocator.
+///
+/// 持有一个值的堆节点，由写者的分配器分配。
+pub(crate) type Node<T, A> = allocator_api2::boxed::Box<T, A>;
+
+/// Frees a node with the writer's allocator.
+///
+/// The allocator type is erased, so that readers, which may free the last value, do not
+/// depend on it.
+///
+/// 使用写者的分配器释放节点。
+///
+/// 分配器类型被擦除，因此可能释放最后一个值的读者不依赖于它。
+pub(crate) trait FreeNode<T>: Send + Sync {
+    /// Drop the value behind `ptr` and free its node.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must come from a node allocated by this allocator, and must not be used again.
+    ///
+    /// drop `ptr` 指向的值并释放其节点。
+    ///
+    /// # 安全性
+    ///
+    /// `ptr` 必须来自由此分配器分配的节点，并且之后不能再被使用。
+    unsafe fn free(&self, ptr: *mut T);
+}
+
+impl<T, A: Allocator + Send + Sync> FreeNode<T> for A {
+    #[inline]
+    unsafe fn free(&self, ptr: *mut T) {
+        drop(unsafe { Node::from_raw_in(ptr, self) });
+    }
+}
+
+/// Reclaimed nodes kept for reuse by the writer.
+///
+/// 为写者复用而保留的已回收节点。
+pub(crate) struct Pool<T, A: Allocator> {
+    nodes: AllocVec<Node<T, A>, A>,
+    capacity: usize,
+}
+
+impl<T, A: Allocator> Pool<T, A> {
+    #[inline]
+    pub(crate) fn new(capacity: usize, alloc: A) -> Self {
+        Self {
+            nodes: AllocVec::with_capacity_in(capacity, alloc),
+            capacity,
+        }
+    }
+
+    /// Keep a reclaimed node for reuse if the pool has room, otherwise drop it.
+    ///
+    /// 如果回收池还有空间，则保留已回收的节点以供复用，否则将其 drop。
+    #[inline]
+    pub(crate) fn release(&mut self, node: Node<T, A>) {
+        if self.nodes.len() < self.capacity {
+            self.nodes.push(node);
+        }
+    }
+
+    /// Release the first `count` nodes of `reclaimed`, oldest first, until `budget` is
+    /// spent, and return how many were released. The rest stay in `reclaimed`.
+    ///
+    /// 按从旧到新的顺序释放 `reclaimed` 中的前 `count` 个节点，直到 `budget` 用完，
+    /// 并返回释放的数量。其余节点留在 `reclaimed` 中。
+    pub(crate) fn release_within(
+        &mut self,
+        reclaimed: &mut AllocVec<(usize, Node<T, A>), A>,
+        count: usize,
+        budget: Option<ReclaimBudget>,
+    ) -> usize {
+        let limit = match budget {
+            Some(ReclaimBudget::Items(items)) => count.min(items),
+            _ => count,
+        };
+        #[cfg(feature = "std")]
+        let deadline = match budget {
+            // A budget too large to represent never runs out.
+            // 大到无法表示的预算永远不会用完。
+            Some(ReclaimBudget::Time(time)) => std::time::Instant::now().checked_add(time),
+            _ => None,
+        };
+
+        let mut drain = reclaimed.drain(..count);
+        let mut released = 0;
+        while released < limit {
+            #[cfg(feature = "std")]
+            if released > 0
+                && deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline)
+            {
+                break;
+            }
+            let Some((_, node)) = drain.next() else {
+                break;
+            };
+            self.release(node);
+            released += 1;
+        }
+        drain.keep_rest();
+        released
+    }
+
+    #[inline]
+    pub(crate) fn take(&mut self) -> Option<T> {
+        self.nodes.pop().map(Node::into_inner)
+    }
+
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Build a node holding `f(recycled)`, reusing the allocation of a recycled node when
+    /// the pool has one.
+    ///
+    /// 构建一个持有 `f(recycled)` 的节点，回收池中有节点时复用其内存分配。
+    pub(crate) fn build_with<F>(&mut self, f: F, alloc: &A) -> Node<T, A>
+    where
+        A: Clone,
+        F: FnOnce(Option<T>) -> T,
+    {
+        let Some(node) = self.nodes.pop() else {
+            return Node::new_in(f(None), alloc.clone());
+        };
+
+        let (raw, alloc) = Node::into_raw_with_allocator(node);
+        // Safety: `raw` came from a `Node<T, A>` and is read exactly once. The allocation is
+        // then owned as uninitialized memory, so it is freed without dropping `T` again
+        // if `f` panics.
+        // 安全性：`raw` 来自 `Node<T, A>` 且只被读取一次。之后该分配作为未初始化内存被持有，
+        // 因此如果 `f` panic，它会被释放而不会再次 drop `T`。
+        let (old, slot) = unsafe {
+            (
+                raw.read(),
+                Node::from_raw_in(raw as *mut MaybeUninit<T>, alloc),
+            )
+        };
+        Node::write(slot, f(Some(old)))
+    }
+}
+
+/// Iterator over values reclaimed by a single collection, returned by
+/// [`RawWriter::drain_reclaimed`] of the built-in backends.
+///
+/// Values that are not consumed are released as by a regular collection when the
+/// iterator is dropped.
+///
+/// 遍历单次回收所回收的值的迭代器，由内置后端的 [`RawWriter::drain_reclaimed`] 返回。
+///
+/// 迭代器被 drop 时，未被消费的值会像常规回收一样被释放。
+pub struct Drain<'a, T, A: Allocator> {
+    drain: alloc_vec::Drain<'a, (usize, Node<T, A>), A>,
+    recycled: &'a mut Pool<T, A>,
+}
+
+impl<'a, T, A: Allocator> Drain<'a, T, A> {
+    /// Drain every node of `reclaimed`, releasing the ones left over into `recycled`.
+    ///
+    /// 取出 `reclaimed` 中的所有节点，剩余的节点会被释放到 `recycled` 中。
+    #[inline]
+    pub(crate) fn new(
+        reclaimed: &'a mut AllocVec<(usize, Node<T, A>), A>,
+        recycled: &'a mut Pool<T, A>,
+    ) -> Self {
+        Self {
+            drain: reclaimed.drain(..),
+            recycled,
+        }
+    }
+
+    /// Drain the first `count` nodes of `garbage`.
+    ///
+    /// 取出 `garbage` 中的前 `count` 个节点。
+    #[inline]
+    pub(crate) fn prefix(
+        garbage: &'a mut AllocVec<(usize, Node<T, A>), A>,
+        count: usize,
+        recycled: &'a mut Pool<T, A>,
+    ) -> Self {
+        Self {
+            drain: garbage.drain(..count),
+            recycled,
+        }
+    }
+}
+
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = (usize, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, T)> {
+        let (version, node) = self.drain.next()?;
+        Some((version, Node::into_inner(node)))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> Drop for Drain<'_, T, A> {
+    fn drop(&mut self) {
+        for (_, node) in self.drain.by_ref() {
+            self.recycled.release(node);
+        }
+    }
+}
+
+impl<T, A: Allocator> fmt::Debug for Drain<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Drain")
+            .field("remaining", &self.drain.len())
+            .finish()
+    }
+}
+
+// ============================================================================
+// Reader registry shared by every backend
+// 所有后端共用的读者注册表
+// ============================================================================
+
+/// A slot kept in a [`Registry`], marked in use while a reader holds it.
+///
+/// 保存在 [`Registry`] 中的槽，在被读者持有期间标记为使用中。
+pub(crate) trait RegistrySlot {
+    /// Whether a reader holds the slot. Slots are created with the flag cleared.
+    ///
+    /// 是否有读者持有该槽。槽在创建时该标志为清除状态。
+    fn in_use(&self) -> &AtomicBool;
+}
+
+/// Slots announced by the readers of a container, reused when their reader is dropped.
+///
+/// Slots are only freed with the registry, so a scan never has to prune them, and a
+/// reader takes the first free slot, so the slots of dropped readers are reused in order.
+///
+/// 容器的读者公布所用的槽，在其读者被 drop 后复用。
+///
+/// 槽只会随注册表一起释放，因此扫描永远不需要移除它们；读者获取第一个空闲槽，
+/// 因此被 drop 的读者的槽会被按顺序复用。
+pub(crate) struct Registry<S> {
+    pub(crate) slots: Mutex<Vec<Arc<S>>>,
+    in_use: AtomicUsize,
+}
+
+impl<S: RegistrySlot> Registry<S> {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            slots: Mutex::new(Vec::new()),
+            in_use: AtomicUsize::new(0),
+        }
+    }
+
+    /// Take the first free slot, resetting it with `reuse`, or add one built by `new`.
+    ///
+    /// Both closures run under the registry lock, so a concurrent scan either sees the
+    /// slot in its new state or finished before the closure ran.
+    ///
+    /// 获取第一个空闲槽并使用 `reuse` 重置它，或者添加一个由 `new` 构建的槽。
+    ///
+    /// 两个闭包都在注册表锁内运行，因此并发的扫描要么看到处于新状态的槽，
+    /// 要么在闭包运行之前就已完成。
+    pub(crate) fn register(
+        &self,
+        metrics: &observe::Metrics,
+        new: impl FnOnce() -> S,
+        reuse: impl FnOnce(&S),
+    ) -> Arc<S> {
+        let mut slots = self.slots.lock();
+        // Only registration sets the flag, and it holds the lock.
+        // 只有注册会设置该标志，并且注册持有锁。
+        let slot = match slots
+            .iter()
+            .find(|slot| !slot.in_use().load(Ordering::Acquire))
+        {
+            Some(slot) => {
+                reuse(slot);
+                Arc::clone(slot)
+            }
+            None => {
+                let slot = Arc::new(new());
+                slots.push(Arc::clone(&slot));
+                slot
+            }
+        };
+        slot.in_use().store(true, Ordering::Relaxed);
+        let in_use = self.in_use.fetch_add(1, Ordering::Relaxed) + 1;
+        observe::reader_registered(metrics, in_use);
+        slot
+    }
+
+    /// Free `slot` for the next reader. The reader must no longer announce anything in it.
+    ///
+    /// 将 `slot` 留给下一个读者。读者必须不再通过它公布任何内容。
+    #[inline]
+    pub(crate) fn release(&self, metrics: &observe::Metrics, slot: &S) {
+        slot.in_use().store(false, Ordering::Release);
+        self.in_use.fetch_sub(1, Ordering::Relaxed);
+        observe::reader_deregistered(metrics);
+    }
+
+    /// Number of slots held by a live reader.
+    ///
+    /// 被存活读者持有的槽数量。
+    #[inline]
+    pub(crate) fn in_use(&self) -> usize {
+        self.in_use.load(Ordering::Relaxed)
+    }
+
+    /// Count the allocated slots and those in use.
+    ///
+    /// 统计已分配的槽和使用中的槽。
+    pub(crate) fn reader_slots(&self) -> ReaderSlots {
+        let allocated = self.slots.lock().len();
+        ReaderSlots {
+            allocated,
+            in_use: self.in_use(),
+        }
+    }
+}
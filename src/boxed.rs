@@ -0,0 +1,361 @@
+//! Swap container for unsized values such as `str`, slices and trait objects.
+//!
+//! 用于 `str`、切片和 trait 对象等非固定大小值的交换容器。
+
+use alloc::boxed::Box;
+use core::fmt;
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+use crate::{LocalReader, ReadGuard, SmrReader, SmrSwap};
+
+/// A single-writer, multi-reader swap container for unsized values.
+///
+/// `SmrSwapBox<T>` stores a `Box<T>` where `T` may be `str`, `[U]` or `dyn Trait`.
+/// Its guards resolve the box once when loading, so dereferencing a
+/// [`BoxReadGuard<str>`](BoxReadGuard) or `BoxReadGuard<dyn Trait>` reaches the value
+/// in a single hop.
+///
+/// 用于非固定大小值的单写多读交换容器。
+///
+/// `SmrSwapBox<T>` 存储一个 `Box<T>`，其中 `T` 可以是 `str`、`[U]` 或 `dyn Trait`。
+/// 它的守卫在加载时解析一次 box，因此解引用 [`BoxReadGuard<str>`](BoxReadGuard)
+/// 或 `BoxReadGuard<dyn Trait>` 只需一跳即可访问到值。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::SmrSwapBox;
+///
+/// let mut swap: SmrSwapBox<str> = SmrSwapBox::new("hello".into());
+/// let local = swap.local();
+///
+/// swap.store("world".into());
+///
+/// let guard = local.load();
+/// assert_eq!(&*guard, "world");
+/// assert_eq!(guard.len(), 5);
+/// ```
+pub struct SmrSwapBox<T: ?Sized + 'static> {
+    swap: SmrSwap<Box<T>>,
+}
+
+/// A handle for creating `LocalBoxReader`s that can be shared across threads.
+///
+/// 可以跨线程共享的用于创建 `LocalBoxReader` 的句柄。
+pub struct SmrBoxReader<T: ?Sized + 'static> {
+    inner: SmrReader<Box<T>>,
+}
+
+/// Thread-local reader handle for an [`SmrSwapBox`], not Sync.
+///
+/// [`SmrSwapBox`] 的线程本地读取句柄，不是 Sync。
+pub struct LocalBoxReader<T: ?Sized + 'static> {
+    inner: LocalReader<Box<T>>,
+}
+
+/// RAII guard for reading unsized values.
+///
+/// Dereferences directly to `T`. The value is protected until the guard is dropped.
+///
+/// 用于读取非固定大小值的 RAII 守卫。
+///
+/// 直接解引用为 `T`。在守卫被 drop 之前，值是受保护的。
+pub struct BoxReadGuard<'a, T: ?Sized + 'static> {
+    inner: ReadGuard<'a, Box<T>>,
+    ptr: NonNull<T>,
+}
+
+impl<'a, T: ?Sized + 'static> BoxReadGuard<'a, T> {
+    #[inline]
+    fn new(inner: ReadGuard<'a, Box<T>>) -> Self {
+        let ptr = NonNull::from(&**inner);
+        Self { inner, ptr }
+    }
+
+    /// Get the version that this guard is pinned to.
+    ///
+    /// 获取此守卫被 pin 到的版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.inner.version()
+    }
+}
+
+impl<T: ?Sized + 'static> Deref for BoxReadGuard<'_, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        // Safety: `ptr` points into the box protected by `inner`, which is pinned
+        // for as long as this guard is alive.
+        // 安全性：`ptr` 指向由 `inner` 保护的 box，只要此守卫存活它就保持 pin。
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: ?Sized + 'static> Clone for BoxReadGuard<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<T: ?Sized + 'static> AsRef<T> for BoxReadGuard<'_, T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: ?Sized + fmt::Debug + 'static> fmt::Debug for BoxReadGuard<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxReadGuard")
+            .field("value", &self.deref())
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+// ============================================================================
+// SmrSwapBox implementation
+// ============================================================================
+
+impl<T: ?Sized + 'static> SmrSwapBox<T> {
+    /// Create a new container with the given boxed initial value.
+    ///
+    /// 使用给定的 box 初始值创建新容器。
+    #[inline]
+    pub fn new(initial: Box<T>) -> Self {
+        Self {
+            swap: SmrSwap::new(initial),
+        }
+    }
+
+    /// Create a new thread-local reader for this container.
+    ///
+    /// 为此容器创建一个新的线程本地读取者。
+    #[inline]
+    pub fn local(&self) -> LocalBoxReader<T> {
+        LocalBoxReader {
+            inner: self.swap.local(),
+        }
+    }
+
+    /// Create a new `SmrBoxReader` that can be shared across threads.
+    ///
+    /// 创建一个新的 `SmrBoxReader`，可以在线程之间共享。
+    #[inline]
+    pub fn reader(&self) -> SmrBoxReader<T> {
+        SmrBoxReader {
+            inner: self.swap.reader(),
+        }
+    }
+
+    /// Store a new boxed value, making it visible to readers.
+    ///
+    /// 存储新的 box 值，使其对读者可见。
+    #[inline]
+    pub fn store(&mut self, new_value: Box<T>) {
+        self.swap.store(new_value);
+    }
+
+    /// Get a reference to the current value (writer-only, no pinning required).
+    ///
+    /// 获取当前值的引用（仅写者可用，无需 pin）。
+    #[inline]
+    pub fn get(&self) -> &T {
+        self.swap.get()
+    }
+
+    /// Update the value using a closure.
+    ///
+    /// 使用闭包更新值。
+    #[inline]
+    pub fn update<F>(&mut self, f: F)
+    where
+        F: FnOnce(&T) -> Box<T>,
+    {
+        self.swap.update(|current| f(current));
+    }
+
+    /// Get the current global version.
+    ///
+    /// 获取当前全局版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.swap.version()
+    }
+
+    /// Get the number of retired objects waiting for garbage collection.
+    ///
+    /// 获取等待垃圾回收的已退休对象数量。
+    #[inline]
+    pub fn garbage_count(&self) -> usize {
+        self.swap.garbage_count()
+    }
+
+    /// Get a reference to the previously stored value, if any.
+    ///
+    /// 获取上一个存储值的引用（如果存在）。
+    #[inline]
+    pub fn previous(&self) -> Option<&T> {
+        self.swap.previous().map(|boxed| &**boxed)
+    }
+
+    /// Manually trigger garbage collection.
+    ///
+    /// 手动触发垃圾回收。
+    #[inline]
+    pub fn collect(&mut self) {
+        self.swap.collect();
+    }
+
+    /// Read the current value with RAII guard.
+    ///
+    /// 使用 RAII 守卫读取当前值。
+    #[inline]
+    pub fn load(&self) -> BoxReadGuard<'_, T> {
+        BoxReadGuard::new(self.swap.load())
+    }
+}
+
+// ============================================================================
+// Reader implementations
+// ============================================================================
+
+impl<T: ?Sized + 'static> LocalBoxReader<T> {
+    /// Read the current value with RAII guard.
+    ///
+    /// 使用 RAII 守卫读取当前值。
+    #[inline]
+    pub fn load(&self) -> BoxReadGuard<'_, T> {
+        BoxReadGuard::new(self.inner.load())
+    }
+
+    /// Check if this reader is currently pinned.
+    ///
+    /// 检查此读者当前是否被 pin。
+    #[inline]
+    pub fn is_pinned(&self) -> bool {
+        self.inner.is_pinned()
+    }
+
+    /// Get the current global version.
+    ///
+    /// 获取当前全局版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.inner.version()
+    }
+
+    /// Apply a closure function to the current value and transform the result.
+    ///
+    /// 对当前值应用闭包函数并转换结果。
+    #[inline]
+    pub fn map<F, U>(&self, f: F) -> U
+    where
+        F: FnOnce(&T) -> U,
+    {
+        f(&self.load())
+    }
+
+    /// Create a new `SmrBoxReader` from this `LocalBoxReader`.
+    ///
+    /// 从此 `LocalBoxReader` 创建一个新的 `SmrBoxReader`。
+    #[inline]
+    pub fn share(&self) -> SmrBoxReader<T> {
+        SmrBoxReader {
+            inner: self.inner.share(),
+        }
+    }
+}
+
+impl<T: ?Sized + 'static> SmrBoxReader<T> {
+    /// Create a new `LocalBoxReader` for the current thread.
+    ///
+    /// 为当前线程创建一个新的 `LocalBoxReader`。
+    #[inline]
+    pub fn local(&self) -> LocalBoxReader<T> {
+        LocalBoxReader {
+            inner: self.inner.local(),
+        }
+    }
+}
+
+impl<T: ?Sized + 'static> Clone for LocalBoxReader<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized + 'static> Clone for SmrBoxReader<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized + 'static> fmt::Debug for LocalBoxReader<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalBoxReader")
+            .field("is_pinned", &self.is_pinned())
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+impl<T: ?Sized + 'static> fmt::Debug for SmrBoxReader<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmrBoxReader").finish()
+    }
+}
+
+// ============================================================================
+// Standard Trait Implementations
+// 标准 trait 实现
+// ============================================================================
+
+impl<T: ?Sized + 'static> Default for SmrSwapBox<T>
+where
+    Box<T>: Default,
+{
+    /// Create a new SmrSwapBox with the default boxed value.
+    ///
+    /// 使用默认的 box 值创建一个新的 SmrSwapBox。
+    #[inline]
+    fn default() -> Self {
+        Self::new(Box::default())
+    }
+}
+
+impl<T: ?Sized + 'static> From<Box<T>> for SmrSwapBox<T> {
+    /// Create a new SmrSwapBox from a boxed value.
+    ///
+    /// 从一个 box 值创建一个新的 SmrSwapBox。
+    #[inline]
+    fn from(value: Box<T>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug + 'static> fmt::Debug for SmrSwapBox<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmrSwapBox")
+            .field("value", &self.get())
+            .field("version", &self.version())
+            .field("garbage_count", &self.garbage_count())
+            .finish()
+    }
+}
@@ -0,0 +1,1273 @@
+//! Version-based reclamation engine behind [`SmrSwap`](crate::SmrSwap).
+//!
+//! The writer publishes values through an atomic pointer and bumps a global version on
+//! every store. Readers announce the version they pinned in a per-reader slot, and the
+//! writer only reclaims retired values that are older than every announced version.
+//!
+//! Keeping the engine in this crate gives `SmrSwap` direct access to retired values,
+//! which it needs to hand them back to the writer instead of dropping them.
+//!
+//! [`SmrSwap`](crate::SmrSwap) 背后的基于版本的回收引擎。
+//!
+//! 写者通过原子指针发布值，并在每次存储时递增全局版本。读者在各自的槽中公布它们 pin 的版本，
+//! 写者只回收比所有已公布版本都旧的已退休值。
+//!
+//! 将引擎放在本 crate 中，使 `SmrSwap` 可以直接访问已退休的值，
+//! 从而可以将它们交还给写者，而不是直接 drop。
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use allocator_api2::alloc::{Allocator, Global};
+use allocator_api2::vec::Vec as AllocVec;
+
+use crate::Strategy;
+use crate::backend::{
+    CapacityError, Collected, Config, Drain, FreeNode, Node, Pool, RawGuard, RawReader, RawShared,
+    RawWriter, ReaderSlots, ReclaimBudget, Registry, RegistrySlot,
+};
+use crate::checks;
+use crate::observe;
+use crate::qsbr::QsbrReader;
+use crate::shim::{
+    Arc, AtomicBool, AtomicPtr, AtomicUsize, Box, Cell, Ordering, heavy_barrier, light_barrier,
+};
+use crate::strategy::Adaptive;
+
+/// Version stored in a reader slot while the reader is not pinned.
+///
+/// 读者未被 pin 时存储在读者槽中的版本。
+pub(crate) const INACTIVE_VERSION: usize = usize::MAX;
+
+/// Values of [`Shared::mode`], the algorithm readers of an adaptive container must use.
+///
+/// [`Shared::mode`] 的取值，即自适应容器的读者必须使用的算法。
+const MODE_WRITE_PREFERRED: usize = 0;
+const MODE_READ_PREFERRED: usize = 1;
+
+/// Per-reader slot announcing the version the reader is pinned to.
+///
+/// 公布读者被 pin 到的版本的读者槽。
+#[repr(align(64))]
+pub(crate) struct ReaderSlot {
+    pub(crate) active_version: AtomicUsize,
+    /// Number of loads, only counted by readers of adaptive containers. The count carries
+    /// over to the next reader of the slot.
+    ///
+    /// 加载次数，仅由自适应容器的读者统计。该计数会延续给该槽的下一个读者。
+    loads: AtomicUsize,
+    in_use: AtomicBool,
+}
+
+impl ReaderSlot {
+    #[inline]
+    pub(crate) fn new(active_version: usize) -> Self {
+        Self {
+            active_version: AtomicUsize::new(active_version),
+            loads: AtomicUsize::new(0),
+            in_use: AtomicBool::new(false),
+        }
+    }
+}
+
+impl RegistrySlot for ReaderSlot {
+    #[inline]
+    fn in_use(&self) -> &AtomicBool {
+        &self.in_use
+    }
+}
+
+/// Slot shared by every reader assigned to it, used instead of per-reader slots when the
+/// container is sharded.
+///
+/// Pinned readers join one of two generations. A generation keeps the version announced by
+/// the reader that took it from zero for as long as its pins overlap, so once it lags behind
+/// the current version, new readers move on to the other generation and it drains.
+///
+/// 分配给它的所有读者共享的槽，在容器分片时代替每个读者各自的槽。
+///
+/// 被 pin 的读者加入两代中的一代。只要一代的 pin 相互重叠，它就会保留将其从零变为非零的读者
+/// 公布的版本，因此当它落后于当前版本时，新的读者会转到另一代，使这一代逐渐清空。
+#[repr(align(64))]
+pub(crate) struct Shard {
+    generations: [ShardGeneration; 2],
+    /// Index of the generation new readers join.
+    ///
+    /// 新读者加入的代的索引。
+    current: AtomicUsize,
+    /// Number of loads, only counted by readers of adaptive containers. Updates may be
+    /// lost when readers race, which only makes the count approximate.
+    ///
+    /// 加载次数，仅由自适应容器的读者统计。读者竞争时更新可能丢失，这只会使计数变为近似值。
+    loads: AtomicUsize,
+}
+
+/// Readers pinned on one generation of a [`Shard`].
+///
+/// `pins` counts the readers pinned on the generation, and `version` is the version announced
+/// by the reader that took it from zero. Versions only grow, so while `pins` stays non-zero
+/// the announced version is at most the version of every value its readers load.
+///
+/// [`Shard`] 的一代中被 pin 的读者。
+///
+/// `pins` 统计在这一代上被 pin 的读者数量，`version` 是将其从零变为非零的读者公布的版本。
+/// 版本只会增长，因此只要 `pins` 保持非零，公布的版本就不大于其读者加载的任何值的版本。
+struct ShardGeneration {
+    pins: AtomicUsize,
+    version: AtomicUsize,
+}
+
+impl Shard {
+    fn new() -> Self {
+        Self {
+            generations: core::array::from_fn(|_| ShardGeneration {
+                pins: AtomicUsize::new(0),
+                version: AtomicUsize::new(0),
+            }),
+            current: AtomicUsize::new(0),
+            loads: AtomicUsize::new(0),
+        }
+    }
+
+    /// Join a generation, announcing `version` if this reader takes it from zero, and return
+    /// its index.
+    ///
+    /// 加入一代，如果此读者将其从零变为非零则公布 `version`，并返回该代的索引。
+    #[inline(always)]
+    fn pin(&self, version: usize) -> usize {
+        let mut index = self.current.load(Ordering::Relaxed);
+        let generation = &self.generations[index];
+        // Overlapping pins would keep a lagging generation pinned at its version forever, so
+        // readers switch to the other generation once it drained. Which generation a reader
+        // joins never affects safety: each one announces its own version.
+        // 相互重叠的 pin 会使落后的一代永远停留在其版本上，因此另一代清空后读者会切换过去。
+        // 读者加入哪一代从不影响安全性：每一代都公布自己的版本。
+        if generation.pins.load(Ordering::Relaxed) != 0
+            && generation.version.load(Ordering::Relaxed) < version
+        {
+            let other = index ^ 1;
+            index = if self.generations[other].pins.load(Ordering::Acquire) == 0 {
+                match self.current.compare_exchange(
+                    index,
+                    other,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => other,
+                    Err(current) => current,
+                }
+            } else {
+                // A reader that already joined the other generation switched first, and its
+                // increment released the switch.
+                // 已经加入另一代的读者先完成了切换，其递增操作释放了该切换。
+                self.current.load(Ordering::Relaxed)
+            };
+        }
+
+        let generation = &self.generations[index];
+        // Only the reader that takes the generation from zero announces. A reader joining a
+        // pinned generation reads values at least as new as the announced version, because
+        // its increment reads from the announcing reader's increment.
+        // 只有将这一代从零变为非零的读者公布版本。加入已被 pin 的一代的读者读取的值
+        // 至少与已公布的版本一样新，因为它的递增读取自公布者的递增。
+        if generation.pins.fetch_add(1, Ordering::AcqRel) == 0 {
+            generation.version.store(version, Ordering::Release);
+        }
+        index
+    }
+
+    /// Leave the generation joined by [`pin`](Self::pin).
+    ///
+    /// 离开 [`pin`](Self::pin) 加入的代。
+    #[inline(always)]
+    fn unpin(&self, index: usize) {
+        self.generations[index].pins.fetch_sub(1, Ordering::Release);
+    }
+
+    /// Number of readers pinned on the shard.
+    ///
+    /// 在该分片上被 pin 的读者数量。
+    fn pins(&self) -> usize {
+        self.generations
+            .iter()
+            .map(|generation| generation.pins.load(Ordering::Acquire))
+            .sum()
+    }
+
+    /// Oldest version announced by a pinned generation.
+    ///
+    /// 被 pin 的代所公布的最旧版本。
+    fn min_version(&self) -> Option<usize> {
+        self.generations
+            .iter()
+            .filter(|generation| generation.pins.load(Ordering::Acquire) != 0)
+            .map(|generation| generation.version.load(Ordering::Acquire))
+            .min()
+    }
+}
+
+/// Where a reader announces its version.
+///
+/// 读者公布其版本的位置。
+enum Registration {
+    Slot(Arc<ReaderSlot>),
+    Fixed(usize),
+    Shard(usize),
+}
+
+/// Announcement target of a reader, resolved from its [`Registration`].
+///
+/// 读者的公布目标，由其 [`Registration`] 解析得到。
+enum Announcement<'a> {
+    Slot(&'a ReaderSlot),
+    Shard(&'a Shard),
+}
+
+/// State shared between the writer and all readers.
+///
+/// 写者与所有读者共享的状态。
+#[repr(align(64))]
+pub(crate) struct Shared<T: 'static> {
+    pub(crate) global_version: AtomicUsize,
+    pub(crate) min_active_version: AtomicUsize,
+    pub(crate) ptr: AtomicPtr<T>,
+    pub(crate) readers: Registry<ReaderSlot>,
+    pub(crate) strategy: Strategy,
+    /// Algorithm of an adaptive container, written by the writer during collections.
+    ///
+    /// 自适应容器的算法，由写者在回收期间写入。
+    mode: AtomicUsize,
+    /// Slots of a container with bounded readers, allocated up front, empty otherwise.
+    /// The first one belongs to the writer's own reader.
+    ///
+    /// 读者数量有上限的容器预先分配的槽，否则为空。第一个槽属于写者自己的读者。
+    fixed: Box<[ReaderSlot]>,
+    /// Shards of a sharded container, empty otherwise.
+    ///
+    /// 分片容器的分片，否则为空。
+    shards: Box<[Shard]>,
+    /// Shard assigned to the next reader, taken round-robin.
+    ///
+    /// 分配给下一个读者的分片，轮流选取。
+    next_shard: AtomicUsize,
+    /// Number of live readers assigned to a shard.
+    ///
+    /// 分配到分片的存活读者数量。
+    sharded_readers: AtomicUsize,
+    pub(crate) metrics: observe::Metrics,
+    alloc: Box<dyn FreeNode<T>>,
+    // Readers hand out `&T` on any thread and the last handle may drop the value.
+    // 读者会在任意线程上交出 `&T`，最后一个句柄可能会 drop 该值。
+    _marker: PhantomData<T>,
+}
+
+impl<T: 'static> Drop for Shared<T> {
+    fn drop(&mut self) {
+        // Drop the current value held by ptr to avoid leaking it.
+        // Drop ptr 持有的当前值，以避免泄漏。
+        let ptr = self.ptr.load(Ordering::Acquire);
+        if !ptr.is_null() {
+            unsafe {
+                self.alloc.free(ptr);
+            }
+        }
+    }
+}
+
+impl<T: 'static> Shared<T> {
+    /// Whether the container was built with bounded readers.
+    ///
+    /// 容器是否以有上限的读者构建。
+    #[inline]
+    pub(crate) fn is_bounded(&self) -> bool {
+        !self.fixed.is_empty()
+    }
+
+    /// Claim a free fixed slot. Claiming is bounded by the number of slots and never
+    /// allocates.
+    ///
+    /// 获取一个空闲的固定槽。获取槽的次数受槽数量限制，且从不分配内存。
+    pub(crate) fn claim_fixed(&self) -> Result<usize, CapacityError> {
+        let index = self
+            .fixed
+            .iter()
+            .position(|slot| {
+                slot.in_use
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            })
+            .ok_or(CapacityError {
+                max_readers: self.fixed.len() - 1,
+            })?;
+        observe::reader_registered(&self.metrics, self.live_count());
+        Ok(index)
+    }
+
+    /// Fixed slot at `index`.
+    ///
+    /// 位于 `index` 的固定槽。
+    #[inline(always)]
+    pub(crate) fn fixed_slot(&self, index: usize) -> &ReaderSlot {
+        &self.fixed[index]
+    }
+
+    /// Number of fixed slots held by a reader.
+    ///
+    /// 被读者持有的固定槽数量。
+    fn fixed_in_use(&self) -> usize {
+        self.fixed
+            .iter()
+            .filter(|slot| slot.in_use.load(Ordering::Relaxed))
+            .count()
+    }
+
+    /// Number of live readers, whether registered, holding a fixed slot or sharded.
+    ///
+    /// 存活读者的数量，无论是已注册的、持有固定槽的还是分片的。
+    fn live_count(&self) -> usize {
+        self.readers.in_use() + self.fixed_in_use() + self.sharded_readers.load(Ordering::Relaxed)
+    }
+
+    /// Free a fixed slot; it must already be inactive.
+    ///
+    /// 释放一个固定槽；它必须已经处于非活动状态。
+    #[inline]
+    pub(crate) fn release_fixed(&self, index: usize) {
+        self.fixed[index].in_use.store(false, Ordering::Release);
+        observe::reader_deregistered(&self.metrics);
+    }
+}
+
+/// Writer side of the [`Versioned`](crate::backend::Versioned) backend.
+///
+/// [`Versioned`](crate::backend::Versioned) 后端的写者端。
+pub struct VersionedWriter<T: 'static, A: Allocator = Global> {
+    shared: Arc<Shared<T>>,
+    /// Retired values tagged with the last version in which they were current, oldest first.
+    ///
+    /// 已退休的值，以它们作为当前值的最后版本标记，最旧的在前。
+    garbage: AllocVec<(usize, Node<T, A>), A>,
+    recycled: Pool<T, A>,
+    alloc: A,
+    auto_reclaim_threshold: Option<usize>,
+    auto_reclaim_budget: Option<ReclaimBudget>,
+    adaptive: Option<Adaptive>,
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static> VersionedWriter<T, A> {
+    pub(crate) fn new(data: T, config: Config, alloc: A) -> Self {
+        let (ptr, _) = Node::into_raw_with_allocator(Node::new_in(data, alloc.clone()));
+        // Bounded readers take precedence over shards; the extra slot is the writer's own.
+        // 有上限的读者优先于分片；多出的一个槽属于写者自己。
+        let (fixed, shards) = match config.max_readers {
+            Some(max_readers) => (max_readers + 1, 0),
+            None => (0, config.reader_shards),
+        };
+        let shared = Arc::new(Shared {
+            global_version: AtomicUsize::new(config.initial_version),
+            min_active_version: AtomicUsize::new(0),
+            ptr: AtomicPtr::new(ptr),
+            readers: Registry::new(),
+            strategy: config.strategy,
+            mode: AtomicUsize::new(MODE_WRITE_PREFERRED),
+            fixed: (0..fixed)
+                .map(|_| ReaderSlot::new(INACTIVE_VERSION))
+                .collect(),
+            shards: (0..shards).map(|_| Shard::new()).collect(),
+            next_shard: AtomicUsize::new(0),
+            sharded_readers: AtomicUsize::new(0),
+            metrics: observe::Metrics::new(config.name.as_ref(), config.initial_version),
+            alloc: Box::new(alloc.clone()),
+            _marker: PhantomData,
+        });
+
+        Self {
+            shared,
+            garbage: AllocVec::new_in(alloc.clone()),
+            recycled: Pool::new(config.recycle_capacity, alloc.clone()),
+            alloc,
+            auto_reclaim_threshold: config.auto_reclaim_threshold,
+            auto_reclaim_budget: config.auto_reclaim_budget,
+            adaptive: (config.strategy == Strategy::Adaptive).then(Adaptive::new),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn store(&mut self, data: T) {
+        self.publish(Node::new_in(data, self.alloc.clone()));
+    }
+
+    /// Store a value built from a recycled one, reusing its allocation when possible.
+    ///
+    /// 从回收的值构建并存储新值，尽可能复用其内存分配。
+    pub(crate) fn store_with<F>(&mut self, f: F)
+    where
+        F: FnOnce(Option<T>) -> T,
+    {
+        let node = self.recycled.build_with(f, &self.alloc);
+        self.publish(node);
+    }
+
+    fn publish(&mut self, node: Node<T, A>) {
+        let (new_ptr, _) = Node::into_raw_with_allocator(node);
+        let old_ptr = self.shared.ptr.swap(new_ptr, Ordering::Release);
+
+        // Increment global version.
+        // The old value belongs to the previous version (the one before this increment).
+        // 增加全局版本。
+        // 旧值属于前一个版本（此次增加之前的那个）。
+        let old_version = self.shared.global_version.fetch_add(1, Ordering::AcqRel);
+
+        if !old_ptr.is_null() {
+            // Safe because we just swapped it out and we own the writer
+            unsafe {
+                self.garbage
+                    .push((old_version, Node::from_raw_in(old_ptr, self.alloc.clone())));
+            }
+        }
+
+        if let Some(adaptive) = &mut self.adaptive {
+            adaptive.stored();
+        }
+
+        observe::stored(
+            &self.shared.metrics,
+            old_version,
+            old_version + 1,
+            self.garbage.len(),
+        );
+
+        // Auto-reclaim
+        if let Some(threshold) = self.auto_reclaim_threshold
+            && self.garbage.len() > threshold
+        {
+            self.collect_with(true, self.auto_reclaim_budget);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn take_recycled(&mut self) -> Option<T> {
+        self.recycled.take()
+    }
+
+    #[inline]
+    pub(crate) fn collect(&mut self) {
+        self.collect_with(false, None);
+    }
+
+    #[inline]
+    pub(crate) fn collect_budget(&mut self, budget: ReclaimBudget) -> Collected {
+        self.collect_with(false, Some(budget))
+    }
+
+    fn collect_with(&mut self, automatic: bool, budget: Option<ReclaimBudget>) -> Collected {
+        let _span = observe::collect_span(automatic);
+        let reclaimable = self.reclaimable();
+
+        let timer = observe::Timer::start();
+        let reclaimed = self
+            .recycled
+            .release_within(&mut self.garbage, reclaimable, budget);
+        observe::collected(&self.shared.metrics, reclaimed, self.garbage.len(), timer);
+        Collected {
+            reclaimed,
+            pending: reclaimable - reclaimed,
+            remaining: self.garbage.len(),
+        }
+    }
+
+    /// Run a collection and move every reclaimed value into `sink`, bypassing the recycle pool.
+    ///
+    /// 执行一次回收，并将所有已回收的值移入 `sink`，绕过回收池。
+    pub(crate) fn collect_into<E: Extend<T>>(&mut self, sink: &mut E) {
+        let _span = observe::collect_span(false);
+        let reclaimable = self.reclaimable();
+
+        let timer = observe::Timer::start();
+        sink.extend(
+            self.garbage
+                .drain(..reclaimable)
+                .map(|(_, node)| Node::into_inner(node)),
+        );
+        observe::collected(&self.shared.metrics, reclaimable, self.garbage.len(), timer);
+    }
+
+    /// Run a collection and return an iterator over the reclaimed values and their versions.
+    ///
+    /// 执行一次回收，并返回一个遍历已回收值及其版本的迭代器。
+    pub(crate) fn drain_reclaimed(&mut self) -> Drain<'_, T, A> {
+        let _span = observe::collect_span(false);
+        let reclaimable = self.reclaimable();
+        // The values are dropped by the caller, so no drop time is attributed to them.
+        // 这些值由调用者 drop，因此不计入 drop 时间。
+        observe::collected(
+            &self.shared.metrics,
+            reclaimable,
+            self.garbage.len() - reclaimable,
+            observe::Timer::start(),
+        );
+        Drain::prefix(&mut self.garbage, reclaimable, &mut self.recycled)
+    }
+
+    /// Scan the readers and count the oldest retired values that are no longer reachable.
+    ///
+    /// 扫描读者，并统计不再可访问的最旧的已退休值的数量。
+    #[inline]
+    fn reclaimable(&mut self) -> usize {
+        let threshold = self.reclaim_threshold();
+        // Retired versions increase monotonically, so the reclaimable values form a prefix.
+        // 退休版本单调递增，因此可回收的值构成一个前缀。
+        self.garbage
+            .partition_point(|(version, _)| *version < threshold)
+    }
+}
+
+impl<T: 'static, A: Allocator> VersionedWriter<T, A> {
+    #[inline]
+    pub(crate) fn local(&self) -> VersionedReader<T> {
+        VersionedReader::new(self.shared.clone())
+    }
+
+    #[inline]
+    pub(crate) fn try_local(&self) -> Result<VersionedReader<T>, CapacityError> {
+        VersionedReader::try_new(self.shared.clone())
+    }
+
+    #[inline]
+    pub(crate) fn shared(&self) -> VersionedShared<T> {
+        VersionedShared {
+            shared: self.shared.clone(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn qsbr(&self) -> QsbrReader<T> {
+        QsbrReader::new(self.shared.clone())
+    }
+
+    #[inline]
+    pub(crate) fn try_qsbr(&self) -> Result<QsbrReader<T>, CapacityError> {
+        QsbrReader::try_new(self.shared.clone())
+    }
+
+    #[inline]
+    pub(crate) fn previous(&self) -> Option<&T> {
+        self.garbage.last().map(|(_, node)| &**node)
+    }
+
+    #[inline]
+    pub(crate) fn get(&self) -> &T {
+        // Safety: We own the writer, and the current pointer is always valid.
+        // 安全性：我们拥有写者，当前指针始终有效。
+        unsafe { &*self.shared.ptr.load(Ordering::Acquire) }
+    }
+
+    #[inline]
+    pub(crate) fn version(&self) -> usize {
+        self.shared.global_version.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub(crate) fn strategy(&self) -> Strategy {
+        self.shared.strategy
+    }
+
+    /// The algorithm in use: the strategy itself, or the current choice of an adaptive one.
+    ///
+    /// 正在使用的算法：策略本身，或自适应策略的当前选择。
+    #[inline]
+    pub(crate) fn active_strategy(&self) -> Strategy {
+        match &self.adaptive {
+            Some(adaptive) => adaptive.current(),
+            None => self.shared.strategy,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn garbage_count(&self) -> usize {
+        self.garbage.len()
+    }
+
+    #[inline]
+    pub(crate) fn recycled_count(&self) -> usize {
+        self.recycled.len()
+    }
+
+    /// Count the registered readers that are still alive, and how many of them are pinned.
+    ///
+    /// Readers holding a fixed slot or assigned to a shard are counted too. Each pins its
+    /// shard once, however many guards it holds, so the shard pin counts add up to the
+    /// pinned sharded readers.
+    ///
+    /// 统计仍然存活的已注册读者数量，以及其中被 pin 的数量。
+    ///
+    /// 持有固定槽或分配到分片的读者也会被统计。无论持有多少守卫，每个读者只 pin 其分片一次，
+    /// 因此分片的 pin 计数之和即为被 pin 的分片读者数量。
+    pub(crate) fn live_readers(&self) -> (usize, usize) {
+        let readers = self.shared.readers.slots.lock();
+        let pinned = readers
+            .iter()
+            .filter(|slot| slot.active_version.load(Ordering::Acquire) != INACTIVE_VERSION)
+            .count();
+        drop(readers);
+        let sharded_pinned: usize = self.shared.shards.iter().map(Shard::pins).sum();
+        let fixed_pinned = self
+            .shared
+            .fixed
+            .iter()
+            .filter(|slot| slot.active_version.load(Ordering::Acquire) != INACTIVE_VERSION)
+            .count();
+        (
+            self.shared.live_count(),
+            pinned + fixed_pinned + sharded_pinned,
+        )
+    }
+
+    /// Count the reader slots, including fixed slots and shards.
+    ///
+    /// Shards are shared by every reader assigned to them, so they always count as in use.
+    ///
+    /// 统计读者槽，包括固定槽和分片。
+    ///
+    /// 分片由分配到其上的所有读者共享，因此始终计为使用中。
+    pub(crate) fn reader_slots(&self) -> ReaderSlots {
+        let registered = self.shared.readers.reader_slots();
+        let fixed = self.shared.fixed_in_use();
+        let shards = self.shared.shards.len();
+        ReaderSlots {
+            allocated: registered.allocated + self.shared.fixed.len() + shards,
+            in_use: registered.in_use + fixed + shards,
+        }
+    }
+
+    /// Scan the readers and publish the version below which retired values are unreachable.
+    ///
+    /// 扫描读者并发布一个版本，低于该版本的已退休值不可再被访问。
+    fn reclaim_threshold(&mut self) -> usize {
+        let current_version = self.shared.global_version.load(Ordering::Acquire);
+
+        // Safety limit ensures we never reclaim the most recent retired value (previous).
+        // The most recent retired value has version = current_version - 1.
+        // With safety_limit = current_version - 2, we only reclaim versions < current_version - 2,
+        // so the previous value (version = current_version - 1) is always preserved.
+        // 安全限制确保我们永远不会回收最近退休的值（previous）。
+        // 最近退休的值的版本 = current_version - 1。
+        // 使用 safety_limit = current_version - 2，我们只回收版本 < current_version - 2 的，
+        // 因此上一个值（版本 = current_version - 1）始终被保留。
+        let safety_limit = current_version.saturating_sub(2);
+
+        let mut min_active = current_version;
+
+        // Force memory visibility of any preceding stores and serialize reader streams.
+        // This ensures we see any active readers that have completed their light_barrier.
+        heavy_barrier(self.active_strategy());
+
+        // Free slots are inactive, so every registered slot can be scanned as it is.
+        // 空闲槽处于非活动状态，因此每个已注册的槽都可以直接扫描。
+        let shared_readers = self.shared.readers.slots.lock();
+
+        let mut pinned = 0;
+        let mut loads = 0usize;
+        for arc_slot in shared_readers.iter() {
+            let version = arc_slot.active_version.load(Ordering::Acquire);
+            if version != INACTIVE_VERSION {
+                min_active = min_active.min(version);
+                pinned += 1;
+            }
+            loads = loads.wrapping_add(arc_slot.loads.load(Ordering::Relaxed));
+        }
+
+        for slot in self.shared.fixed.iter() {
+            let version = slot.active_version.load(Ordering::Acquire);
+            if version != INACTIVE_VERSION {
+                min_active = min_active.min(version);
+                pinned += 1;
+            }
+            loads = loads.wrapping_add(slot.loads.load(Ordering::Relaxed));
+        }
+
+        // A pinned shard protects everything from its announced version on.
+        // 被 pin 的分片保护从其公布的版本开始的所有值。
+        for shard in self.shared.shards.iter() {
+            if let Some(version) = shard.min_version() {
+                min_active = min_active.min(version);
+                pinned += 1;
+            }
+            loads = loads.wrapping_add(shard.loads.load(Ordering::Relaxed));
+        }
+
+        drop(shared_readers);
+        observe::readers_scanned(&self.shared.metrics, self.shared.live_count(), pinned);
+
+        let reclaim_threshold = min_active.min(safety_limit);
+
+        self.shared
+            .min_active_version
+            .store(reclaim_threshold, Ordering::Release);
+
+        // The switch happens after this scan published its threshold, so it only changes
+        // how later scans fence. A reader that already sees the new mode while this scan
+        // missed its announcement loads a pointer at least as new as the value current
+        // during this scan, which the scan never reclaims, and the next scan fences for the
+        // new mode and sees the announcement.
+        // 切换发生在本次扫描发布其阈值之后，因此只会改变之后的扫描如何执行 fence。
+        // 已经看到新模式、而其公布未被本次扫描看到的读者，加载的指针至少与本次扫描期间的
+        // 当前值一样新，而扫描从不回收当前值；下一次扫描会按新模式执行 fence 并看到该公布。
+        if let Some(next) = self.adaptive.as_mut().and_then(|a| a.decide(loads)) {
+            self.switch_mode(next);
+        }
+
+        reclaim_threshold
+    }
+
+    /// Switch the algorithm of an adaptive container.
+    ///
+    /// Readers read the mode after publishing their version and only skip the full fence
+    /// when they see read-preferred. Moving to read-preferred is immediate, since this
+    /// writer already pairs every later scan with a heavy barrier. Moving back needs a
+    /// grace period: the heavy barrier below makes every version published by a reader
+    /// that still saw read-preferred visible to all later scans, and readers that run after
+    /// it see write-preferred and fence themselves.
+    ///
+    /// 切换自适应容器的算法。
+    ///
+    /// 读者在发布其版本之后读取模式，只有在看到读优先时才会跳过完整的 fence。
+    /// 切换到读优先是立即生效的，因为此写者之后的每次扫描都会配合一个重型屏障。
+    /// 切回写优先则需要一个宽限期：下面的重型屏障使仍然看到读优先的读者所发布的每个版本
+    /// 对之后的所有扫描可见，而在其之后运行的读者会看到写优先并自行执行 fence。
+    fn switch_mode(&mut self, next: Strategy) {
+        observe::strategy_switched(&self.shared.metrics, next);
+        if next == Strategy::ReadPreferred {
+            self.shared
+                .mode
+                .store(MODE_READ_PREFERRED, Ordering::Relaxed);
+        } else {
+            self.shared
+                .mode
+                .store(MODE_WRITE_PREFERRED, Ordering::Relaxed);
+            heavy_barrier(Strategy::ReadPreferred);
+        }
+    }
+}
+
+/// A `Sync` handle to the shared state, used to create readers on other threads.
+///
+/// 共享状态的 `Sync` 句柄，用于在其他线程上创建读者。
+pub struct VersionedShared<T: 'static> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: 'static> VersionedShared<T> {
+    #[inline]
+    pub(crate) fn local(&self) -> VersionedReader<T> {
+        VersionedReader::new(self.shared.clone())
+    }
+
+    #[inline]
+    pub(crate) fn try_local(&self) -> Result<VersionedReader<T>, CapacityError> {
+        VersionedReader::try_new(self.shared.clone())
+    }
+
+    #[inline]
+    pub(crate) fn qsbr(&self) -> QsbrReader<T> {
+        QsbrReader::new(self.shared.clone())
+    }
+
+    #[inline]
+    pub(crate) fn try_qsbr(&self) -> Result<QsbrReader<T>, CapacityError> {
+        QsbrReader::try_new(self.shared.clone())
+    }
+}
+
+impl<T: 'static> Clone for VersionedShared<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+/// Thread-local reader of the [`Versioned`](crate::backend::Versioned) backend.
+///
+/// [`Versioned`](crate::backend::Versioned) 后端的线程本地读者。
+pub struct VersionedReader<T: 'static> {
+    registration: Registration,
+    shared: Arc<Shared<T>>,
+    pin_count: Cell<usize>,
+    /// Version loaded by the outermost pin.
+    ///
+    /// 最外层 pin 加载的版本。
+    pinned_version: Cell<usize>,
+    /// Generation of the shard joined by the outermost pin of a sharded reader.
+    ///
+    /// 分片读者的最外层 pin 所加入的分片代。
+    shard_generation: Cell<usize>,
+    strategy: Strategy,
+}
+
+impl<T: 'static> VersionedReader<T> {
+    fn new(shared: Arc<Shared<T>>) -> Self {
+        match Self::try_new(shared) {
+            Ok(reader) => reader,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    fn try_new(shared: Arc<Shared<T>>) -> Result<Self, CapacityError> {
+        let registration = if shared.is_bounded() {
+            Registration::Fixed(shared.claim_fixed()?)
+        } else if shared.shards.is_empty() {
+            // Register the reader immediately; a free slot is already inactive.
+            // 立即注册读者；空闲槽已经处于非活动状态。
+            let slot = shared.readers.register(
+                &shared.metrics,
+                || ReaderSlot::new(INACTIVE_VERSION),
+                |_| {},
+            );
+            Registration::Slot(slot)
+        } else {
+            // Shards are handed out in turn, which spreads readers evenly without taking the lock.
+            // 分片轮流分配，无需加锁即可将读者均匀分布。
+            let index = shared.next_shard.fetch_add(1, Ordering::Relaxed) % shared.shards.len();
+            shared.sharded_readers.fetch_add(1, Ordering::Relaxed);
+            observe::reader_registered(&shared.metrics, shared.live_count());
+            Registration::Shard(index)
+        };
+
+        Ok(Self {
+            registration,
+            strategy: shared.strategy,
+            shared,
+            pin_count: Cell::new(0),
+            pinned_version: Cell::new(INACTIVE_VERSION),
+            shard_generation: Cell::new(0),
+        })
+    }
+
+    /// Resolve where this reader announces its version.
+    ///
+    /// 解析此读者公布其版本的位置。
+    #[inline(always)]
+    fn announcement(&self) -> Announcement<'_> {
+        match &self.registration {
+            Registration::Slot(slot) => Announcement::Slot(slot),
+            Registration::Fixed(index) => Announcement::Slot(self.shared.fixed_slot(*index)),
+            Registration::Shard(index) => Announcement::Shard(&self.shared.shards[*index]),
+        }
+    }
+
+    /// Announce `version` before loading a value.
+    ///
+    /// 在加载值之前公布 `version`。
+    #[inline(always)]
+    fn announce(&self, version: usize) {
+        match self.announcement() {
+            Announcement::Slot(slot) => slot.active_version.store(version, Ordering::Release),
+            Announcement::Shard(shard) => self.shard_generation.set(shard.pin(version)),
+        }
+    }
+
+    /// Withdraw the announcement made by [`announce`](Self::announce).
+    ///
+    /// 撤回 [`announce`](Self::announce) 做出的公布。
+    #[inline(always)]
+    fn withdraw(&self) {
+        match self.announcement() {
+            Announcement::Slot(slot) => {
+                slot.active_version
+                    .store(INACTIVE_VERSION, Ordering::Release);
+            }
+            Announcement::Shard(shard) => shard.unpin(self.shard_generation.get()),
+        }
+    }
+
+    /// Count a load for the adaptive strategy.
+    ///
+    /// 为自适应策略统计一次加载。
+    #[inline(always)]
+    fn count_load(&self) {
+        let loads = match self.announcement() {
+            Announcement::Slot(slot) => &slot.loads,
+            Announcement::Shard(shard) => &shard.loads,
+        };
+        // Only this reader writes a slot counter, so no read-modify-write is needed. Shard
+        // counters may lose updates, which only makes the count approximate.
+        // 只有此读者写入槽计数器，因此不需要读-改-写操作。分片计数器可能丢失更新，
+        // 这只会使计数变为近似值。
+        let count = loads.load(Ordering::Relaxed);
+        loads.store(count.wrapping_add(1), Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub(crate) fn is_pinned(&self) -> bool {
+        self.pin_count.get() > 0
+    }
+
+    #[inline]
+    pub(crate) fn version(&self) -> usize {
+        self.shared.global_version.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub(crate) fn pin(&self) -> VersionedGuard<'_, T> {
+        let pin_count = self.pin_count.get();
+
+        // Reentrant pin: the version is already protected by the outer pin.
+        // Just increment count and reuse the existing pinned pointer.
+        // 可重入 pin：版本已经被外层 pin 保护。
+        // 只需增加计数并复用现有的 pinned 指针。
+        if pin_count > 0 {
+            self.pin_count.set(pin_count + 1);
+            checks::guard_pinned(pin_count + 1);
+
+            // Load the pointer that corresponds to our already-pinned version.
+            // Since we're reentrant, we should see the same or newer pointer.
+            // 加载与我们已 pin 版本对应的指针。
+            // 由于是可重入的，我们应该看到相同或更新的指针。
+            let ptr = self.shared.ptr.load(Ordering::Acquire);
+            let version = self.pinned_version.get();
+
+            return VersionedGuard {
+                reader: self,
+                ptr,
+                version,
+            };
+        }
+
+        // First pin: need to acquire a version and validate it.
+        // 首次 pin：需要获取版本并验证。
+        let wait_free = matches!(self.registration, Registration::Fixed(_));
+        let mut retried = false;
+        loop {
+            let current_version = self.shared.global_version.load(Ordering::Acquire);
+
+            self.announce(current_version);
+
+            // Light barrier coupled with Writer's Heavy barrier prevents Store-Load reordering.
+            light_barrier(self.strategy);
+
+            // An adaptive container falls back to a full fence unless it is read-preferred.
+            // The mode must be read after the version was published; see `switch_mode`.
+            // 自适应容器除非处于读优先模式，否则回退为完整的 fence。
+            // 模式必须在版本发布之后读取；参见 `switch_mode`。
+            if self.strategy == Strategy::Adaptive
+                && self.shared.mode.load(Ordering::Relaxed) != MODE_READ_PREFERRED
+            {
+                light_barrier(Strategy::WritePreferred);
+            }
+
+            // Check if our version is still valid (not yet reclaimed).
+            // 检查我们的版本是否仍然有效（尚未被回收）。
+            let min_active = self.shared.min_active_version.load(Ordering::Acquire);
+
+            // A bounded reader retries at most once, which keeps the pin wait-free. The
+            // check only keeps the announcement tight: a scan that published a newer
+            // `min_active` missed the announcement, so the pointer loaded below is at least
+            // as new as the value current during that scan, which it never reclaims, and
+            // every later scan sees the announcement.
+            // 有上限的读者最多重试一次，从而保持 pin 是无等待的。该检查只是让公布的版本更紧凑：
+            // 发布了更新的 `min_active` 的扫描错过了此次公布，因此下面加载的指针至少与该次扫描
+            // 期间的当前值一样新，而扫描从不回收当前值；之后的每次扫描都能看到此次公布。
+            if current_version >= min_active || (wait_free && retried) {
+                self.pinned_version.set(current_version);
+                break;
+            }
+
+            self.withdraw();
+            retried = true;
+
+            // Version was reclaimed between our read and store.
+            // Retry with a fresh version.
+            // 版本在我们读取和存储之间被回收了。
+            // 用新版本重试。
+            core::hint::spin_loop();
+        }
+
+        self.pin_count.set(1);
+
+        if self.strategy == Strategy::Adaptive {
+            self.count_load();
+        }
+
+        // Capture the pointer and version at pin time for snapshot semantics.
+        // 在 pin 时捕获指针和版本以实现快照语义。
+        let ptr = self.shared.ptr.load(Ordering::Acquire);
+        let version = self.pinned_version.get();
+
+        VersionedGuard {
+            reader: self,
+            ptr,
+            version,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn share(&self) -> VersionedShared<T> {
+        VersionedShared {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T: 'static> Clone for VersionedReader<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.shared.clone())
+    }
+}
+
+impl<T: 'static> Drop for VersionedReader<T> {
+    #[inline]
+    fn drop(&mut self) {
+        checks::reader_dropped(self.pin_count.get());
+        // The slot is inactive once the last guard is gone, so it can be reused.
+        // 最后一个守卫消失后该槽即为非活动状态，因此可以被复用。
+        match &self.registration {
+            Registration::Slot(slot) => self.shared.readers.release(&self.shared.metrics, slot),
+            Registration::Fixed(index) => self.shared.release_fixed(*index),
+            Registration::Shard(_) => {
+                self.shared.sharded_readers.fetch_sub(1, Ordering::Relaxed);
+                observe::reader_deregistered(&self.shared.metrics);
+            }
+        }
+    }
+}
+
+/// RAII pin on a [`VersionedReader`], dereferencing to the value captured at pin time.
+///
+/// [`VersionedReader`] 上的 RAII pin，解引用为 pin 时捕获的值。
+#[must_use]
+pub struct VersionedGuard<'a, T: 'static> {
+    reader: &'a VersionedReader<T>,
+    ptr: *const T,
+    version: usize,
+}
+
+impl<T: 'static> VersionedGuard<'_, T> {
+    #[inline]
+    pub(crate) fn version(&self) -> usize {
+        self.version
+    }
+}
+
+impl<T> Deref for VersionedGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // Safety: pin() guarantees pinned_version >= min_active,
+        // and the pointer was captured at pin time.
+        // The value is valid as long as guard is held.
+        // 安全性：pin() 保证 pinned_version >= min_active，
+        // 并且指针在 pin 时被捕获。
+        // 只要 guard 被持有，值就是有效的。
+        unsafe { &*self.ptr }
+    }
+}
+
+impl<T> Clone for VersionedGuard<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let pin_count = self.reader.pin_count.get();
+
+        assert!(
+            pin_count > 0,
+            "BUG: Cloning a ReadGuard in an unpinned state (pin_count = 0). \
+             This indicates incorrect API usage or a library bug."
+        );
+
+        self.reader.pin_count.set(pin_count + 1);
+        checks::guard_pinned(pin_count + 1);
+
+        VersionedGuard {
+            reader: self.reader,
+            ptr: self.ptr,
+            version: self.version,
+        }
+    }
+}
+
+impl<T> Drop for VersionedGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        let pin_count = self.reader.pin_count.get();
+
+        assert!(
+            pin_count > 0,
+            "BUG: Dropping a ReadGuard in an unpinned state (pin_count = 0). \
+             This indicates incorrect API usage or a library bug."
+        );
+
+        if pin_count == 1 {
+            self.reader.withdraw();
+        }
+
+        self.reader.pin_count.set(pin_count - 1);
+    }
+}
+
+// ============================================================================
+// Backend trait implementations
+// 后端 trait 实现
+// ============================================================================
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static> RawWriter<T, A>
+    for VersionedWriter<T, A>
+{
+    type Reader = VersionedReader<T>;
+    type Shared = VersionedShared<T>;
+    type Drain<'a>
+        = Drain<'a, T, A>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn new(data: T, config: Config, alloc: A) -> Self {
+        Self::new(data, config, alloc)
+    }
+
+    #[inline]
+    fn store(&mut self, data: T) {
+        self.store(data);
+    }
+
+    #[inline]
+    fn store_with<F>(&mut self, f: F)
+    where
+        F: FnOnce(Option<T>) -> T,
+    {
+        self.store_with(f);
+    }
+
+    #[inline]
+    fn take_recycled(&mut self) -> Option<T> {
+        self.take_recycled()
+    }
+
+    #[inline]
+    fn recycled_count(&self) -> usize {
+        self.recycled_count()
+    }
+
+    #[inline]
+    fn collect(&mut self) {
+        self.collect();
+    }
+
+    #[inline]
+    fn collect_budget(&mut self, budget: ReclaimBudget) -> Collected {
+        self.collect_budget(budget)
+    }
+
+    #[inline]
+    fn collect_into<E: Extend<T>>(&mut self, sink: &mut E) {
+        self.collect_into(sink);
+    }
+
+    #[inline]
+    fn drain_reclaimed(&mut self) -> Drain<'_, T, A> {
+        self.drain_reclaimed()
+    }
+
+    #[inline]
+    fn local(&self) -> VersionedReader<T> {
+        self.local()
+    }
+
+    #[inline]
+    fn try_local(&self) -> Result<VersionedReader<T>, CapacityError> {
+        self.try_local()
+    }
+
+    #[inline]
+    fn shared(&self) -> VersionedShared<T> {
+        self.shared()
+    }
+
+    #[inline]
+    fn get(&self) -> &T {
+        self.get()
+    }
+
+    #[inline]
+    fn previous(&self) -> Option<&T> {
+        self.previous()
+    }
+
+    #[inline]
+    fn version(&self) -> usize {
+        self.version()
+    }
+
+    #[inline]
+    fn garbage_count(&self) -> usize {
+        self.garbage_count()
+    }
+
+    #[inline]
+    fn strategy(&self) -> Strategy {
+        self.strategy()
+    }
+
+    #[inline]
+    fn active_strategy(&self) -> Strategy {
+        self.active_strategy()
+    }
+
+    #[inline]
+    fn live_readers(&self) -> (usize, usize) {
+        self.live_readers()
+    }
+
+    #[inline]
+    fn reader_slots(&self) -> ReaderSlots {
+        self.reader_slots()
+    }
+}
+
+impl<T: 'static> RawReader<T> for VersionedReader<T> {
+    type Shared = VersionedShared<T>;
+    type Guard<'a> = VersionedGuard<'a, T>;
+
+    #[inline]
+    fn pin(&self) -> VersionedGuard<'_, T> {
+        self.pin()
+    }
+
+    #[inline]
+    fn is_pinned(&self) -> bool {
+        self.is_pinned()
+    }
+
+    #[inline]
+    fn version(&self) -> usize {
+        self.version()
+    }
+
+    #[inline]
+    fn share(&self) -> VersionedShared<T> {
+        self.share()
+    }
+}
+
+impl<T: 'static> RawShared<T> for VersionedShared<T> {
+    type Reader = VersionedReader<T>;
+
+    #[inline]
+    fn local(&self) -> VersionedReader<T> {
+        self.local()
+    }
+
+    #[inline]
+    fn try_local(&self) -> Result<VersionedReader<T>, CapacityError> {
+        self.try_local()
+    }
+}
+
+impl<T: 'static> RawGuard<T> for VersionedGuard<'_, T> {
+    #[inline]
+    fn version(&self) -> usize {
+        self.version()
+    }
+}
@@ -0,0 +1,86 @@
+//! Runtime checks for API misuse, enabled by the `debug-checks` feature.
+//!
+//! Every check compiles to nothing unless the feature is enabled. Misuse that would
+//! stall reclamation forever panics with the name of the API involved; readers that
+//! outlive their writer only produce a warning on stderr, since that is allowed.
+//!
+//! 由 `debug-checks` feature 启用的 API 误用运行时检查。
+//!
+//! 除非启用了该 feature，否则每个检查都会被编译为空操作。会使回收永久停滞的误用会 panic，
+//! 并给出所涉及 API 的名称；比写者存活更久的读者只会在 stderr 上产生警告，因为这是允许的。
+
+#![allow(unused_variables)]
+
+/// Maximum number of guards pinned at once on a single `LocalReader`.
+///
+/// Nested loads are cheap and legitimate, but no real program nests this deep: reaching
+/// the limit means guards are leaked in a loop.
+///
+/// 单个 `LocalReader` 上同时被 pin 的守卫的最大数量。
+///
+/// 嵌套加载开销很低且是合法的，但实际程序不会嵌套得这么深：达到该限制意味着守卫在循环中被泄漏。
+#[cfg(feature = "debug-checks")]
+pub(crate) const MAX_PIN_DEPTH: usize = 1024;
+
+/// A guard was pinned on a reader, which now holds `depth` guards.
+///
+/// 读者上 pin 了一个守卫，该读者现在持有 `depth` 个守卫。
+#[inline(always)]
+pub(crate) fn guard_pinned(depth: usize) {
+    #[cfg(feature = "debug-checks")]
+    assert!(
+        depth <= MAX_PIN_DEPTH,
+        "smr-swap: more than {MAX_PIN_DEPTH} `ReadGuard`s are pinned on one `LocalReader`; \
+         guards returned by `LocalReader::load` are being leaked (e.g. with `mem::forget`)"
+    );
+}
+
+/// A reader was dropped while `depth` guards were still pinned on it.
+///
+/// Guards borrow their reader, so this is only reachable when a guard was forgotten.
+///
+/// 读者在仍有 `depth` 个守卫被 pin 时被 drop。
+///
+/// 守卫借用其读者，因此只有在守卫被 forget 时才会出现这种情况。
+#[inline(always)]
+pub(crate) fn reader_dropped(depth: usize) {
+    #[cfg(feature = "debug-checks")]
+    if depth > 0 && !std::thread::panicking() {
+        panic!(
+            "smr-swap: `LocalReader` dropped while {depth} `ReadGuard`(s) were still pinned; \
+             a guard returned by `LocalReader::load` was leaked (e.g. with `mem::forget`)"
+        );
+    }
+}
+
+/// The writer is about to collect garbage through `api`.
+///
+/// `pinned` tells whether the writer's own reader still holds a guard, which can only
+/// happen when a guard from `SmrSwap::load` was forgotten.
+///
+/// 写者即将通过 `api` 回收垃圾。
+///
+/// `pinned` 表示写者自己的读者是否仍持有守卫，这只会在 `SmrSwap::load` 返回的守卫被 forget 时发生。
+#[inline(always)]
+pub(crate) fn writer_collecting(api: &'static str, pinned: bool) {
+    #[cfg(feature = "debug-checks")]
+    assert!(
+        !pinned,
+        "smr-swap: `SmrSwap::{api}` called while a guard returned by `SmrSwap::load` is still \
+         pinned; the guard was leaked (e.g. with `mem::forget`) and blocks reclamation"
+    );
+}
+
+/// The writer was dropped while `readers` readers (`pinned` of them holding a guard) were alive.
+///
+/// 写者在仍有 `readers` 个读者存活（其中 `pinned` 个持有守卫）时被 drop。
+#[cfg(feature = "debug-checks")]
+#[inline(always)]
+pub(crate) fn writer_dropped(readers: usize, pinned: usize) {
+    if readers > 0 {
+        std::eprintln!(
+            "smr-swap warning: `SmrSwap` dropped while {readers} `LocalReader`(s) are still \
+             alive ({pinned} pinned); they keep the last value alive until they are dropped"
+        );
+    }
+}
@@ -0,0 +1,373 @@
+//! An `arc-swap`-shaped API on top of `SmrSwap<Arc<T>>`.
+//!
+//! [`ArcSwap`] mirrors the most used parts of `arc_swap::ArcSwap`, so call sites can be
+//! switched between the two implementations with a type alias:
+//!
+//! ```rust
+//! // type Config = arc_swap::ArcSwap<String>;
+//! type Config = smr_swap::compat::ArcSwap<String>;
+//!
+//! let config = Config::from_pointee(String::from("v1"));
+//! config.store(std::sync::Arc::new(String::from("v2")));
+//! assert_eq!(**config.load(), "v2");
+//! ```
+//!
+//! Unlike `SmrSwap`, `ArcSwap` is `Sync` and every method takes `&self`: writers are
+//! serialized by a mutex, and readers use an implicit per-thread `LocalReader`.
+//!
+//! 基于 `SmrSwap<Arc<T>>` 的 `arc-swap` 风格 API。
+//!
+//! [`ArcSwap`] 对应 `arc_swap::ArcSwap` 最常用的部分，因此可以通过类型别名在两种实现之间切换调用点。
+//!
+//! 与 `SmrSwap` 不同，`ArcSwap` 是 `Sync` 的，且所有方法都接收 `&self`：
+//! 写者由互斥锁串行化，读者使用隐式的线程级 `LocalReader`。
+
+use core::any::Any;
+use core::cell::RefCell;
+use core::fmt;
+use core::mem;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::vec::Vec;
+
+use crate::{LocalReader, ReadGuard, SmrReader, SmrSwap};
+
+/// Source of unique [`ArcSwap`] identifiers.
+///
+/// 唯一 [`ArcSwap`] 标识符的来源。
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+struct CachedReader {
+    id: usize,
+    alive: Weak<()>,
+    reader: Rc<dyn Any>,
+}
+
+std::thread_local! {
+    /// Per-thread readers of every `ArcSwap` this thread has read from.
+    ///
+    /// 当前线程读取过的每个 `ArcSwap` 的读取者。
+    static LOCAL_READERS: RefCell<Vec<CachedReader>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Types that can be compared by address with the value stored in an [`ArcSwap`].
+///
+/// 可以与 [`ArcSwap`] 中存储的值按地址比较的类型。
+pub trait AsRaw<T> {
+    /// Get the address of the pointed-to value.
+    ///
+    /// 获取所指向值的地址。
+    fn as_raw(&self) -> *const T;
+}
+
+impl<T> AsRaw<T> for &Arc<T> {
+    #[inline]
+    fn as_raw(&self) -> *const T {
+        Arc::as_ptr(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> AsRaw<T> for &Guard<T> {
+    #[inline]
+    fn as_raw(&self) -> *const T {
+        Arc::as_ptr(self)
+    }
+}
+
+impl<T> AsRaw<T> for *const T {
+    #[inline]
+    fn as_raw(&self) -> *const T {
+        *self
+    }
+}
+
+impl<T> AsRaw<T> for *mut T {
+    #[inline]
+    fn as_raw(&self) -> *const T {
+        *self
+    }
+}
+
+/// A `Sync` swap container for `Arc<T>` with an `arc_swap::ArcSwap`-shaped API.
+///
+/// 具有 `arc_swap::ArcSwap` 风格 API 的 `Arc<T>` `Sync` 交换容器。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::compat::ArcSwap;
+/// use std::sync::Arc;
+///
+/// let counter = ArcSwap::from_pointee(0);
+/// counter.rcu(|n| **n + 1);
+///
+/// let current = counter.load_full();
+/// assert_eq!(*current, 1);
+///
+/// let previous = counter.compare_and_swap(&current, Arc::new(10));
+/// assert!(Arc::ptr_eq(&previous, &current));
+/// assert_eq!(**counter.load(), 10);
+/// ```
+pub struct ArcSwap<T: 'static> {
+    // Declared first so that this thread's cached reader is released before the writer.
+    // 首先声明，以便在写者之前释放当前线程缓存的读取者。
+    registration: Registration,
+    writer: Mutex<SmrSwap<Arc<T>>>,
+    reader: SmrReader<Arc<T>>,
+}
+
+/// Identity of an [`ArcSwap`] in the thread-local reader cache.
+///
+/// [`ArcSwap`] 在线程本地读取者缓存中的标识。
+struct Registration {
+    id: usize,
+    // Thread-local readers hold a weak reference, so dead entries can be pruned.
+    // 线程本地读取者持有弱引用，因此可以清理失效的条目。
+    alive: Arc<()>,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // Other threads prune their entries the next time they read a new container.
+        // 其他线程会在下次读取新容器时清理它们的条目。
+        let _ = LOCAL_READERS.try_with(|readers| {
+            let removed = {
+                let mut readers = readers.borrow_mut();
+                readers
+                    .iter()
+                    .position(|c| c.id == self.id)
+                    .map(|index| readers.swap_remove(index))
+            };
+            drop(removed);
+        });
+    }
+}
+
+impl<T: Send + Sync + 'static> ArcSwap<T> {
+    /// Create a new container holding `value`.
+    ///
+    /// 创建一个持有 `value` 的新容器。
+    pub fn new(value: Arc<T>) -> Self {
+        let swap = SmrSwap::new(value);
+        Self {
+            reader: swap.reader(),
+            writer: Mutex::new(swap),
+            registration: Registration {
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                alive: Arc::new(()),
+            },
+        }
+    }
+
+    /// Create a new container holding `Arc::new(value)`.
+    ///
+    /// 创建一个持有 `Arc::new(value)` 的新容器。
+    #[inline]
+    pub fn from_pointee(value: T) -> Self {
+        Self::new(Arc::new(value))
+    }
+
+    #[inline]
+    fn writer(&self) -> MutexGuard<'_, SmrSwap<Arc<T>>> {
+        self.writer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Get the implicit `LocalReader` of the current thread, creating it on first use.
+    ///
+    /// 获取当前线程的隐式 `LocalReader`，首次使用时创建。
+    fn thread_reader(&self) -> Rc<LocalReader<Arc<T>>> {
+        let Registration { id, alive } = &self.registration;
+        let cached = LOCAL_READERS.try_with(|readers| {
+            let mut readers = readers.borrow_mut();
+            if let Some(cached) = readers.iter().find(|c| c.id == *id) {
+                return Rc::clone(&cached.reader);
+            }
+            // Drop the readers of containers that no longer exist before adding a new one.
+            // 在添加新读取者之前，drop 已不存在的容器的读取者。
+            readers.retain(|c| c.alive.strong_count() > 0);
+            let reader: Rc<dyn Any> = Rc::new(self.reader.local());
+            readers.push(CachedReader {
+                id: *id,
+                alive: Arc::downgrade(alive),
+                reader: Rc::clone(&reader),
+            });
+            reader
+        });
+
+        match cached {
+            Ok(reader) => Rc::downcast(reader).expect("reader registered under a unique id"),
+            // The thread-local storage is being destroyed; fall back to a temporary reader.
+            // 线程本地存储正在被销毁；退回到临时读取者。
+            Err(_) => Rc::new(self.reader.local()),
+        }
+    }
+
+    /// Load the current value as a guard that dereferences to `Arc<T>`.
+    ///
+    /// 以解引用为 `Arc<T>` 的守卫加载当前值。
+    #[inline]
+    pub fn load(&self) -> Guard<T> {
+        let reader = self.thread_reader();
+        let guard = reader.load();
+        // Safety: the guard borrows the reader behind the `Rc`, whose address is stable.
+        // `Guard` keeps the `Rc` alive and drops the guard before it.
+        // 安全性：守卫借用 `Rc` 后面的读取者，其地址是稳定的。
+        // `Guard` 保持 `Rc` 存活，并在它之前 drop 守卫。
+        let guard =
+            unsafe { mem::transmute::<ReadGuard<'_, Arc<T>>, ReadGuard<'static, Arc<T>>>(guard) };
+        Guard {
+            guard,
+            _reader: reader,
+        }
+    }
+
+    /// Load the current value as an owned `Arc<T>`.
+    ///
+    /// 以拥有所有权的 `Arc<T>` 加载当前值。
+    #[inline]
+    pub fn load_full(&self) -> Arc<T> {
+        self.thread_reader().map(Arc::clone)
+    }
+
+    /// Store a new value.
+    ///
+    /// 存储新值。
+    #[inline]
+    pub fn store(&self, value: Arc<T>) {
+        self.writer().store(value);
+    }
+
+    /// Store a new value and return the previous one.
+    ///
+    /// 存储新值并返回之前的值。
+    #[inline]
+    pub fn swap(&self, value: Arc<T>) -> Arc<T> {
+        self.writer().swap(value)
+    }
+
+    /// Replace the value with `f(current)` and return the previous value.
+    ///
+    /// Writers are serialized, so `f` is called exactly once.
+    ///
+    /// 将值替换为 `f(current)` 并返回之前的值。
+    ///
+    /// 写者是串行化的，因此 `f` 只会被调用一次。
+    pub fn rcu<R, F>(&self, mut f: F) -> Arc<T>
+    where
+        F: FnMut(&Arc<T>) -> R,
+        R: Into<Arc<T>>,
+    {
+        let mut writer = self.writer();
+        let previous = Arc::clone(writer.get());
+        writer.store(f(&previous).into());
+        previous
+    }
+
+    /// Store `new` if the current value is `current` (compared by address).
+    ///
+    /// Returns the value before the operation: equal to `current` if the swap happened,
+    /// and the value that prevented it otherwise.
+    ///
+    /// 如果当前值是 `current`（按地址比较），则存储 `new`。
+    ///
+    /// 返回操作之前的值：如果交换发生则等于 `current`，否则为阻止交换的值。
+    pub fn compare_and_swap<C>(&self, current: C, new: Arc<T>) -> Guard<T>
+    where
+        C: AsRaw<T>,
+    {
+        let mut writer = self.writer();
+        // Writers are serialized by the lock, so this is the value `writer` holds.
+        // 写者由锁串行化，因此这就是 `writer` 持有的值。
+        let previous = self.load();
+        if Arc::as_ptr(&previous) == current.as_raw() {
+            writer.store(new);
+        }
+        previous
+    }
+
+    /// Create a `SmrReader` for this container.
+    ///
+    /// 为此容器创建一个 `SmrReader`。
+    #[inline]
+    pub fn reader(&self) -> SmrReader<Arc<T>> {
+        self.reader.clone()
+    }
+
+    /// Consume the container and return its writer.
+    ///
+    /// 消耗容器并返回其写者。
+    #[inline]
+    pub fn into_inner(self) -> SmrSwap<Arc<T>> {
+        self.writer.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<T: Send + Sync + Default + 'static> Default for ArcSwap<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::from_pointee(T::default())
+    }
+}
+
+impl<T: Send + Sync + 'static> From<Arc<T>> for ArcSwap<T> {
+    #[inline]
+    fn from(value: Arc<T>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Send + Sync + fmt::Debug + 'static> fmt::Debug for ArcSwap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArcSwap").field(&**self.load()).finish()
+    }
+}
+
+/// RAII guard returned by [`ArcSwap::load`], dereferencing to `Arc<T>`.
+///
+/// Holds the implicit per-thread reader alive for as long as the value is pinned.
+/// It is `!Send`, like the reader it borrows from.
+///
+/// [`ArcSwap::load`] 返回的 RAII 守卫，解引用为 `Arc<T>`。
+///
+/// 在值被 pin 期间保持隐式的线程级读取者存活。它与所借用的读取者一样是 `!Send` 的。
+pub struct Guard<T: 'static> {
+    // Declared before `_reader` so that it is dropped first.
+    // 在 `_reader` 之前声明，以便先被 drop。
+    guard: ReadGuard<'static, Arc<T>>,
+    _reader: Rc<LocalReader<Arc<T>>>,
+}
+
+impl<T: 'static> Guard<T> {
+    /// Convert the guard into an owned `Arc<T>`.
+    ///
+    /// 将守卫转换为拥有所有权的 `Arc<T>`。
+    #[inline]
+    pub fn into_inner(guard: Self) -> Arc<T> {
+        Arc::clone(&guard.guard)
+    }
+}
+
+impl<T: 'static> Deref for Guard<T> {
+    type Target = Arc<T>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Arc<T> {
+        &self.guard
+    }
+}
+
+impl<T: fmt::Debug + 'static> fmt::Debug for Guard<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self.guard, f)
+    }
+}
+
+impl<T: fmt::Display + 'static> fmt::Display for Guard<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self.guard, f)
+    }
+}
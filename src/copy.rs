@@ -0,0 +1,432 @@
+//! Allocation-free swap container for small `Copy` values.
+//!
+//! 用于小型 `Copy` 值的无分配交换容器。
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+use core::ptr;
+
+use crate::shim::sync::{Arc, AtomicUsize, Ordering, fence};
+
+/// One of the two buffers of an [`SmrCopy`], protected by its own sequence.
+///
+/// The sequence is odd while the writer is filling `value` and `2 * version`
+/// once the value of `version` is in place.
+///
+/// [`SmrCopy`] 的两个缓冲区之一，由其自身的序列号保护。
+///
+/// 写者填充 `value` 时序列号为奇数，`version` 的值写入完成后为 `2 * version`。
+struct Slot<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<T>,
+}
+
+/// Shared state of an [`SmrCopy`]: a double buffer and the published version.
+///
+/// The value of `version` lives in `slots[version & 1]`. The writer fills the other
+/// slot and only then publishes the next version, so the slot readers are sent to
+/// is never being written unless the writer has completed a newer store since.
+///
+/// [`SmrCopy`] 的共享状态：一个双缓冲区和已发布的版本。
+///
+/// `version` 的值位于 `slots[version & 1]`。写者填充另一个槽，之后才发布下一个版本，
+/// 因此读者被引导到的槽不会正在被写入，除非写者此后已经完成了一次更新的存储。
+struct Shared<T> {
+    version: AtomicUsize,
+    slots: [Slot<T>; 2],
+}
+
+// Safety: readers never hand out references into a slot, only copies validated
+// against its sequence; the only writer is the owner of the `SmrCopy`.
+// 安全性：读者从不交出指向槽的引用，只交出经过其序列号验证的副本；唯一的写者是 `SmrCopy` 的所有者。
+unsafe impl<T: Copy + Send> Sync for Shared<T> {}
+unsafe impl<T: Copy + Send> Send for Shared<T> {}
+
+impl<T: Copy> Shared<T> {
+    /// Read a consistent copy of the latest value and its version.
+    ///
+    /// Readers never wait for the writer: a copy is only discarded when the writer
+    /// has reused its slot, which takes a completed store after `version`, so every
+    /// retry means the writer made progress.
+    ///
+    /// 读取最新值的一致副本及其版本。
+    ///
+    /// 读者从不等待写者：只有当写者复用了该槽时副本才会被丢弃，而这需要在 `version`
+    /// 之后完成一次存储，因此每次重试都意味着写者取得了进展。
+    #[inline]
+    fn read(&self) -> (T, usize) {
+        loop {
+            let version = self.version.load(Ordering::Acquire);
+            let slot = &self.slots[version & 1];
+            let before = slot.sequence.load(Ordering::Acquire);
+
+            // A slot that moved past `version` is retried as well, so that versions
+            // never go backwards for a reader.
+            // 已经越过 `version` 的槽同样会重试，从而保证对读者而言版本从不倒退。
+            if before == 2 * version {
+                // The read may race with the writer and copy a torn value, which is not a
+                // valid `T` for types such as `bool`, enums or references. The bytes are
+                // therefore copied as `MaybeUninit<T>`, as `crossbeam`'s seqlock does, and
+                // only treated as a `T` once the sequence proves no store overlapped the copy.
+                // 读取可能与写者竞争并复制出撕裂的值，对于 `bool`、枚举或引用等类型，这不是有效的 `T`。
+                // 因此像 `crossbeam` 的序列锁一样以 `MaybeUninit<T>` 复制这些字节，
+                // 只有在序列号证明没有存储与该复制重叠时才将其视为 `T`。
+                let value =
+                    unsafe { ptr::read_volatile(slot.value.get().cast::<MaybeUninit<T>>()) };
+
+                fence(Ordering::Acquire);
+                if slot.sequence.load(Ordering::Relaxed) == before {
+                    // Safety: no store ran during the copy, so it holds the value of `version`.
+                    // 安全性：复制期间没有存储运行，因此它持有 `version` 对应的值。
+                    return (unsafe { value.assume_init() }, version);
+                }
+            }
+
+            // The writer reused the slot after publishing a newer version; load that one.
+            // 写者在发布更新的版本之后复用了该槽；改为加载那个版本。
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// A single-writer, multi-reader container for small `Copy` values.
+///
+/// `SmrCopy<T>` stores the value inline in a double buffer instead of in a heap
+/// node, so `store` never allocates and produces no garbage, and `load` never
+/// pins. Its API mirrors `SmrSwap`: the writer stores and updates, readers obtain
+/// guards that dereference to the value and report its version.
+///
+/// Readers copy the value out of the buffer the latest version was written to, while
+/// the writer fills the other one, so `load` never waits for a store in progress. A
+/// copy is only retried when two stores complete during it, which makes `load`
+/// lock-free. This suits counters and small structs; large values are better served
+/// by `SmrSwap`.
+///
+/// 用于小型 `Copy` 值的单写多读容器。
+///
+/// `SmrCopy<T>` 将值内联存储在双缓冲区中，而不是存储在堆节点中，因此 `store` 从不分配内存、
+/// 不产生垃圾，`load` 也从不 pin。它的 API 与 `SmrSwap` 对应：写者存储和更新，
+/// 读者获取可以解引用为值并报告其版本的守卫。
+///
+/// 读者从最新版本所写入的缓冲区中复制值，而写者填充另一个缓冲区，因此 `load` 从不等待
+/// 进行中的存储。只有当复制期间完成了两次存储时才会重试，这使得 `load` 是无锁的。
+/// 这适用于计数器和小型结构体；大型值更适合使用 `SmrSwap`。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::SmrCopy;
+///
+/// let mut counter = SmrCopy::new(0u64);
+/// let local = counter.local();
+///
+/// counter.update(|n| n + 1);
+///
+/// let guard = local.load();
+/// assert_eq!(*guard, 1);
+/// assert_eq!(guard.version(), 1);
+/// ```
+pub struct SmrCopy<T: Copy> {
+    shared: Arc<Shared<T>>,
+    current: T,
+}
+
+/// Reader handle for an [`SmrCopy`].
+///
+/// Unlike `LocalReader`, a `CopyReader` holds no per-thread state, so it is `Send`,
+/// `Sync` and cheap to clone.
+///
+/// [`SmrCopy`] 的读取句柄。
+///
+/// 与 `LocalReader` 不同，`CopyReader` 不持有线程级状态，因此它是 `Send`、`Sync` 的，且克隆开销很小。
+pub struct CopyReader<T: Copy> {
+    shared: Arc<Shared<T>>,
+}
+
+/// A consistent copy of an [`SmrCopy`] value together with its version.
+///
+/// [`SmrCopy`] 值的一致副本及其版本。
+#[derive(Clone, Copy)]
+pub struct CopyGuard<T: Copy> {
+    value: T,
+    version: usize,
+}
+
+// ============================================================================
+// SmrCopy implementation
+// ============================================================================
+
+impl<T: Copy + Send> SmrCopy<T> {
+    /// Create a new container with the given initial value.
+    ///
+    /// 使用给定的初始值创建新容器。
+    #[inline]
+    pub fn new(initial: T) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                version: AtomicUsize::new(0),
+                slots: [
+                    Slot {
+                        sequence: AtomicUsize::new(0),
+                        value: UnsafeCell::new(initial),
+                    },
+                    Slot {
+                        sequence: AtomicUsize::new(0),
+                        value: UnsafeCell::new(initial),
+                    },
+                ],
+            }),
+            current: initial,
+        }
+    }
+
+    /// Create a new reader for this container.
+    ///
+    /// 为此容器创建一个新的读取者。
+    #[inline]
+    pub fn local(&self) -> CopyReader<T> {
+        CopyReader {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Create a new reader that can be shared across threads.
+    ///
+    /// `CopyReader` is already `Sync`, so this is equivalent to [`local`](SmrCopy::local).
+    ///
+    /// 创建一个可以在线程之间共享的新读取者。
+    ///
+    /// `CopyReader` 已经是 `Sync` 的，因此这等同于 [`local`](SmrCopy::local)。
+    #[inline]
+    pub fn reader(&self) -> CopyReader<T> {
+        self.local()
+    }
+
+    /// Store a new value, making it visible to readers.
+    ///
+    /// No allocation takes place and nothing is retired.
+    ///
+    /// 存储新值，使其对读者可见。
+    ///
+    /// 不会发生内存分配，也不会退休任何对象。
+    #[inline]
+    pub fn store(&mut self, new_value: T) {
+        let version = self.shared.version.load(Ordering::Relaxed) + 1;
+        let slot = &self.shared.slots[version & 1];
+        slot.sequence.store(2 * version - 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        // Safety: we are the only writer, and readers discard any copy that overlaps
+        // with this write.
+        // 安全性：我们是唯一的写者，读者会丢弃与此写入重叠的任何副本。
+        unsafe { ptr::write_volatile(slot.value.get(), new_value) };
+
+        slot.sequence.store(2 * version, Ordering::Release);
+        self.shared.version.store(version, Ordering::Release);
+        self.current = new_value;
+    }
+
+    /// Get a reference to the current value (writer-only, no synchronization required).
+    ///
+    /// 获取当前值的引用（仅写者可用，无需同步）。
+    #[inline]
+    pub fn get(&self) -> &T {
+        &self.current
+    }
+
+    /// Update the value using a closure.
+    ///
+    /// 使用闭包更新值。
+    #[inline]
+    pub fn update<F>(&mut self, f: F)
+    where
+        F: FnOnce(&T) -> T,
+    {
+        self.store(f(&self.current));
+    }
+
+    /// Store a new value and return the old one.
+    ///
+    /// 存储新值并返回旧值。
+    #[inline]
+    pub fn swap(&mut self, new_value: T) -> T {
+        let old = self.current;
+        self.store(new_value);
+        old
+    }
+
+    /// Get the current version.
+    ///
+    /// The version is incremented each time `store()` is called.
+    ///
+    /// 获取当前版本。
+    ///
+    /// 每次调用 `store()` 时版本会增加。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.shared.version.load(Ordering::Relaxed)
+    }
+
+    /// Read the current value as a guard.
+    ///
+    /// 以守卫的形式读取当前值。
+    #[inline]
+    pub fn load(&self) -> CopyGuard<T> {
+        CopyGuard {
+            value: self.current,
+            version: self.version(),
+        }
+    }
+}
+
+// ============================================================================
+// CopyReader implementation
+// ============================================================================
+
+impl<T: Copy + Send> CopyReader<T> {
+    /// Read a consistent copy of the current value.
+    ///
+    /// 读取当前值的一致副本。
+    #[inline]
+    pub fn load(&self) -> CopyGuard<T> {
+        let (value, version) = self.shared.read();
+        CopyGuard { value, version }
+    }
+
+    /// Read a consistent copy of the current value without its version.
+    ///
+    /// 读取当前值的一致副本，不包括其版本。
+    #[inline]
+    pub fn get(&self) -> T {
+        self.shared.read().0
+    }
+
+    /// Apply a closure function to the current value and return the result.
+    ///
+    /// 对当前值应用闭包函数并返回结果。
+    #[inline]
+    pub fn map<F, U>(&self, f: F) -> U
+    where
+        F: FnOnce(&T) -> U,
+    {
+        f(&self.get())
+    }
+
+    /// Get the current version.
+    ///
+    /// 获取当前版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.shared.version.load(Ordering::Acquire)
+    }
+
+    /// Create another reader for the same container.
+    ///
+    /// 为同一个容器创建另一个读取者。
+    #[inline]
+    pub fn local(&self) -> CopyReader<T> {
+        self.clone()
+    }
+}
+
+impl<T: Copy> Clone for CopyReader<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T: Copy + Send> fmt::Debug for CopyReader<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CopyReader")
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+// ============================================================================
+// CopyGuard implementation
+// ============================================================================
+
+impl<T: Copy> CopyGuard<T> {
+    /// Get the version of the copied value.
+    ///
+    /// 获取被复制值的版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// Return the copied value.
+    ///
+    /// 返回被复制的值。
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Copy> Deref for CopyGuard<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Copy> AsRef<T> for CopyGuard<T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for CopyGuard<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CopyGuard")
+            .field("value", &self.value)
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+// ============================================================================
+// Standard Trait Implementations
+// 标准 trait 实现
+// ============================================================================
+
+impl<T: Copy + Send + Default> Default for SmrCopy<T> {
+    /// Create a new SmrCopy with the default value.
+    ///
+    /// 使用默认值创建一个新的 SmrCopy。
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy + Send> From<T> for SmrCopy<T> {
+    /// Create a new SmrCopy from a value.
+    ///
+    /// 从一个值创建一个新的 SmrCopy。
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Copy + Send + fmt::Debug> fmt::Debug for SmrCopy<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmrCopy")
+            .field("value", self.get())
+            .field("version", &self.version())
+            .finish()
+    }
+}
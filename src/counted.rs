@@ -0,0 +1,566 @@
+//! Reference-counting reclamation backend.
+//!
+//! The current value, its version and its reference count sit behind one lock. A load
+//! takes the lock and increments the count of the current value, and dropping the guard
+//! decrements it. The writer reclaims retired values whose count dropped to zero.
+//!
+//! Every load takes a lock, so this backend is much slower than the others. It exists to
+//! rule out the reclamation scheme when debugging: it has no fences and no versions to
+//! reason about.
+//!
+//! 引用计数回收后端。
+//!
+//! 当前值、其版本和其引用计数位于同一把锁之后。加载时获取锁并增加当前值的计数，
+//! drop 守卫时减少计数。写者回收计数降为零的已退休值。
+//!
+//! 每次加载都会获取锁，因此该后端比其他后端慢得多。它的存在是为了在调试时排除回收方案的问题：
+//! 它没有需要推理的 fence 和版本。
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use allocator_api2::alloc::{Allocator, Global};
+use allocator_api2::vec::Vec as AllocVec;
+
+use crate::Strategy;
+use crate::backend::{
+    Collected, Config, Drain, FreeNode, Node, Pool, RawGuard, RawReader, RawShared, RawWriter,
+    ReaderSlots, ReclaimBudget, Registry, RegistrySlot,
+};
+use crate::checks;
+use crate::observe;
+use crate::shim::{Arc, AtomicBool, AtomicUsize, Box, Cell, Mutex, Ordering, Vec};
+
+/// The current value with its version and reference count.
+///
+/// 当前值及其版本和引用计数。
+struct Current<T> {
+    ptr: *mut T,
+    version: usize,
+    refs: Arc<AtomicUsize>,
+}
+
+/// Registry slot of one reader.
+///
+/// 一个读者的注册槽。
+struct CountedSlot {
+    /// Number of live guards, also read by the writer.
+    ///
+    /// 存活守卫的数量，写者也会读取。
+    guards: AtomicUsize,
+    in_use: AtomicBool,
+}
+
+impl RegistrySlot for CountedSlot {
+    #[inline]
+    fn in_use(&self) -> &AtomicBool {
+        &self.in_use
+    }
+}
+
+/// State shared between the writer and all readers.
+///
+/// 写者与所有读者共享的状态。
+struct CountedState<T: 'static> {
+    current: Mutex<Current<T>>,
+    /// Copy of the current version, readable without the lock.
+    ///
+    /// 当前版本的副本，无需加锁即可读取。
+    global_version: AtomicUsize,
+    readers: Registry<CountedSlot>,
+    /// Retired values left behind by a dropped writer, freed with the state.
+    ///
+    /// 被 drop 的写者遗留的已退休值，随状态一起释放。
+    orphans: Mutex<Vec<*mut T>>,
+    metrics: observe::Metrics,
+    alloc: Box<dyn FreeNode<T>>,
+    // Readers hand out `&T` on any thread and the last handle may drop the value.
+    // 读者会在任意线程上交出 `&T`，最后一个句柄可能会 drop 该值。
+    _marker: PhantomData<T>,
+}
+
+// Safety: the current and orphaned pointers are owned by the state and only freed when it
+// drops, so the state is as thread-safe as the values it owns.
+// 安全性：当前指针和被遗留的指针由状态拥有，并且只在状态被 drop 时释放，
+// 因此状态的线程安全性与其拥有的值相同。
+unsafe impl<T: Send + Sync> Send for CountedState<T> {}
+unsafe impl<T: Send + Sync> Sync for CountedState<T> {}
+
+impl<T: 'static> Drop for CountedState<T> {
+    fn drop(&mut self) {
+        let current = self.current.lock().ptr;
+        let orphans = core::mem::take(&mut *self.orphans.lock());
+        for ptr in orphans.into_iter().chain(Some(current)) {
+            // Safety: no reader is left, and every pointer is a node owned by the state.
+            // 安全性：已经没有读者了，并且每个指针都是由状态拥有的节点。
+            unsafe { self.alloc.free(ptr) };
+        }
+    }
+}
+
+/// Writer side of the [`RefCounted`](crate::backend::RefCounted) backend.
+///
+/// [`RefCounted`](crate::backend::RefCounted) 后端的写者端。
+pub struct CountedWriter<T: 'static, A: Allocator = Global> {
+    state: Arc<CountedState<T>>,
+    /// Retired values tagged with the last version in which they were current, oldest first.
+    ///
+    /// 已退休的值，以它们作为当前值的最后版本标记，最旧的在前。
+    garbage: AllocVec<(usize, Node<T, A>), A>,
+    /// Reference counts of the retired values, in the same order.
+    ///
+    /// 已退休值的引用计数，顺序相同。
+    refs: Vec<Arc<AtomicUsize>>,
+    /// Values no reader can reach that are not released yet, oldest first. Only a
+    /// budgeted collection leaves any behind.
+    ///
+    /// 读者已无法访问但尚未释放的值，最旧的在前。只有受预算限制的回收才会留下这些值。
+    reclaimed: AllocVec<(usize, Node<T, A>), A>,
+    recycled: Pool<T, A>,
+    alloc: A,
+    auto_reclaim_threshold: Option<usize>,
+    auto_reclaim_budget: Option<ReclaimBudget>,
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static> CountedWriter<T, A> {
+    fn publish(&mut self, node: Node<T, A>) {
+        let (new_ptr, _) = Node::into_raw_with_allocator(node);
+        let (old_ptr, old_version, old_refs) = {
+            let mut current = self.state.current.lock();
+            let old_version = current.version;
+            current.version += 1;
+            self.state
+                .global_version
+                .store(current.version, Ordering::Release);
+            let old_ptr = core::mem::replace(&mut current.ptr, new_ptr);
+            let old_refs = core::mem::replace(&mut current.refs, Arc::new(AtomicUsize::new(0)));
+            (old_ptr, old_version, old_refs)
+        };
+
+        // Safety: the old pointer was replaced, and only the writer retires values.
+        // 安全性：旧指针已被替换，并且只有写者会退休值。
+        self.garbage.push((old_version, unsafe {
+            Node::from_raw_in(old_ptr, self.alloc.clone())
+        }));
+        self.refs.push(old_refs);
+
+        observe::stored(
+            &self.state.metrics,
+            old_version,
+            old_version + 1,
+            self.retired(),
+        );
+
+        if let Some(threshold) = self.auto_reclaim_threshold
+            && self.retired() > threshold
+        {
+            let _span = observe::collect_span(true);
+            self.release_reclaimed(self.auto_reclaim_budget);
+        }
+    }
+
+    /// Number of retired values not yet released, reachable or not.
+    ///
+    /// 尚未释放的已退休值的数量，无论是否可被访问。
+    #[inline]
+    fn retired(&self) -> usize {
+        self.garbage.len() + self.reclaimed.len()
+    }
+
+    /// Collect and release the reclaimed values into the recycle pool until `budget` is spent.
+    ///
+    /// 执行回收，并将回收的值释放到回收池中，直到 `budget` 用完。
+    fn release_reclaimed(&mut self, budget: Option<ReclaimBudget>) -> Collected {
+        self.reclaim();
+        let timer = observe::Timer::start();
+        let count = self.reclaimed.len();
+        let reclaimed = self
+            .recycled
+            .release_within(&mut self.reclaimed, count, budget);
+        observe::collected(&self.state.metrics, reclaimed, self.retired(), timer);
+        Collected {
+            reclaimed,
+            pending: self.reclaimed.len(),
+            remaining: self.retired(),
+        }
+    }
+
+    /// Move every unreferenced retired value except the two newest into `self.reclaimed`,
+    /// keeping their order.
+    ///
+    /// 将除最新两个以外所有未被引用的已退休值按原顺序移入 `self.reclaimed`。
+    fn reclaim(&mut self) {
+        {
+            let readers = self.state.readers.slots.lock();
+            let pinned = readers
+                .iter()
+                .filter(|slot| slot.guards.load(Ordering::Relaxed) > 0)
+                .count();
+            observe::readers_scanned(&self.state.metrics, self.state.readers.in_use(), pinned);
+        }
+
+        // Retired values gain no new references, so a count of zero is final.
+        // 已退休的值不会获得新的引用，因此计数为零就是最终结果。
+        let pending = self.reclaimed.len();
+        let candidates = self.garbage.len().saturating_sub(2);
+        let mut index = 0;
+        for _ in 0..candidates {
+            if self.refs[index].load(Ordering::Acquire) > 0 {
+                index += 1;
+            } else {
+                self.refs.remove(index);
+                self.reclaimed.push(self.garbage.remove(index));
+            }
+        }
+
+        // Values a budgeted collection left behind may be newer than ones released now.
+        // 受预算限制的回收留下的值可能比这次释放的值更新。
+        if pending > 0 {
+            self.reclaimed.sort_unstable_by_key(|(version, _)| *version);
+        }
+    }
+}
+
+impl<T: 'static, A: Allocator> Drop for CountedWriter<T, A> {
+    fn drop(&mut self) {
+        // Readers may still hold guards to retired values, so they are handed over to the
+        // shared state instead of being dropped here.
+        // 读者可能仍持有指向已退休值的守卫，因此将它们移交给共享状态，而不是在此处 drop。
+        let mut orphans = self.state.orphans.lock();
+        for (_, node) in self.garbage.drain(..) {
+            orphans.push(Node::into_raw_with_allocator(node).0);
+        }
+    }
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static> RawWriter<T, A>
+    for CountedWriter<T, A>
+{
+    type Reader = CountedReader<T>;
+    type Shared = CountedShared<T>;
+    type Drain<'a>
+        = Drain<'a, T, A>
+    where
+        Self: 'a;
+
+    fn new(data: T, config: Config, alloc: A) -> Self {
+        let (ptr, _) = Node::into_raw_with_allocator(Node::new_in(data, alloc.clone()));
+        let state = Arc::new(CountedState {
+            current: Mutex::new(Current {
+                ptr,
+                version: config.initial_version,
+                refs: Arc::new(AtomicUsize::new(0)),
+            }),
+            global_version: AtomicUsize::new(config.initial_version),
+            readers: Registry::new(),
+            orphans: Mutex::new(Vec::new()),
+            metrics: observe::Metrics::new(config.name.as_ref(), config.initial_version),
+            alloc: Box::new(alloc.clone()),
+            _marker: PhantomData,
+        });
+
+        Self {
+            state,
+            garbage: AllocVec::new_in(alloc.clone()),
+            refs: Vec::new(),
+            reclaimed: AllocVec::new_in(alloc.clone()),
+            recycled: Pool::new(config.recycle_capacity, alloc.clone()),
+            alloc,
+            auto_reclaim_threshold: config.auto_reclaim_threshold,
+            auto_reclaim_budget: config.auto_reclaim_budget,
+        }
+    }
+
+    #[inline]
+    fn store(&mut self, data: T) {
+        self.publish(Node::new_in(data, self.alloc.clone()));
+    }
+
+    #[inline]
+    fn store_with<F>(&mut self, f: F)
+    where
+        F: FnOnce(Option<T>) -> T,
+    {
+        let node = self.recycled.build_with(f, &self.alloc);
+        self.publish(node);
+    }
+
+    #[inline]
+    fn take_recycled(&mut self) -> Option<T> {
+        self.recycled.take()
+    }
+
+    #[inline]
+    fn recycled_count(&self) -> usize {
+        self.recycled.len()
+    }
+
+    #[inline]
+    fn collect(&mut self) {
+        let _span = observe::collect_span(false);
+        self.release_reclaimed(None);
+    }
+
+    #[inline]
+    fn collect_budget(&mut self, budget: ReclaimBudget) -> Collected {
+        let _span = observe::collect_span(false);
+        self.release_reclaimed(Some(budget))
+    }
+
+    fn collect_into<E: Extend<T>>(&mut self, sink: &mut E) {
+        let _span = observe::collect_span(false);
+        self.reclaim();
+        let timer = observe::Timer::start();
+        let reclaimed = self.reclaimed.len();
+        sink.extend(
+            self.reclaimed
+                .drain(..)
+                .map(|(_, node)| Node::into_inner(node)),
+        );
+        observe::collected(&self.state.metrics, reclaimed, self.garbage.len(), timer);
+    }
+
+    fn drain_reclaimed(&mut self) -> Drain<'_, T, A> {
+        let _span = observe::collect_span(false);
+        self.reclaim();
+        observe::collected(
+            &self.state.metrics,
+            self.reclaimed.len(),
+            self.garbage.len(),
+            observe::Timer::start(),
+        );
+        Drain::new(&mut self.reclaimed, &mut self.recycled)
+    }
+
+    #[inline]
+    fn local(&self) -> CountedReader<T> {
+        CountedReader::new(self.state.clone())
+    }
+
+    #[inline]
+    fn shared(&self) -> CountedShared<T> {
+        CountedShared {
+            state: self.state.clone(),
+        }
+    }
+
+    #[inline]
+    fn get(&self) -> &T {
+        // Safety: only the writer retires the current value.
+        // 安全性：只有写者会退休当前值。
+        unsafe { &*self.state.current.lock().ptr }
+    }
+
+    #[inline]
+    fn previous(&self) -> Option<&T> {
+        self.garbage.last().map(|(_, node)| &**node)
+    }
+
+    #[inline]
+    fn version(&self) -> usize {
+        self.state.global_version.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    fn garbage_count(&self) -> usize {
+        self.retired()
+    }
+
+    #[inline]
+    fn strategy(&self) -> Strategy {
+        Strategy::WritePreferred
+    }
+
+    fn live_readers(&self) -> (usize, usize) {
+        let readers = self.state.readers.slots.lock();
+        let pinned = readers
+            .iter()
+            .filter(|slot| slot.guards.load(Ordering::Relaxed) > 0)
+            .count();
+        (self.state.readers.in_use(), pinned)
+    }
+
+    #[inline]
+    fn reader_slots(&self) -> ReaderSlots {
+        self.state.readers.reader_slots()
+    }
+}
+
+/// A `Sync` handle to the shared state, used to create readers on other threads.
+///
+/// 共享状态的 `Sync` 句柄，用于在其他线程上创建读者。
+pub struct CountedShared<T: 'static> {
+    state: Arc<CountedState<T>>,
+}
+
+impl<T: 'static> Clone for CountedShared<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: 'static> RawShared<T> for CountedShared<T> {
+    type Reader = CountedReader<T>;
+
+    #[inline]
+    fn local(&self) -> CountedReader<T> {
+        CountedReader::new(self.state.clone())
+    }
+}
+
+/// Thread-local reader of the [`RefCounted`](crate::backend::RefCounted) backend.
+///
+/// [`RefCounted`](crate::backend::RefCounted) 后端的线程本地读者。
+pub struct CountedReader<T: 'static> {
+    slot: Arc<CountedSlot>,
+    state: Arc<CountedState<T>>,
+    pinned_version: Cell<usize>,
+}
+
+impl<T: 'static> CountedReader<T> {
+    fn new(state: Arc<CountedState<T>>) -> Self {
+        // A free slot has no guards left.
+        // 空闲槽已经没有守卫。
+        let slot = state.readers.register(
+            &state.metrics,
+            || CountedSlot {
+                guards: AtomicUsize::new(0),
+                in_use: AtomicBool::new(false),
+            },
+            |_| {},
+        );
+
+        Self {
+            slot,
+            state,
+            pinned_version: Cell::new(0),
+        }
+    }
+
+    /// Count one more guard, returning the new number of guards.
+    ///
+    /// 多计一个守卫，返回新的守卫数量。
+    #[inline]
+    fn add_guard(&self) -> usize {
+        // Only this reader writes its counter, so no read-modify-write is needed.
+        // 只有此读者写入其计数器，因此不需要读-改-写操作。
+        let guards = self.slot.guards.load(Ordering::Relaxed) + 1;
+        self.slot.guards.store(guards, Ordering::Relaxed);
+        checks::guard_pinned(guards);
+        guards
+    }
+}
+
+impl<T: 'static> Clone for CountedReader<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.state.clone())
+    }
+}
+
+impl<T: 'static> Drop for CountedReader<T> {
+    #[inline]
+    fn drop(&mut self) {
+        checks::reader_dropped(self.slot.guards.load(Ordering::Relaxed));
+        self.state.readers.release(&self.state.metrics, &self.slot);
+    }
+}
+
+impl<T: 'static> RawReader<T> for CountedReader<T> {
+    type Shared = CountedShared<T>;
+    type Guard<'a> = CountedGuard<'a, T>;
+
+    fn pin(&self) -> CountedGuard<'_, T> {
+        let (ptr, version, refs) = {
+            let current = self.state.current.lock();
+            current.refs.fetch_add(1, Ordering::Relaxed);
+            (current.ptr, current.version, current.refs.clone())
+        };
+
+        if self.add_guard() == 1 {
+            self.pinned_version.set(version);
+        }
+
+        CountedGuard {
+            reader: self,
+            ptr,
+            refs,
+            version: self.pinned_version.get(),
+        }
+    }
+
+    #[inline]
+    fn is_pinned(&self) -> bool {
+        self.slot.guards.load(Ordering::Relaxed) > 0
+    }
+
+    #[inline]
+    fn version(&self) -> usize {
+        self.state.global_version.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    fn share(&self) -> CountedShared<T> {
+        CountedShared {
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Guard of a [`CountedReader`], holding one reference to its value.
+///
+/// [`CountedReader`] 的守卫，持有对其值的一个引用。
+#[must_use]
+pub struct CountedGuard<'a, T: 'static> {
+    reader: &'a CountedReader<T>,
+    ptr: *const T,
+    refs: Arc<AtomicUsize>,
+    version: usize,
+}
+
+impl<T> Deref for CountedGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // Safety: the writer does not reclaim a value while its count is non-zero.
+        // 安全性：值的计数不为零时，写者不会回收它。
+        unsafe { &*self.ptr }
+    }
+}
+
+impl<T> Clone for CountedGuard<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.refs.fetch_add(1, Ordering::Relaxed);
+        self.reader.add_guard();
+
+        CountedGuard {
+            reader: self.reader,
+            ptr: self.ptr,
+            refs: self.refs.clone(),
+            version: self.version,
+        }
+    }
+}
+
+impl<T> Drop for CountedGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // Pairs with the acquire load of the writer, which frees the value at zero.
+        // 与写者的 acquire 加载配对，写者在计数为零时释放该值。
+        self.refs.fetch_sub(1, Ordering::Release);
+        let guards = self.reader.slot.guards.load(Ordering::Relaxed);
+        self.reader.slot.guards.store(guards - 1, Ordering::Relaxed);
+    }
+}
+
+impl<T> RawGuard<T> for CountedGuard<'_, T> {
+    #[inline]
+    fn version(&self) -> usize {
+        self.version
+    }
+}
@@ -0,0 +1,520 @@
+//! Fixed-capacity swap container that never allocates.
+//!
+//! 从不分配内存的固定容量交换容器。
+
+use crate::checks;
+use crate::shim::sync::{AtomicBool, AtomicUsize, Ordering};
+use core::cell::{Cell, UnsafeCell};
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+
+/// Index stored in a reader slot while the reader is not pinned.
+///
+/// 读者未被 pin 时存储在读者槽中的索引。
+const UNPINNED: usize = usize::MAX;
+
+/// A preallocated value slot.
+///
+/// `value`, `version` and `occupied` are only written by the writer, and only while
+/// the slot is neither current nor pinned by a reader.
+///
+/// 预分配的值槽。
+///
+/// `value`、`version` 和 `occupied` 只由写者写入，并且只在该槽既不是当前槽、也没有被读者 pin 时写入。
+struct Slot<T> {
+    value: UnsafeCell<MaybeUninit<T>>,
+    version: UnsafeCell<usize>,
+    occupied: UnsafeCell<bool>,
+}
+
+impl<T> Slot<T> {
+    const fn empty() -> Self {
+        Self {
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+            version: UnsafeCell::new(0),
+            occupied: UnsafeCell::new(false),
+        }
+    }
+}
+
+/// Registration of one reader, announcing the value slot it is pinned to.
+///
+/// 一个读者的注册信息，公布它被 pin 到的值槽。
+struct ReaderSlot {
+    claimed: AtomicBool,
+    pinned: AtomicUsize,
+}
+
+impl ReaderSlot {
+    const fn empty() -> Self {
+        Self {
+            claimed: AtomicBool::new(false),
+            pinned: AtomicUsize::new(UNPINNED),
+        }
+    }
+}
+
+/// A single-writer, multi-reader swap container with `N` preallocated value slots and
+/// at most `R` readers.
+///
+/// `StaticSwap` never allocates: values live inline in the container, which can be
+/// declared in a `static` since [`new`](StaticSwap::new) is a `const fn`. Readers
+/// announce the slot they pin, and the writer only writes into slots that are neither
+/// current nor pinned, so [`try_store`](StaticWriter::try_store) hands the value back
+/// when every slot is still in use. With `N >= R + 2` a store always finds a free slot.
+///
+/// Retired values stay in their slot until it is reused or [`collect`](StaticWriter::collect)
+/// is called. A reader stays pinned to one slot while it holds guards, so nested loads
+/// return the value of the outermost guard.
+///
+/// 具有 `N` 个预分配值槽、最多 `R` 个读者的单写多读交换容器。
+///
+/// `StaticSwap` 从不分配内存：值内联存储在容器中，并且由于 [`new`](StaticSwap::new) 是
+/// `const fn`，容器可以声明在 `static` 中。读者公布它们 pin 的槽，写者只写入既不是当前槽、
+/// 也没有被 pin 的槽，因此当所有槽都仍在使用时，[`try_store`](StaticWriter::try_store) 会交还该值。
+/// 当 `N >= R + 2` 时，存储总能找到空闲槽。
+///
+/// 已退休的值保留在其槽中，直到该槽被复用或调用了 [`collect`](StaticWriter::collect)。
+/// 读者在持有守卫期间保持 pin 在同一个槽上，因此嵌套加载返回最外层守卫的值。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::StaticSwap;
+///
+/// static LEVEL: StaticSwap<u32, 4, 2> = StaticSwap::new(1);
+///
+/// let mut writer = LEVEL.claim_writer().unwrap();
+/// let reader = LEVEL.local().unwrap();
+///
+/// writer.try_store(2).unwrap();
+///
+/// let guard = reader.load();
+/// assert_eq!(*guard, 2);
+/// assert_eq!(guard.version(), 1);
+/// ```
+pub struct StaticSwap<T, const N: usize, const R: usize = 4> {
+    slots: [Slot<T>; N],
+    readers: [ReaderSlot; R],
+    current: AtomicUsize,
+    version: AtomicUsize,
+    writer_claimed: AtomicBool,
+}
+
+// Safety: slot contents are written by the single claimed writer only while no reader
+// can observe them, and readers only hand out `&T`.
+// 安全性：槽内容只由唯一被获取的写者在没有读者能观察到时写入，读者只交出 `&T`。
+unsafe impl<T: Send + Sync, const N: usize, const R: usize> Sync for StaticSwap<T, N, R> {}
+unsafe impl<T: Send, const N: usize, const R: usize> Send for StaticSwap<T, N, R> {}
+
+/// The writer of a [`StaticSwap`], handed out by [`claim_writer`](StaticSwap::claim_writer).
+///
+/// Dropping the writer releases the claim.
+///
+/// [`StaticSwap`] 的写者，由 [`claim_writer`](StaticSwap::claim_writer) 交出。
+///
+/// drop 写者会释放该获取。
+pub struct StaticWriter<'a, T, const N: usize, const R: usize = 4> {
+    swap: &'a StaticSwap<T, N, R>,
+}
+
+/// Reader of a [`StaticSwap`] occupying one of its `R` reader slots.
+///
+/// Like `LocalReader`, it is `Send` but not `Sync`. Dropping it frees the reader slot.
+///
+/// 占用 [`StaticSwap`] 的 `R` 个读者槽之一的读者。
+///
+/// 与 `LocalReader` 一样，它是 `Send` 但不是 `Sync` 的。drop 它会释放读者槽。
+pub struct StaticLocalReader<'a, T, const N: usize, const R: usize = 4> {
+    swap: &'a StaticSwap<T, N, R>,
+    index: usize,
+    pin_count: Cell<usize>,
+}
+
+/// RAII guard for reading a [`StaticSwap`] value.
+///
+/// 用于读取 [`StaticSwap`] 值的 RAII 守卫。
+#[must_use]
+pub struct StaticSwapGuard<'a, T, const N: usize, const R: usize = 4> {
+    reader: &'a StaticLocalReader<'a, T, N, R>,
+    slot: usize,
+}
+
+// ============================================================================
+// StaticSwap implementation
+// ============================================================================
+
+impl<T, const N: usize, const R: usize> StaticSwap<T, N, R> {
+    const CHECK_CAPACITY: () = assert!(N >= 2, "StaticSwap needs at least 2 slots");
+
+    /// Create a new container holding `initial`.
+    ///
+    /// 创建一个持有 `initial` 的新容器。
+    pub const fn new(initial: T) -> Self {
+        let () = Self::CHECK_CAPACITY;
+
+        let mut slots = [const { Slot::empty() }; N];
+        slots[0] = Slot {
+            value: UnsafeCell::new(MaybeUninit::new(initial)),
+            version: UnsafeCell::new(0),
+            occupied: UnsafeCell::new(true),
+        };
+
+        Self {
+            slots,
+            readers: [const { ReaderSlot::empty() }; R],
+            current: AtomicUsize::new(0),
+            version: AtomicUsize::new(0),
+            writer_claimed: AtomicBool::new(false),
+        }
+    }
+
+    /// Claim the single writer of this container.
+    ///
+    /// Returns `None` while another writer is alive.
+    ///
+    /// 获取此容器唯一的写者。
+    ///
+    /// 当另一个写者仍然存活时返回 `None`。
+    #[inline]
+    pub fn claim_writer(&self) -> Option<StaticWriter<'_, T, N, R>> {
+        self.writer_claimed
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| StaticWriter { swap: self })
+    }
+
+    /// Create a new reader, or return `None` if all `R` reader slots are in use.
+    ///
+    /// 创建一个新的读者；如果 `R` 个读者槽都已被使用，则返回 `None`。
+    pub fn local(&self) -> Option<StaticLocalReader<'_, T, N, R>> {
+        let index = self.readers.iter().position(|reader| {
+            reader
+                .claimed
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        })?;
+        Some(StaticLocalReader {
+            swap: self,
+            index,
+            pin_count: Cell::new(0),
+        })
+    }
+
+    /// Get the current global version.
+    ///
+    /// 获取当前全局版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Check whether any reader is pinned to `slot`.
+    ///
+    /// 检查是否有读者被 pin 到 `slot`。
+    #[inline]
+    fn is_pinned(&self, slot: usize) -> bool {
+        self.readers
+            .iter()
+            .any(|reader| reader.pinned.load(Ordering::SeqCst) == slot)
+    }
+}
+
+impl<T, const N: usize, const R: usize> Drop for StaticSwap<T, N, R> {
+    fn drop(&mut self) {
+        for slot in &mut self.slots {
+            if *slot.occupied.get_mut() {
+                // Safety: occupied slots hold an initialized value, and no reader or
+                // writer can borrow the container anymore.
+                // 安全性：被占用的槽持有已初始化的值，并且读者和写者都不能再借用该容器。
+                unsafe { slot.value.get_mut().assume_init_drop() };
+            }
+        }
+    }
+}
+
+// ============================================================================
+// StaticWriter implementation
+// ============================================================================
+
+impl<T, const N: usize, const R: usize> StaticWriter<'_, T, N, R> {
+    /// Store a new value, making it visible to readers.
+    ///
+    /// Returns the value back if every slot other than the current one is still pinned
+    /// by a reader. The previous occupant of the chosen slot is dropped.
+    ///
+    /// 存储新值，使其对读者可见。
+    ///
+    /// 如果除当前槽之外的每个槽都仍被读者 pin，则交还该值。所选槽之前的值会被 drop。
+    pub fn try_store(&mut self, value: T) -> Result<(), T> {
+        let swap = self.swap;
+        let current = swap.current.load(Ordering::Relaxed);
+        let Some(index) = (1..N)
+            .map(|offset| (current + offset) % N)
+            .find(|&index| !swap.is_pinned(index))
+        else {
+            return Err(value);
+        };
+
+        let version = swap.version.load(Ordering::Relaxed) + 1;
+        let slot = &swap.slots[index];
+        // Safety: the slot is not current and no reader is pinned to it. A reader that
+        // announces it from now on sees a different current slot and retries.
+        // 安全性：该槽不是当前槽，且没有读者被 pin 到它。此后公布该槽的读者会看到不同的当前槽并重试。
+        unsafe {
+            if *slot.occupied.get() {
+                (*slot.value.get()).assume_init_drop();
+            }
+            (*slot.value.get()).write(value);
+            *slot.version.get() = version;
+            *slot.occupied.get() = true;
+        }
+
+        swap.version.store(version, Ordering::Release);
+        swap.current.store(index, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Get a reference to the current value (writer-only, no pinning required).
+    ///
+    /// 获取当前值的引用（仅写者可用，无需 pin）。
+    #[inline]
+    pub fn get(&self) -> &T {
+        let slot = &self.swap.slots[self.swap.current.load(Ordering::Relaxed)];
+        // Safety: the current slot is initialized and only the writer replaces it.
+        // 安全性：当前槽已初始化，并且只有写者会替换它。
+        unsafe { (*slot.value.get()).assume_init_ref() }
+    }
+
+    /// Get the current global version.
+    ///
+    /// 获取当前全局版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.swap.version()
+    }
+
+    /// Get the number of retired values still held in their slots.
+    ///
+    /// 获取仍保留在其槽中的已退休值的数量。
+    pub fn garbage_count(&self) -> usize {
+        let current = self.swap.current.load(Ordering::Relaxed);
+        self.swap
+            .slots
+            .iter()
+            .enumerate()
+            // Safety: `occupied` is only written by the writer.
+            // 安全性：`occupied` 只由写者写入。
+            .filter(|&(index, slot)| index != current && unsafe { *slot.occupied.get() })
+            .count()
+    }
+
+    /// Drop every retired value that no reader is pinned to.
+    ///
+    /// 丢弃所有没有读者 pin 的已退休值。
+    pub fn collect(&mut self) {
+        let swap = self.swap;
+        let current = swap.current.load(Ordering::Relaxed);
+        for (index, slot) in swap.slots.iter().enumerate() {
+            if index == current || swap.is_pinned(index) {
+                continue;
+            }
+            // Safety: as in `try_store`, the slot is neither current nor pinned.
+            // 安全性：与 `try_store` 相同，该槽既不是当前槽也没有被 pin。
+            unsafe {
+                if *slot.occupied.get() {
+                    *slot.occupied.get() = false;
+                    (*slot.value.get()).assume_init_drop();
+                }
+            }
+        }
+    }
+}
+
+impl<T, const N: usize, const R: usize> Drop for StaticWriter<'_, T, N, R> {
+    #[inline]
+    fn drop(&mut self) {
+        self.swap.writer_claimed.store(false, Ordering::Release);
+    }
+}
+
+// ============================================================================
+// StaticLocalReader implementation
+// ============================================================================
+
+impl<'a, T, const N: usize, const R: usize> StaticLocalReader<'a, T, N, R> {
+    /// Read the current value with RAII guard.
+    ///
+    /// While this reader already holds a guard, the new guard reads the same slot.
+    ///
+    /// 使用 RAII 守卫读取当前值。
+    ///
+    /// 当此读者已持有守卫时，新守卫读取同一个槽。
+    pub fn load(&self) -> StaticSwapGuard<'_, T, N, R> {
+        let reader = &self.swap.readers[self.index];
+        let pin_count = self.pin_count.get();
+        if pin_count > 0 {
+            self.pin_count.set(pin_count + 1);
+            checks::guard_pinned(pin_count + 1);
+            return StaticSwapGuard {
+                reader: self,
+                slot: reader.pinned.load(Ordering::Relaxed),
+            };
+        }
+
+        let slot = loop {
+            let slot = self.swap.current.load(Ordering::Acquire);
+            reader.pinned.store(slot, Ordering::SeqCst);
+            // The writer publishes a new slot before scanning the pins, so either it sees
+            // our pin or we see its new slot.
+            // 写者在扫描 pin 之前发布新槽，因此要么它看到我们的 pin，要么我们看到它的新槽。
+            if self.swap.current.load(Ordering::SeqCst) == slot {
+                break slot;
+            }
+            core::hint::spin_loop();
+        };
+
+        self.pin_count.set(1);
+        StaticSwapGuard { reader: self, slot }
+    }
+
+    /// Check if this reader is currently pinned.
+    ///
+    /// 检查此读者当前是否被 pin。
+    #[inline]
+    pub fn is_pinned(&self) -> bool {
+        self.pin_count.get() > 0
+    }
+
+    /// Get the current global version.
+    ///
+    /// 获取当前全局版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.swap.version()
+    }
+
+    /// Apply a closure to the current value and return the result.
+    ///
+    /// 对当前值应用闭包并返回结果。
+    #[inline]
+    pub fn map<F, U>(&self, f: F) -> U
+    where
+        F: FnOnce(&T) -> U,
+    {
+        f(&self.load())
+    }
+}
+
+impl<T, const N: usize, const R: usize> Drop for StaticLocalReader<'_, T, N, R> {
+    fn drop(&mut self) {
+        checks::reader_dropped(self.pin_count.get());
+        let reader = &self.swap.readers[self.index];
+        reader.pinned.store(UNPINNED, Ordering::Release);
+        reader.claimed.store(false, Ordering::Release);
+    }
+}
+
+impl<T, const N: usize, const R: usize> fmt::Debug for StaticLocalReader<'_, T, N, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticLocalReader")
+            .field("index", &self.index)
+            .field("is_pinned", &self.is_pinned())
+            .finish()
+    }
+}
+
+// ============================================================================
+// StaticSwapGuard implementation
+// ============================================================================
+
+impl<T, const N: usize, const R: usize> StaticSwapGuard<'_, T, N, R> {
+    /// Get the version of the value this guard reads.
+    ///
+    /// 获取此守卫所读取的值的版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        // Safety: the slot is pinned, so the writer does not touch it.
+        // 安全性：该槽已被 pin，因此写者不会修改它。
+        unsafe { *self.reader.swap.slots[self.slot].version.get() }
+    }
+}
+
+impl<T, const N: usize, const R: usize> Deref for StaticSwapGuard<'_, T, N, R> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        // Safety: the slot was current when it was pinned, so it is initialized, and the
+        // writer does not touch it while it stays pinned.
+        // 安全性：该槽在被 pin 时是当前槽，因此已初始化，并且在保持 pin 期间写者不会修改它。
+        unsafe { (*self.reader.swap.slots[self.slot].value.get()).assume_init_ref() }
+    }
+}
+
+impl<T, const N: usize, const R: usize> Clone for StaticSwapGuard<'_, T, N, R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let pin_count = self.reader.pin_count.get();
+        self.reader.pin_count.set(pin_count + 1);
+        checks::guard_pinned(pin_count + 1);
+        Self {
+            reader: self.reader,
+            slot: self.slot,
+        }
+    }
+}
+
+impl<T, const N: usize, const R: usize> Drop for StaticSwapGuard<'_, T, N, R> {
+    #[inline]
+    fn drop(&mut self) {
+        let pin_count = self.reader.pin_count.get();
+        if pin_count == 1 {
+            self.reader.swap.readers[self.reader.index]
+                .pinned
+                .store(UNPINNED, Ordering::Release);
+        }
+        self.reader.pin_count.set(pin_count - 1);
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, const R: usize> fmt::Debug for StaticSwapGuard<'_, T, N, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticSwapGuard")
+            .field("value", &self.deref())
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+// ============================================================================
+// Standard Trait Implementations
+// 标准 trait 实现
+// ============================================================================
+
+impl<T: Default, const N: usize, const R: usize> Default for StaticSwap<T, N, R> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, const N: usize, const R: usize> fmt::Debug for StaticSwap<T, N, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticSwap")
+            .field("slots", &N)
+            .field("max_readers", &R)
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, const R: usize> fmt::Debug for StaticWriter<'_, T, N, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticWriter")
+            .field("value", self.get())
+            .field("version", &self.version())
+            .finish()
+    }
+}
@@ -0,0 +1,387 @@
+//! Swap containers that can be declared in a `static`.
+//!
+//! 可以在 `static` 中声明的交换容器。
+
+use core::any::Any;
+use core::cell::RefCell;
+use core::fmt;
+use core::mem;
+use core::ops::Deref;
+use std::rc::Rc;
+use std::sync::{Mutex, OnceLock};
+use std::vec::Vec;
+
+use crate::{LocalReader, ReadGuard, SmrReader, SmrSwap};
+
+std::thread_local! {
+    /// Per-thread readers of every `StaticSmrSwap` this thread has read from,
+    /// keyed by the address of the static.
+    ///
+    /// 当前线程读取过的每个 `StaticSmrSwap` 的读取者，以静态变量的地址为键。
+    static LOCAL_READERS: RefCell<Vec<(usize, Rc<dyn Any>)>> = const { RefCell::new(Vec::new()) };
+}
+
+struct State<T: 'static> {
+    reader: SmrReader<T>,
+    writer: Mutex<Option<SmrSwap<T>>>,
+}
+
+/// A swap container that can be declared in a `static`.
+///
+/// `StaticSmrSwap` is `Sync` and can be initialized lazily through
+/// [`lazy`](StaticSmrSwap::lazy) (or the [`smr_static!`](crate::smr_static) macro),
+/// or explicitly through [`init`](StaticSmrSwap::init). Reads go through an implicit
+/// per-thread `LocalReader` that is created on first use and released when the thread exits.
+///
+/// The single writer is an ordinary [`SmrSwap`], handed out exactly once by
+/// [`claim_writer`](StaticSmrSwap::claim_writer).
+///
+/// 可以在 `static` 中声明的交换容器。
+///
+/// `StaticSmrSwap` 是 `Sync` 的，可以通过 [`lazy`](StaticSmrSwap::lazy)
+/// （或 [`smr_static!`](crate::smr_static) 宏）延迟初始化，也可以通过
+/// [`init`](StaticSmrSwap::init) 显式初始化。读取通过隐式的线程级 `LocalReader` 进行，
+/// 它在首次使用时创建，并在线程退出时释放。
+///
+/// 唯一的写者是一个普通的 [`SmrSwap`]，由 [`claim_writer`](StaticSmrSwap::claim_writer) 只交出一次。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::StaticSmrSwap;
+///
+/// static CONFIG: StaticSmrSwap<String> = StaticSmrSwap::new();
+///
+/// CONFIG.init(String::from("v1")).unwrap();
+///
+/// let mut writer = CONFIG.claim_writer().unwrap();
+/// assert!(CONFIG.claim_writer().is_none());
+///
+/// writer.store(String::from("v2"));
+/// std::thread::spawn(|| assert_eq!(*CONFIG.load(), "v2")).join().unwrap();
+/// ```
+pub struct StaticSmrSwap<T: 'static> {
+    state: OnceLock<State<T>>,
+    init: Option<fn() -> T>,
+}
+
+impl<T: 'static> StaticSmrSwap<T> {
+    /// Create an uninitialized container.
+    ///
+    /// It must be initialized with [`init`](StaticSmrSwap::init) before it is read.
+    ///
+    /// 创建一个未初始化的容器。
+    ///
+    /// 在读取之前必须通过 [`init`](StaticSmrSwap::init) 初始化。
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            state: OnceLock::new(),
+            init: None,
+        }
+    }
+
+    /// Create a container that is initialized with `init` on first access.
+    ///
+    /// 创建一个在首次访问时使用 `init` 初始化的容器。
+    #[inline]
+    pub const fn lazy(init: fn() -> T) -> Self {
+        Self {
+            state: OnceLock::new(),
+            init: Some(init),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> StaticSmrSwap<T> {
+    #[inline]
+    fn build(value: T) -> State<T> {
+        let swap = SmrSwap::new(value);
+        State {
+            reader: swap.reader(),
+            writer: Mutex::new(Some(swap)),
+        }
+    }
+
+    #[inline]
+    fn state(&self) -> Option<&State<T>> {
+        match self.init {
+            Some(init) => Some(self.state.get_or_init(|| Self::build(init()))),
+            None => self.state.get(),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    fn initialized_state(&self) -> &State<T> {
+        match self.state() {
+            Some(state) => state,
+            None => panic!("StaticSmrSwap was read before `init` was called"),
+        }
+    }
+
+    /// Initialize the container with `value`.
+    ///
+    /// Returns `Err(value)` if the container has already been initialized,
+    /// either by a previous `init` or lazily.
+    ///
+    /// 使用 `value` 初始化容器。
+    ///
+    /// 如果容器已经被初始化（通过之前的 `init` 或延迟初始化），则返回 `Err(value)`。
+    pub fn init(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.state
+            .get_or_init(|| Self::build(value.take().expect("value is only taken once")));
+        match value {
+            Some(value) => Err(value),
+            None => Ok(()),
+        }
+    }
+
+    /// Check whether the container has been initialized.
+    ///
+    /// 检查容器是否已被初始化。
+    #[inline]
+    pub fn is_initialized(&self) -> bool {
+        self.state.get().is_some()
+    }
+
+    /// Claim the single writer of this container.
+    ///
+    /// Returns the writer on the first call and `None` on every later call.
+    /// Returns `None` if the container is neither initialized nor lazy.
+    ///
+    /// 获取此容器唯一的写者。
+    ///
+    /// 第一次调用时返回写者，之后的每次调用都返回 `None`。
+    /// 如果容器既未初始化也不是延迟初始化的，则返回 `None`。
+    pub fn claim_writer(&self) -> Option<SmrSwap<T>> {
+        self.state()?
+            .writer
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
+    /// Create a `SmrReader` for this container.
+    ///
+    /// 为此容器创建一个 `SmrReader`。
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container is neither initialized nor lazy.
+    ///
+    /// 如果容器既未初始化也不是延迟初始化的，则 panic。
+    #[inline]
+    #[track_caller]
+    pub fn reader(&self) -> SmrReader<T> {
+        self.initialized_state().reader.clone()
+    }
+
+    /// Create a new explicit `LocalReader` for the current thread.
+    ///
+    /// 为当前线程创建一个新的显式 `LocalReader`。
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container is neither initialized nor lazy.
+    ///
+    /// 如果容器既未初始化也不是延迟初始化的，则 panic。
+    #[inline]
+    #[track_caller]
+    pub fn local(&self) -> LocalReader<T> {
+        self.initialized_state().reader.local()
+    }
+
+    /// Get the implicit `LocalReader` of the current thread, creating it on first use.
+    ///
+    /// 获取当前线程的隐式 `LocalReader`，首次使用时创建。
+    #[track_caller]
+    fn thread_reader(&'static self) -> Rc<LocalReader<T>> {
+        let state = self.initialized_state();
+        let key = self as *const Self as usize;
+
+        let cached = LOCAL_READERS.try_with(|readers| {
+            let mut readers = readers.borrow_mut();
+            if let Some((_, reader)) = readers.iter().find(|(k, _)| *k == key) {
+                return Rc::clone(reader);
+            }
+            let reader: Rc<dyn Any> = Rc::new(state.reader.local());
+            readers.push((key, Rc::clone(&reader)));
+            reader
+        });
+
+        match cached {
+            Ok(reader) => Rc::downcast(reader).expect("reader registered under a unique key"),
+            // The thread-local storage is being destroyed; fall back to a temporary reader.
+            // 线程本地存储正在被销毁；退回到临时读取者。
+            Err(_) => Rc::new(state.reader.local()),
+        }
+    }
+
+    /// Read the current value through the implicit per-thread reader.
+    ///
+    /// 通过隐式的线程级读取者读取当前值。
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container is neither initialized nor lazy.
+    ///
+    /// 如果容器既未初始化也不是延迟初始化的，则 panic。
+    #[inline]
+    #[track_caller]
+    pub fn load(&'static self) -> StaticReadGuard<T> {
+        let reader = self.thread_reader();
+        let guard = reader.load();
+        // Safety: the guard borrows the reader behind the `Rc`, whose address is stable.
+        // `StaticReadGuard` keeps the `Rc` alive and drops the guard before it.
+        // 安全性：守卫借用 `Rc` 后面的读取者，其地址是稳定的。
+        // `StaticReadGuard` 保持 `Rc` 存活，并在它之前 drop 守卫。
+        let guard = unsafe { mem::transmute::<ReadGuard<'_, T>, ReadGuard<'static, T>>(guard) };
+        StaticReadGuard {
+            guard,
+            _reader: reader,
+        }
+    }
+
+    /// Apply a closure to the current value through the implicit per-thread reader.
+    ///
+    /// 通过隐式的线程级读取者对当前值应用闭包。
+    #[inline]
+    #[track_caller]
+    pub fn map<F, U>(&'static self, f: F) -> U
+    where
+        F: FnOnce(&T) -> U,
+    {
+        self.thread_reader().map(f)
+    }
+
+    /// Load the current value and clone it.
+    ///
+    /// 加载当前值并克隆它。
+    #[inline]
+    #[track_caller]
+    pub fn load_cloned(&'static self) -> T
+    where
+        T: Clone,
+    {
+        self.map(T::clone)
+    }
+
+    /// Get the current global version.
+    ///
+    /// 获取当前全局版本。
+    #[inline]
+    #[track_caller]
+    pub fn version(&'static self) -> usize {
+        self.thread_reader().version()
+    }
+}
+
+impl<T: 'static> Default for StaticSmrSwap<T> {
+    /// Create an uninitialized container.
+    ///
+    /// 创建一个未初始化的容器。
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> fmt::Debug for StaticSmrSwap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticSmrSwap")
+            .field("initialized", &self.state.get().is_some())
+            .finish()
+    }
+}
+
+/// RAII guard returned by [`StaticSmrSwap::load`].
+///
+/// Holds the implicit per-thread reader alive for as long as the value is pinned.
+/// It is `!Send`, like the reader it borrows from.
+///
+/// [`StaticSmrSwap::load`] 返回的 RAII 守卫。
+///
+/// 在值被 pin 期间保持隐式的线程级读取者存活。它与所借用的读取者一样是 `!Send` 的。
+pub struct StaticReadGuard<T: 'static> {
+    // Declared before `_reader` so that it is dropped first.
+    // 在 `_reader` 之前声明，以便先被 drop。
+    guard: ReadGuard<'static, T>,
+    _reader: Rc<LocalReader<T>>,
+}
+
+impl<T: 'static> StaticReadGuard<T> {
+    /// Get the version that this guard is pinned to.
+    ///
+    /// 获取此守卫被 pin 到的版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.guard.version()
+    }
+}
+
+impl<T: 'static> Deref for StaticReadGuard<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T: 'static> AsRef<T> for StaticReadGuard<T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T: fmt::Debug + 'static> fmt::Debug for StaticReadGuard<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticReadGuard")
+            .field("value", &self.deref())
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+/// Declare lazily initialized [`StaticSmrSwap`] statics.
+///
+/// The initializer runs on first access, from whichever thread gets there first.
+///
+/// 声明延迟初始化的 [`StaticSmrSwap`] 静态变量。
+///
+/// 初始化表达式在首次访问时运行，由最先访问的线程执行。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::smr_static;
+///
+/// smr_static! {
+///     static LIMITS: Vec<u32> = vec![10, 20];
+///     pub(crate) static NAME: String = String::from("service");
+/// }
+///
+/// assert_eq!(LIMITS.load().len(), 2);
+///
+/// let mut writer = NAME.claim_writer().unwrap();
+/// writer.store(String::from("renamed"));
+/// assert_eq!(*NAME.load(), "renamed");
+/// ```
+#[macro_export]
+macro_rules! smr_static {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr;)+) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::StaticSmrSwap<$ty> = $crate::StaticSmrSwap::lazy({
+                fn __smr_static_init() -> $ty {
+                    $init
+                }
+                __smr_static_init
+            });
+        )+
+    };
+}
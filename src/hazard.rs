@@ -0,0 +1,697 @@
+//! Hazard-pointer reclamation backend.
+//!
+//! Every reader owns a record of hazard slots. A load announces the pointer it is about
+//! to read in a free slot, fences, and re-reads the current pointer to make sure it was
+//! not retired in between. The writer fences after retiring values, collects every
+//! announced pointer, and reclaims the retired values that no slot points to.
+//!
+//! Every node carries the version it was published in, so a guard reports the version of
+//! the value it protects without any further synchronization with the writer.
+//!
+//! Nested loads reuse the slot that already protects the current value, and take a new
+//! slot only when the value changed in between, so a reader needs as many slots as the
+//! number of distinct values it holds at once.
+//!
+//! 风险指针回收后端。
+//!
+//! 每个读者拥有一条由风险槽组成的记录。加载时会在空闲槽中公布即将读取的指针，执行 fence，
+//! 然后重新读取当前指针以确保它在此期间没有退休。写者在退休值之后执行 fence，收集所有已公布的指针，
+//! 并回收没有任何槽指向的已退休值。
+//!
+//! 每个节点都携带其发布时的版本，因此守卫无需与写者进一步同步即可报告其保护的值的版本。
+//!
+//! 嵌套加载会复用已经保护当前值的槽，只有在值发生变化时才会占用新槽，
+//! 因此读者需要的槽数等于其同时持有的不同值的数量。
+
+use core::cell::RefCell;
+use core::iter::Map;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr;
+
+use allocator_api2::alloc::{Allocator, Global};
+use allocator_api2::vec::Vec as AllocVec;
+
+use crate::Strategy;
+use crate::backend::{
+    Collected, Config, Drain, FreeNode, Node, Pool, RawGuard, RawReader, RawShared, RawWriter,
+    ReaderSlots, ReclaimBudget, Registry, RegistrySlot,
+};
+use crate::checks;
+use crate::observe;
+use crate::shim::{
+    Arc, AtomicBool, AtomicPtr, AtomicUsize, Box, Cell, Mutex, Ordering, Vec, heavy_barrier,
+    light_barrier,
+};
+
+/// A value with the version in which it was published.
+///
+/// 一个值及其发布时的版本。
+pub struct Stamped<T> {
+    version: usize,
+    value: T,
+}
+
+/// Strip the stamp off a drained value.
+///
+/// 去掉被取出的值的版本标记。
+#[inline]
+fn unstamp<T>((version, stamped): (usize, Stamped<T>)) -> (usize, T) {
+    (version, stamped.value)
+}
+
+/// A pointer announced by a reader.
+///
+/// 读者公布的指针。
+#[repr(align(64))]
+struct HazardSlot<T> {
+    ptr: AtomicPtr<Stamped<T>>,
+}
+
+/// The hazard slots of one reader.
+///
+/// Only the owning reader adds slots, and only the writer scans them, so the lock is
+/// never contended on the read path. The next reader of the record takes its slots over.
+///
+/// 一个读者的风险槽。
+///
+/// 只有所属读者会添加槽，也只有写者会扫描它们，因此读取路径上的锁永远不会发生争用。
+/// 该记录的下一个读者会接管其槽。
+struct HazardRecord<T> {
+    slots: Mutex<Vec<Arc<HazardSlot<T>>>>,
+    in_use: AtomicBool,
+}
+
+impl<T> RegistrySlot for HazardRecord<T> {
+    #[inline]
+    fn in_use(&self) -> &AtomicBool {
+        &self.in_use
+    }
+}
+
+/// State shared between the writer and all readers.
+///
+/// 写者与所有读者共享的状态。
+struct HazardState<T: 'static> {
+    global_version: AtomicUsize,
+    ptr: AtomicPtr<Stamped<T>>,
+    readers: Registry<HazardRecord<T>>,
+    /// Retired values left behind by a dropped writer, freed with the state.
+    ///
+    /// 被 drop 的写者遗留的已退休值，随状态一起释放。
+    orphans: Mutex<Vec<*mut Stamped<T>>>,
+    metrics: observe::Metrics,
+    alloc: Box<dyn FreeNode<Stamped<T>>>,
+    // Readers hand out `&T` on any thread and the last handle may drop the value.
+    // 读者会在任意线程上交出 `&T`，最后一个句柄可能会 drop 该值。
+    _marker: PhantomData<T>,
+}
+
+// Safety: the orphaned pointers are owned by the state and only freed when it drops,
+// so the state is as thread-safe as the values it owns.
+// 安全性：被遗留的指针由状态拥有，并且只在状态被 drop 时释放，因此状态的线程安全性与其拥有的值相同。
+unsafe impl<T: Send + Sync> Send for HazardState<T> {}
+unsafe impl<T: Send + Sync> Sync for HazardState<T> {}
+
+impl<T: 'static> Drop for HazardState<T> {
+    fn drop(&mut self) {
+        let ptr = self.ptr.load(Ordering::Acquire);
+        let orphans = core::mem::take(&mut *self.orphans.lock());
+        for ptr in orphans.into_iter().chain((!ptr.is_null()).then_some(ptr)) {
+            // Safety: no reader is left, and every pointer is a node owned by the state.
+            // 安全性：已经没有读者了，并且每个指针都是由状态拥有的节点。
+            unsafe { self.alloc.free(ptr) };
+        }
+    }
+}
+
+/// Writer side of the [`HazardPointers`](crate::backend::HazardPointers) backend.
+///
+/// [`HazardPointers`](crate::backend::HazardPointers) 后端的写者端。
+pub struct HazardWriter<T: 'static, A: Allocator = Global> {
+    state: Arc<HazardState<T>>,
+    /// Retired values tagged with the last version in which they were current, oldest first.
+    ///
+    /// 已退休的值，以它们作为当前值的最后版本标记，最旧的在前。
+    garbage: AllocVec<(usize, Node<Stamped<T>, A>), A>,
+    /// Values no reader can reach that are not released yet, oldest first. Only a
+    /// budgeted collection leaves any behind.
+    ///
+    /// 读者已无法访问但尚未释放的值，最旧的在前。只有受预算限制的回收才会留下这些值。
+    reclaimed: AllocVec<(usize, Node<Stamped<T>, A>), A>,
+    recycled: Pool<Stamped<T>, A>,
+    alloc: A,
+    auto_reclaim_threshold: Option<usize>,
+    auto_reclaim_budget: Option<ReclaimBudget>,
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static> HazardWriter<T, A> {
+    /// Version the next published value is stamped with.
+    ///
+    /// 下一个发布的值所标记的版本。
+    #[inline]
+    fn next_version(&self) -> usize {
+        // Only the writer bumps the version.
+        // 只有写者会递增版本。
+        self.state.global_version.load(Ordering::Relaxed) + 1
+    }
+
+    fn publish(&mut self, node: Node<Stamped<T>, A>) {
+        let (new_ptr, _) = Node::into_raw_with_allocator(node);
+        let old_ptr = self.state.ptr.swap(new_ptr, Ordering::AcqRel);
+        let old_version = self.state.global_version.fetch_add(1, Ordering::AcqRel);
+
+        // Safety: the old pointer was swapped out, and only the writer retires values.
+        // 安全性：旧指针已被换出，并且只有写者会退休值。
+        self.garbage.push((old_version, unsafe {
+            Node::from_raw_in(old_ptr, self.alloc.clone())
+        }));
+
+        observe::stored(
+            &self.state.metrics,
+            old_version,
+            old_version + 1,
+            self.retired(),
+        );
+
+        if let Some(threshold) = self.auto_reclaim_threshold
+            && self.retired() > threshold
+        {
+            let _span = observe::collect_span(true);
+            self.release_reclaimed(self.auto_reclaim_budget);
+        }
+    }
+
+    /// Number of retired values not yet released, reachable or not.
+    ///
+    /// 尚未释放的已退休值的数量，无论是否可被访问。
+    #[inline]
+    fn retired(&self) -> usize {
+        self.garbage.len() + self.reclaimed.len()
+    }
+
+    /// Collect and release the reclaimed values into the recycle pool until `budget` is spent.
+    ///
+    /// 执行回收，并将回收的值释放到回收池中，直到 `budget` 用完。
+    fn release_reclaimed(&mut self, budget: Option<ReclaimBudget>) -> Collected {
+        self.reclaim();
+        let timer = observe::Timer::start();
+        let count = self.reclaimed.len();
+        let reclaimed = self
+            .recycled
+            .release_within(&mut self.reclaimed, count, budget);
+        observe::collected(&self.state.metrics, reclaimed, self.retired(), timer);
+        Collected {
+            reclaimed,
+            pending: self.reclaimed.len(),
+            remaining: self.retired(),
+        }
+    }
+
+    /// Scan the hazards and move every unprotected retired value except the two newest
+    /// into `self.reclaimed`, keeping their order.
+    ///
+    /// 扫描风险指针，并将除最新两个以外所有未受保护的已退休值按原顺序移入 `self.reclaimed`。
+    fn reclaim(&mut self) {
+        // Pairs with the fence of readers: either a reader sees the retired pointer
+        // replaced and retries, or the scan below sees its hazard.
+        // 与读者的 fence 配对：要么读者看到已退休的指针被替换并重试，要么下面的扫描看到其风险指针。
+        heavy_barrier(Strategy::WritePreferred);
+
+        let mut hazards = Vec::new();
+        let readers = self.state.readers.slots.lock();
+
+        let mut pinned = 0;
+        for record in readers.iter() {
+            let len = hazards.len();
+            hazards.extend(
+                record
+                    .slots
+                    .lock()
+                    .iter()
+                    .map(|slot| slot.ptr.load(Ordering::Acquire))
+                    .filter(|ptr| !ptr.is_null()),
+            );
+            pinned += usize::from(hazards.len() > len);
+        }
+        drop(readers);
+        observe::readers_scanned(&self.state.metrics, self.state.readers.in_use(), pinned);
+
+        // The two newest retired values are always kept, as with the versioned backend.
+        // 与基于版本的后端一样，最新的两个已退休值总是被保留。
+        let pending = self.reclaimed.len();
+        let candidates = self.garbage.len().saturating_sub(2);
+        let mut index = 0;
+        for _ in 0..candidates {
+            let ptr = ptr::from_ref::<Stamped<T>>(&*self.garbage[index].1).cast_mut();
+            if hazards.contains(&ptr) {
+                index += 1;
+            } else {
+                self.reclaimed.push(self.garbage.remove(index));
+            }
+        }
+
+        // Values a budgeted collection left behind may be newer than ones released now.
+        // 受预算限制的回收留下的值可能比这次释放的值更新。
+        if pending > 0 {
+            self.reclaimed.sort_unstable_by_key(|(version, _)| *version);
+        }
+    }
+}
+
+impl<T: 'static, A: Allocator> Drop for HazardWriter<T, A> {
+    fn drop(&mut self) {
+        // Readers may still hold guards to retired values, so they are handed over to the
+        // shared state instead of being dropped here.
+        // 读者可能仍持有指向已退休值的守卫，因此将它们移交给共享状态，而不是在此处 drop。
+        let mut orphans = self.state.orphans.lock();
+        for (_, node) in self.garbage.drain(..) {
+            orphans.push(Node::into_raw_with_allocator(node).0);
+        }
+    }
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static> RawWriter<T, A>
+    for HazardWriter<T, A>
+{
+    type Reader = HazardReader<T>;
+    type Shared = HazardShared<T>;
+    type Drain<'a>
+        = Map<Drain<'a, Stamped<T>, A>, fn((usize, Stamped<T>)) -> (usize, T)>
+    where
+        Self: 'a;
+
+    fn new(data: T, config: Config, alloc: A) -> Self {
+        let node = Node::new_in(
+            Stamped {
+                version: config.initial_version,
+                value: data,
+            },
+            alloc.clone(),
+        );
+        let (ptr, _) = Node::into_raw_with_allocator(node);
+        let state = Arc::new(HazardState {
+            global_version: AtomicUsize::new(config.initial_version),
+            ptr: AtomicPtr::new(ptr),
+            readers: Registry::new(),
+            orphans: Mutex::new(Vec::new()),
+            metrics: observe::Metrics::new(config.name.as_ref(), config.initial_version),
+            alloc: Box::new(alloc.clone()),
+            _marker: PhantomData,
+        });
+
+        Self {
+            state,
+            garbage: AllocVec::new_in(alloc.clone()),
+            reclaimed: AllocVec::new_in(alloc.clone()),
+            recycled: Pool::new(config.recycle_capacity, alloc.clone()),
+            alloc,
+            auto_reclaim_threshold: config.auto_reclaim_threshold,
+            auto_reclaim_budget: config.auto_reclaim_budget,
+        }
+    }
+
+    #[inline]
+    fn store(&mut self, data: T) {
+        let stamped = Stamped {
+            version: self.next_version(),
+            value: data,
+        };
+        self.publish(Node::new_in(stamped, self.alloc.clone()));
+    }
+
+    #[inline]
+    fn store_with<F>(&mut self, f: F)
+    where
+        F: FnOnce(Option<T>) -> T,
+    {
+        let version = self.next_version();
+        let node = self.recycled.build_with(
+            |old| Stamped {
+                version,
+                value: f(old.map(|old| old.value)),
+            },
+            &self.alloc,
+        );
+        self.publish(node);
+    }
+
+    #[inline]
+    fn take_recycled(&mut self) -> Option<T> {
+        self.recycled.take().map(|stamped| stamped.value)
+    }
+
+    #[inline]
+    fn recycled_count(&self) -> usize {
+        self.recycled.len()
+    }
+
+    #[inline]
+    fn collect(&mut self) {
+        let _span = observe::collect_span(false);
+        self.release_reclaimed(None);
+    }
+
+    #[inline]
+    fn collect_budget(&mut self, budget: ReclaimBudget) -> Collected {
+        let _span = observe::collect_span(false);
+        self.release_reclaimed(Some(budget))
+    }
+
+    fn collect_into<E: Extend<T>>(&mut self, sink: &mut E) {
+        let _span = observe::collect_span(false);
+        self.reclaim();
+        let timer = observe::Timer::start();
+        let reclaimed = self.reclaimed.len();
+        sink.extend(
+            self.reclaimed
+                .drain(..)
+                .map(|(_, node)| Node::into_inner(node).value),
+        );
+        observe::collected(&self.state.metrics, reclaimed, self.garbage.len(), timer);
+    }
+
+    fn drain_reclaimed(&mut self) -> Self::Drain<'_> {
+        let _span = observe::collect_span(false);
+        self.reclaim();
+        observe::collected(
+            &self.state.metrics,
+            self.reclaimed.len(),
+            self.garbage.len(),
+            observe::Timer::start(),
+        );
+        Drain::new(&mut self.reclaimed, &mut self.recycled).map(unstamp as fn(_) -> _)
+    }
+
+    #[inline]
+    fn local(&self) -> HazardReader<T> {
+        HazardReader::new(self.state.clone())
+    }
+
+    #[inline]
+    fn shared(&self) -> HazardShared<T> {
+        HazardShared {
+            state: self.state.clone(),
+        }
+    }
+
+    #[inline]
+    fn get(&self) -> &T {
+        // Safety: only the writer retires the current value.
+        // 安全性：只有写者会退休当前值。
+        unsafe { &(*self.state.ptr.load(Ordering::Acquire)).value }
+    }
+
+    #[inline]
+    fn previous(&self) -> Option<&T> {
+        self.garbage.last().map(|(_, node)| &node.value)
+    }
+
+    #[inline]
+    fn version(&self) -> usize {
+        self.state.global_version.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    fn garbage_count(&self) -> usize {
+        self.retired()
+    }
+
+    #[inline]
+    fn strategy(&self) -> Strategy {
+        Strategy::WritePreferred
+    }
+
+    fn live_readers(&self) -> (usize, usize) {
+        let readers = self.state.readers.slots.lock();
+        let pinned = readers
+            .iter()
+            .filter(|record| {
+                record
+                    .slots
+                    .lock()
+                    .iter()
+                    .any(|slot| !slot.ptr.load(Ordering::Acquire).is_null())
+            })
+            .count();
+        (self.state.readers.in_use(), pinned)
+    }
+
+    #[inline]
+    fn reader_slots(&self) -> ReaderSlots {
+        self.state.readers.reader_slots()
+    }
+}
+
+/// A `Sync` handle to the shared state, used to create readers on other threads.
+///
+/// 共享状态的 `Sync` 句柄，用于在其他线程上创建读者。
+pub struct HazardShared<T: 'static> {
+    state: Arc<HazardState<T>>,
+}
+
+impl<T: 'static> Clone for HazardShared<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: 'static> RawShared<T> for HazardShared<T> {
+    type Reader = HazardReader<T>;
+
+    #[inline]
+    fn local(&self) -> HazardReader<T> {
+        HazardReader::new(self.state.clone())
+    }
+}
+
+/// A hazard slot owned by a reader, with the number of its guards using it.
+///
+/// 读者拥有的风险槽，以及正在使用它的守卫数量。
+struct Hazard<T> {
+    slot: Arc<HazardSlot<T>>,
+    guards: usize,
+}
+
+/// Thread-local reader of the [`HazardPointers`](crate::backend::HazardPointers) backend.
+///
+/// [`HazardPointers`](crate::backend::HazardPointers) 后端的线程本地读者。
+pub struct HazardReader<T: 'static> {
+    record: Arc<HazardRecord<T>>,
+    hazards: RefCell<Vec<Hazard<T>>>,
+    state: Arc<HazardState<T>>,
+    pin_count: Cell<usize>,
+    pinned_version: Cell<usize>,
+}
+
+impl<T: 'static> HazardReader<T> {
+    fn new(state: Arc<HazardState<T>>) -> Self {
+        let record = state.readers.register(
+            &state.metrics,
+            || HazardRecord {
+                slots: Mutex::new(Vec::new()),
+                in_use: AtomicBool::new(false),
+            },
+            |_| {},
+        );
+        // The previous reader of the record cleared every slot when its last guard went away.
+        // 该记录的上一个读者在其最后一个守卫消失时已清空每个槽。
+        let hazards = record
+            .slots
+            .lock()
+            .iter()
+            .map(|slot| Hazard {
+                slot: Arc::clone(slot),
+                guards: 0,
+            })
+            .collect();
+
+        Self {
+            record,
+            hazards: RefCell::new(hazards),
+            state,
+            pin_count: Cell::new(0),
+            pinned_version: Cell::new(0),
+        }
+    }
+
+    /// Announce the current pointer in `slot` until it is still current after the fence.
+    ///
+    /// Retries only when a store replaced the pointer in between, so a preempted writer
+    /// never blocks readers.
+    ///
+    /// 在 `slot` 中公布当前指针，直到它在 fence 之后仍是当前值。
+    ///
+    /// 只有当存储在此期间替换了指针时才会重试，因此被抢占的写者永远不会阻塞读者。
+    fn protect(&self, slot: &HazardSlot<T>) -> *mut Stamped<T> {
+        let mut ptr = self.state.ptr.load(Ordering::Acquire);
+        loop {
+            slot.ptr.store(ptr, Ordering::Relaxed);
+            light_barrier(Strategy::WritePreferred);
+
+            // The value is protected once it is still current after the fence.
+            // 如果值在 fence 之后仍然是当前值，它就受到了保护。
+            let current = self.state.ptr.load(Ordering::Acquire);
+            if current == ptr {
+                return ptr;
+            }
+            ptr = current;
+        }
+    }
+
+    /// Index of a hazard that no guard uses, adding a slot to the record if there is none.
+    ///
+    /// 没有守卫使用的风险槽的索引；如果没有，则向记录中添加一个槽。
+    fn free_hazard(&self, hazards: &mut Vec<Hazard<T>>) -> usize {
+        if let Some(index) = hazards.iter().position(|hazard| hazard.guards == 0) {
+            return index;
+        }
+
+        let slot = Arc::new(HazardSlot {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+        });
+        self.record.slots.lock().push(Arc::clone(&slot));
+        hazards.push(Hazard { slot, guards: 0 });
+        hazards.len() - 1
+    }
+}
+
+impl<T: 'static> Clone for HazardReader<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.state.clone())
+    }
+}
+
+impl<T: 'static> Drop for HazardReader<T> {
+    #[inline]
+    fn drop(&mut self) {
+        checks::reader_dropped(self.pin_count.get());
+        self.state
+            .readers
+            .release(&self.state.metrics, &self.record);
+    }
+}
+
+impl<T: 'static> RawReader<T> for HazardReader<T> {
+    type Shared = HazardShared<T>;
+    type Guard<'a> = HazardGuard<'a, T>;
+
+    fn pin(&self) -> HazardGuard<'_, T> {
+        let mut hazards = self.hazards.borrow_mut();
+        let ptr = self.state.ptr.load(Ordering::Acquire);
+
+        // A value already protected by an outer guard needs no new hazard, and the guard
+        // reports the version the outer guard pinned.
+        // 已经被外层守卫保护的值不需要新的风险指针，守卫报告外层守卫 pin 的版本。
+        let (index, ptr, first) = match hazards
+            .iter()
+            .position(|hazard| hazard.guards > 0 && hazard.slot.ptr.load(Ordering::Relaxed) == ptr)
+        {
+            Some(index) => (index, ptr, false),
+            None => {
+                let index = self.free_hazard(&mut hazards);
+                let ptr = self.protect(&hazards[index].slot);
+                (index, ptr, true)
+            }
+        };
+        hazards[index].guards += 1;
+
+        let pin_count = self.pin_count.get() + 1;
+        self.pin_count.set(pin_count);
+        checks::guard_pinned(pin_count);
+        // A first pin has no outer guard, so it always announced a new hazard.
+        // 第一次 pin 没有外层守卫，因此它总是公布了新的风险指针。
+        if pin_count == 1 && first {
+            // Safety: the hazard protects the node.
+            // 安全性：风险指针保护着该节点。
+            self.pinned_version.set(unsafe { (*ptr).version });
+        }
+
+        HazardGuard {
+            reader: self,
+            index,
+            ptr,
+            version: self.pinned_version.get(),
+        }
+    }
+
+    #[inline]
+    fn is_pinned(&self) -> bool {
+        self.pin_count.get() > 0
+    }
+
+    #[inline]
+    fn version(&self) -> usize {
+        self.state.global_version.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    fn share(&self) -> HazardShared<T> {
+        HazardShared {
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Guard of a [`HazardReader`], dereferencing to the value its hazard protects.
+///
+/// [`HazardReader`] 的守卫，解引用为其风险指针保护的值。
+#[must_use]
+pub struct HazardGuard<'a, T: 'static> {
+    reader: &'a HazardReader<T>,
+    index: usize,
+    ptr: *const Stamped<T>,
+    version: usize,
+}
+
+impl<T> Deref for HazardGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // Safety: the hazard announcing `ptr` stays set while the guard is alive.
+        // 安全性：守卫存活期间，公布 `ptr` 的风险指针会保持设置。
+        unsafe { &(*self.ptr).value }
+    }
+}
+
+impl<T> Clone for HazardGuard<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.reader.hazards.borrow_mut()[self.index].guards += 1;
+        let pin_count = self.reader.pin_count.get() + 1;
+        self.reader.pin_count.set(pin_count);
+        checks::guard_pinned(pin_count);
+
+        HazardGuard {
+            reader: self.reader,
+            index: self.index,
+            ptr: self.ptr,
+            version: self.version,
+        }
+    }
+}
+
+impl<T> Drop for HazardGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        let mut hazards = self.reader.hazards.borrow_mut();
+        let hazard = &mut hazards[self.index];
+        hazard.guards -= 1;
+        if hazard.guards == 0 {
+            hazard.slot.ptr.store(ptr::null_mut(), Ordering::Release);
+        }
+        self.reader.pin_count.set(self.reader.pin_count.get() - 1);
+    }
+}
+
+impl<T> RawGuard<T> for HazardGuard<'_, T> {
+    #[inline]
+    fn version(&self) -> usize {
+        self.version
+    }
+}
@@ -3,7 +3,8 @@
 //! A minimal locking, version-based concurrent swap library.
 //!
 //! This library provides a mechanism to swap values atomically while allowing concurrent readers
-//! to access the old value until they are done. It uses `swmr-cell` for version-based garbage collection.
+//! to access the old value until they are done. It uses version-based garbage collection: readers
+//! announce the version they pinned, and retired values are reclaimed once no reader can observe them.
 //!
 //! # Example
 //!
@@ -29,12 +30,89 @@
 //! handle.join().unwrap();
 //! ```
 
+extern crate alloc;
+
+use alloc::borrow::Cow;
 use core::fmt;
+use core::marker::PhantomData;
 use core::ops::Deref;
-use swmr_cell::SwmrCell;
 
-// Re-export for backward compatibility
-pub use swmr_cell::{LocalReader as CellLocalReader, PinGuard, SwmrReader as CellSwmrReader};
+use crate::backend::{Config, RawGuard, RawReader, RawShared, RawWriter};
+
+pub mod backend;
+mod boxed;
+mod cell;
+mod checks;
+#[cfg(feature = "compat")]
+pub mod compat;
+mod copy;
+mod counted;
+mod fixed;
+#[cfg(feature = "std")]
+mod global;
+mod hazard;
+mod log;
+#[cfg(feature = "std")]
+mod map;
+mod observe;
+mod qsbr;
+#[cfg(feature = "std")]
+mod reload;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod shim;
+mod strategy;
+#[cfg(any(all(test, feature = "std"), feature = "testing"))]
+pub mod testing;
+
+pub use backend::{
+    CapacityError, Collected, HazardPointers, ReaderSlots, ReclaimBudget, Reclaimer, RefCounted,
+    Versioned,
+};
+pub use boxed::{BoxReadGuard, LocalBoxReader, SmrBoxReader, SmrSwapBox};
+pub use copy::{CopyGuard, CopyReader, SmrCopy};
+pub use fixed::{StaticLocalReader, StaticSwap, StaticSwapGuard, StaticWriter};
+#[cfg(feature = "std")]
+pub use global::{StaticReadGuard, StaticSmrSwap};
+pub use log::{LogSnapshot, SmrLog};
+pub use qsbr::QsbrReader;
+pub use strategy::Strategy;
+
+/// Thread-local reader of the default backend, formerly re-exported from `swmr-cell`.
+///
+/// 默认后端的线程本地读者，以前从 `swmr-cell` 重新导出。
+#[deprecated(
+    since = "0.10.0",
+    note = "use `LocalReader`, or `backend::VersionedReader` for the backend's reader"
+)]
+pub type CellLocalReader<T> = backend::VersionedReader<T>;
+
+/// Guard of the default backend, formerly re-exported from `swmr-cell`.
+///
+/// 默认后端的守卫，以前从 `swmr-cell` 重新导出。
+#[deprecated(
+    since = "0.10.0",
+    note = "use `ReadGuard`, or `backend::VersionedGuard` for the backend's guard"
+)]
+pub type PinGuard<'a, T> = backend::VersionedGuard<'a, T>;
+
+/// `Sync` reader factory of the default backend, formerly re-exported from `swmr-cell`.
+///
+/// 默认后端的 `Sync` 读者工厂，以前从 `swmr-cell` 重新导出。
+#[deprecated(
+    since = "0.10.0",
+    note = "use `SmrReader`, or `backend::VersionedShared` for the backend's handle"
+)]
+pub type CellSwmrReader<T> = backend::VersionedShared<T>;
+
+#[cfg(feature = "std")]
+pub use map::{MapBatch, MapSnapshot, SmrMap};
+#[cfg(feature = "std")]
+pub use reload::{FileReloader, ReloadError, ReloadHandle};
+#[cfg(feature = "serde")]
+pub use serde_impl::value_only;
+
+pub use allocator_api2::alloc::{Allocator, Global};
 
 /// Main entry point for the SMR swap library.
 ///
@@ -43,9 +121,13 @@ pub use swmr_cell::{LocalReader as CellLocalReader, PinGuard, SwmrReader as Cell
 /// SMR swap 库的主入口点。
 ///
 /// 单写多读的交换容器，带有基于版本的垃圾回收。
-pub struct SmrSwap<T: 'static> {
-    cell: SwmrCell<T>,
-    local: LocalReader<T>,
+pub struct SmrSwap<
+    T: 'static,
+    A: Allocator + Clone + Send + Sync + 'static = Global,
+    R: Reclaimer = Versioned,
+> {
+    cell: R::Writer<T, A>,
+    local: LocalReader<T, R>,
 }
 
 /// A handle for creating `LocalReader`s that can be shared across threads.
@@ -57,23 +139,70 @@ pub struct SmrSwap<T: 'static> {
 ///
 /// 与 `!Sync` 且绑定到单个线程的 `LocalReader` 不同，
 /// `SmrReader` 是 `Sync` 和 `Clone` 的。它充当 `LocalReader` 的工厂。
-pub struct SmrReader<T: 'static> {
-    inner: CellSwmrReader<T>,
+pub struct SmrReader<T: 'static, R: Reclaimer = Versioned> {
+    inner: R::Shared<T>,
 }
 
-impl<T: 'static> SmrReader<T> {
+impl<T: 'static, R: Reclaimer> SmrReader<T, R> {
     /// Create a new `LocalReader` for the current thread.
     ///
+    /// # Panics
+    ///
+    /// Panics if the container bounds its readers and all are in use; see
+    /// [`try_local`](Self::try_local).
+    ///
     /// 为当前线程创建一个新的 `LocalReader`。
+    ///
+    /// # Panics
+    ///
+    /// 如果容器限制了读者数量且都已被使用，则 panic；参见 [`try_local`](Self::try_local)。
     #[inline]
-    pub fn local(&self) -> LocalReader<T> {
+    pub fn local(&self) -> LocalReader<T, R> {
         LocalReader {
             inner: self.inner.local(),
         }
     }
+
+    /// Create a new `LocalReader` for the current thread, or fail if the container was
+    /// built with [`max_readers`](SmrSwapBuilder::max_readers) and all readers are in use.
+    ///
+    /// 为当前线程创建一个新的 `LocalReader`；如果容器通过
+    /// [`max_readers`](SmrSwapBuilder::max_readers) 构建且所有读者都已被使用，则失败。
+    #[inline]
+    pub fn try_local(&self) -> Result<LocalReader<T, R>, CapacityError> {
+        Ok(LocalReader {
+            inner: self.inner.try_local()?,
+        })
+    }
+}
+
+impl<T: 'static> SmrReader<T> {
+    /// Create a new [`QsbrReader`] for the current thread.
+    ///
+    /// 为当前线程创建一个新的 [`QsbrReader`]。
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container bounds its readers and all are in use; see
+    /// [`try_qsbr_reader`](Self::try_qsbr_reader).
+    ///
+    /// 如果容器限制了读者数量且都已被使用，则 panic；参见 [`try_qsbr_reader`](Self::try_qsbr_reader)。
+    #[inline]
+    pub fn qsbr_reader(&self) -> QsbrReader<T> {
+        self.inner.qsbr()
+    }
+
+    /// Create a new [`QsbrReader`], or fail if the container bounds its readers and all are
+    /// in use.
+    ///
+    /// 创建一个新的 [`QsbrReader`]；如果容器限制了读者数量且都已被使用，则失败。
+    #[inline]
+    pub fn try_qsbr_reader(&self) -> Result<QsbrReader<T>, CapacityError> {
+        self.inner.try_qsbr()
+    }
 }
 
-impl<T: 'static> Clone for SmrReader<T> {
+impl<T: 'static, R: Reclaimer> Clone for SmrReader<T, R> {
     #[inline]
     fn clone(&self) -> Self {
         Self {
@@ -82,7 +211,7 @@ impl<T: 'static> Clone for SmrReader<T> {
     }
 }
 
-impl<T: 'static> fmt::Debug for SmrReader<T> {
+impl<T: 'static, R: Reclaimer> fmt::Debug for SmrReader<T, R> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SmrReader").finish()
     }
@@ -93,12 +222,21 @@ impl<T: 'static> fmt::Debug for SmrReader<T> {
 /// Each thread should create its own `LocalReader` via `SmrSwap::local()` and reuse it.
 /// `LocalReader` is `!Sync` and should not be shared between threads.
 ///
+/// With the default [`Versioned`] backend, loading never takes a lock, so an interrupt
+/// handler may load through its own `LocalReader` even while it preempts the writer. The
+/// handler must not share the reader with the code it interrupts. The other backends may
+/// lock while loading.
+///
 /// 线程本地的读取句柄，不是 Sync。
 ///
 /// 每个线程应该通过 `SmrSwap::local()` 创建自己的 `LocalReader` 并重复使用。
 /// `LocalReader` 是 `!Sync` 的，不应在线程之间共享。
-pub struct LocalReader<T: 'static> {
-    inner: CellLocalReader<T>,
+///
+/// 使用默认的 [`Versioned`] 后端时，加载从不获取锁，因此中断处理程序即使在打断写者时，
+/// 也可以通过其自己的 `LocalReader` 加载。处理程序不得与被其打断的代码共享该读者。
+/// 其他后端在加载时可能会加锁。
+pub struct LocalReader<T: 'static, R: Reclaimer = Versioned> {
+    inner: R::Reader<T>,
 }
 
 /// RAII guard for reading values.
@@ -108,20 +246,20 @@ pub struct LocalReader<T: 'static> {
 /// 用于读取值的 RAII 守卫。
 ///
 /// 解引用以访问值。在守卫被 drop 之前，值是受保护的。
-pub struct ReadGuard<'a, T: 'static> {
-    inner: PinGuard<'a, T>,
+pub struct ReadGuard<'a, T: 'static, R: Reclaimer = Versioned> {
+    inner: <R::Reader<T> as RawReader<T>>::Guard<'a>,
 }
 
-impl<'a, T> Deref for ReadGuard<'a, T> {
+impl<'a, T, R: Reclaimer> Deref for ReadGuard<'a, T, R> {
     type Target = T;
 
     #[inline(always)]
     fn deref(&self) -> &T {
-        &*self.inner
+        &self.inner
     }
 }
 
-impl<'a, T> Clone for ReadGuard<'a, T> {
+impl<'a, T, R: Reclaimer> Clone for ReadGuard<'a, T, R> {
     #[inline]
     fn clone(&self) -> Self {
         ReadGuard {
@@ -140,15 +278,101 @@ impl<T: 'static> SmrSwap<T> {
     /// 使用给定的初始值创建新的 SMR 容器。
     #[inline]
     pub fn new(initial: T) -> Self {
-        let cell = SwmrCell::builder()
-            .auto_reclaim_threshold(Some(4))
-            .build(initial);
-        let local = LocalReader {
-            inner: cell.local(),
-        };
-        Self { cell, local }
+        Self::builder().build(initial)
     }
 
+    /// Create a new SMR container with at most `max_readers` readers besides its own.
+    ///
+    /// Reader slots are allocated up front, [`try_local`](Self::try_local) fails instead of
+    /// allocating once they are all in use, and loads are wait-free. Shorthand for
+    /// `SmrSwap::builder().max_readers(max_readers).build(initial)`.
+    ///
+    /// 创建一个新的 SMR 容器，除其自身之外最多有 `max_readers` 个读者。
+    ///
+    /// 读者槽预先分配，所有槽都被使用后 [`try_local`](Self::try_local) 会失败而不是分配内存，
+    /// 并且加载是无等待的。是 `SmrSwap::builder().max_readers(max_readers).build(initial)`
+    /// 的简写。
+    #[inline]
+    pub fn with_max_readers(initial: T, max_readers: usize) -> Self {
+        Self::builder().max_readers(max_readers).build(initial)
+    }
+
+    /// Create a builder for configuring garbage collection and allocation recycling.
+    ///
+    /// 创建用于配置垃圾回收和内存分配回收复用的构建器。
+    #[inline]
+    pub fn builder() -> SmrSwapBuilder<T> {
+        SmrSwapBuilder::default()
+    }
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static> SmrSwap<T, A> {
+    /// Create a new SMR container whose value nodes are allocated with `alloc`.
+    ///
+    /// Every stored value, the garbage list and the recycle pool live in `alloc`;
+    /// only the reader bookkeeping shared with `LocalReader`s uses the global allocator.
+    ///
+    /// 创建一个新的 SMR 容器，其值节点使用 `alloc` 分配。
+    ///
+    /// 每个存储的值、垃圾列表和回收池都位于 `alloc` 中；
+    /// 只有与 `LocalReader` 共享的读者簿记数据使用全局分配器。
+    #[inline]
+    pub fn new_in(initial: T, alloc: A) -> Self {
+        SmrSwapBuilder::default().build_in(initial, alloc)
+    }
+
+    /// Create a new quiescent-state-based reader for this container.
+    ///
+    /// The reader is always pinned and loads without taking a guard; the thread reports
+    /// with [`QsbrReader::quiescent`] when it holds no reference. It can be used alongside
+    /// `LocalReader`s. Only available on the default [`Versioned`] backend.
+    ///
+    /// 为此容器创建一个新的基于静止状态的读者。
+    ///
+    /// 该读者始终被 pin，加载时不获取守卫；线程在不持有任何引用时通过 [`QsbrReader::quiescent`]
+    /// 报告。它可以与 `LocalReader` 一起使用。仅在默认的 [`Versioned`] 后端上可用。
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container bounds its readers and all are in use; see
+    /// [`try_qsbr_reader`](Self::try_qsbr_reader).
+    ///
+    /// 如果容器限制了读者数量且都已被使用，则 panic；参见 [`try_qsbr_reader`](Self::try_qsbr_reader)。
+    #[inline]
+    pub fn qsbr_reader(&self) -> QsbrReader<T> {
+        self.cell.qsbr()
+    }
+
+    /// Create a new quiescent-state-based reader, or fail if the container was built with
+    /// [`max_readers`](SmrSwapBuilder::max_readers) and all readers are in use.
+    ///
+    /// QSBR readers share the fixed slots with `LocalReader`s and never allocate in a
+    /// bounded container. Other containers always succeed.
+    ///
+    /// 创建一个新的基于静止状态的读者；如果容器通过 [`max_readers`](SmrSwapBuilder::max_readers)
+    /// 构建且所有读者都已被使用，则失败。
+    ///
+    /// QSBR 读者与 `LocalReader` 共享固定槽，在有上限的容器中从不分配内存。其他容器总是成功。
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use smr_swap::SmrSwap;
+    ///
+    /// let swap = SmrSwap::with_max_readers(0, 1);
+    /// let reader = swap.try_qsbr_reader().unwrap();
+    /// assert!(swap.try_local().is_err());
+    ///
+    /// drop(reader);
+    /// assert!(swap.try_qsbr_reader().is_ok());
+    /// ```
+    #[inline]
+    pub fn try_qsbr_reader(&self) -> Result<QsbrReader<T>, CapacityError> {
+        self.cell.try_qsbr()
+    }
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static, R: Reclaimer> SmrSwap<T, A, R> {
     /// Create a new thread-local reader for this container.
     ///
     /// Each thread should create its own `LocalReader` and reuse it.
@@ -158,13 +382,63 @@ impl<T: 'static> SmrSwap<T> {
     ///
     /// 每个线程应该创建自己的 `LocalReader` 并重复使用。
     /// `LocalReader` 是 `Send` 但不是 `Sync`。
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container bounds its readers and all are in use; see
+    /// [`try_local`](Self::try_local).
+    ///
+    /// `local` stays infallible because only a container built with
+    /// [`max_readers`](SmrSwapBuilder::max_readers) can run out of readers, and the
+    /// type does not record whether it is bounded. Returning a `Result` here would make
+    /// every unbounded caller handle an error that cannot happen, so bounded
+    /// containers use [`try_local`](Self::try_local) instead.
+    ///
+    /// 如果容器限制了读者数量且都已被使用，则 panic；参见 [`try_local`](Self::try_local)。
+    ///
+    /// `local` 保持不会失败，因为只有通过 [`max_readers`](SmrSwapBuilder::max_readers)
+    /// 构建的容器才会耗尽读者，而类型并不记录容器是否有上限。在这里返回 `Result`
+    /// 会让每个无上限的调用者处理一个不可能发生的错误，因此有上限的容器应改用
+    /// [`try_local`](Self::try_local)。
     #[inline]
-    pub fn local(&self) -> LocalReader<T> {
+    pub fn local(&self) -> LocalReader<T, R> {
         LocalReader {
             inner: self.cell.local(),
         }
     }
 
+    /// Create a new thread-local reader, or fail if the container was built with
+    /// [`max_readers`](SmrSwapBuilder::max_readers) and all readers are in use.
+    ///
+    /// A bounded container never allocates for a reader: the slots are allocated when the
+    /// container is built, and the reader takes a free one or returns [`CapacityError`].
+    /// Other containers always succeed.
+    ///
+    /// 创建一个新的线程本地读取者；如果容器通过 [`max_readers`](SmrSwapBuilder::max_readers)
+    /// 构建且所有读者都已被使用，则失败。
+    ///
+    /// 有上限的容器从不为读者分配内存：槽在构建容器时分配，读者获取一个空闲槽，
+    /// 否则返回 [`CapacityError`]。其他容器总是成功。
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use smr_swap::SmrSwap;
+    ///
+    /// let swap = SmrSwap::with_max_readers(0, 1);
+    /// let reader = swap.try_local().unwrap();
+    /// assert!(swap.try_local().is_err());
+    ///
+    /// drop(reader);
+    /// assert!(swap.try_local().is_ok());
+    /// ```
+    #[inline]
+    pub fn try_local(&self) -> Result<LocalReader<T, R>, CapacityError> {
+        Ok(LocalReader {
+            inner: self.cell.try_local()?,
+        })
+    }
+
     /// Create a new `SmrReader` that can be shared across threads.
     ///
     /// `SmrReader` is `Sync` + `Clone` and acts as a factory for `LocalReader`s.
@@ -175,9 +449,9 @@ impl<T: 'static> SmrSwap<T> {
     /// `SmrReader` 是 `Sync` + `Clone` 的，充当 `LocalReader` 的工厂。
     /// 这对于将读者创建能力分发给其他线程很有用。
     #[inline]
-    pub fn reader(&self) -> SmrReader<T> {
+    pub fn reader(&self) -> SmrReader<T, R> {
         SmrReader {
-            inner: self.cell.reader(),
+            inner: self.cell.shared(),
         }
     }
 
@@ -190,9 +464,72 @@ impl<T: 'static> SmrSwap<T> {
     /// 旧值已退休，将在安全时被垃圾回收。
     #[inline]
     pub fn store(&mut self, new_value: T) {
+        let _span = observe::write_span("store");
         self.cell.store(new_value);
     }
 
+    /// Store a new value built from a recycled one, reusing its heap allocation.
+    ///
+    /// The closure receives a reclaimed value if the recycle pool has one, or `None`
+    /// otherwise. When a value is provided, the new value is written into its allocation,
+    /// so steady-state stores of large values perform no allocation. The pool is filled by
+    /// garbage collection and only when [`SmrSwapBuilder::recycle_capacity`] is non-zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use smr_swap::SmrSwap;
+    ///
+    /// let mut swap = SmrSwap::builder().recycle_capacity(2).build(vec![0u8; 1024]);
+    ///
+    /// for i in 1..10u8 {
+    ///     swap.store_with(|recycled| {
+    ///         let mut buf = recycled.unwrap_or_default();
+    ///         buf.clear();
+    ///         buf.resize(1024, i);
+    ///         buf
+    ///     });
+    /// }
+    ///
+    /// assert_eq!(swap.get()[0], 9);
+    /// ```
+    ///
+    /// 使用回收的值构建并存储新值，复用其堆内存分配。
+    ///
+    /// 如果回收池中有已回收的值，闭包会收到该值，否则收到 `None`。
+    /// 当提供了值时，新值会被写入其内存分配，因此大型值的稳态存储不会产生内存分配。
+    /// 回收池由垃圾回收填充，并且仅在 [`SmrSwapBuilder::recycle_capacity`] 非零时启用。
+    #[inline]
+    pub fn store_with<F>(&mut self, f: F)
+    where
+        F: FnOnce(Option<T>) -> T,
+    {
+        let _span = observe::write_span("store_with");
+        self.cell.store_with(f);
+    }
+
+    /// Take a reclaimed value out of the recycle pool, if any.
+    ///
+    /// Values only enter the pool once garbage collection has proven that no reader can
+    /// observe them, so the returned value is exclusively owned by the caller.
+    ///
+    /// 从回收池中取出一个已回收的值（如果有）。
+    ///
+    /// 只有在垃圾回收证明没有读者可以观察到某个值之后，该值才会进入回收池，
+    /// 因此返回的值由调用者独占。
+    #[inline]
+    pub fn recycle(&mut self) -> Option<T> {
+        self.cell.take_recycled()
+    }
+
+    /// Get the number of reclaimed values waiting in the recycle pool.
+    ///
+    /// 获取回收池中等待复用的已回收值数量。
+    #[inline]
+    pub fn recycled_count(&self) -> usize {
+        self.cell.recycled_count()
+    }
+
     /// Get a reference to the current value (writer-only, no pinning required).
     ///
     /// This is only accessible from the writer thread since `SmrSwap` is `!Sync`.
@@ -219,7 +556,9 @@ impl<T: 'static> SmrSwap<T> {
     where
         F: FnOnce(&T) -> T,
     {
-        self.cell.update(f);
+        let _span = observe::write_span("update");
+        let new_value = f(self.cell.get());
+        self.cell.store(new_value);
     }
 
     /// Get the current global version.
@@ -234,6 +573,28 @@ impl<T: 'static> SmrSwap<T> {
         self.cell.version()
     }
 
+    /// Get the memory barrier strategy of this container.
+    ///
+    /// 获取此容器的内存屏障策略。
+    #[inline]
+    pub fn strategy(&self) -> Strategy {
+        self.cell.strategy()
+    }
+
+    /// Get the algorithm currently in use.
+    ///
+    /// Equal to [`strategy`](Self::strategy) unless it is [`Strategy::Adaptive`], in which
+    /// case it is the `WritePreferred` or `ReadPreferred` algorithm last chosen.
+    ///
+    /// 获取当前正在使用的算法。
+    ///
+    /// 除非策略为 [`Strategy::Adaptive`]，否则与 [`strategy`](Self::strategy) 相同；
+    /// 对于自适应策略，返回最近选择的 `WritePreferred` 或 `ReadPreferred` 算法。
+    #[inline]
+    pub fn active_strategy(&self) -> Strategy {
+        self.cell.active_strategy()
+    }
+
     /// Get the number of retired objects waiting for garbage collection.
     ///
     /// 获取等待垃圾回收的已退休对象数量。
@@ -242,6 +603,50 @@ impl<T: 'static> SmrSwap<T> {
         self.cell.garbage_count()
     }
 
+    /// Count the reader slots allocated by this container and those held by live readers.
+    ///
+    /// Dropping a `LocalReader` or [`QsbrReader`], including when its thread exits, frees
+    /// its slot, and the next reader takes the first free slot instead of allocating one.
+    /// Slots are only released with the container, so [`ReaderSlots::allocated`] is the
+    /// largest number of readers that were ever alive at once, including the writer's own.
+    /// Compare it with [`ReaderSlots::in_use`] to spot readers that are leaked rather than
+    /// dropped.
+    ///
+    /// Fixed slots of [`max_readers`](SmrSwapBuilder::max_readers) and shards of
+    /// [`reader_shards`](SmrSwapBuilder::reader_shards) are allocated up front; shards
+    /// always count as in use.
+    ///
+    /// 统计此容器分配的读者槽，以及被存活读者持有的槽。
+    ///
+    /// drop `LocalReader` 或 [`QsbrReader`]（包括其线程退出时）会释放其槽，下一个读者会获取
+    /// 第一个空闲槽而不是分配新槽。槽只会随容器一起释放，因此 [`ReaderSlots::allocated`]
+    /// 是曾经同时存活的最大读者数量（包括写者自己的读者）。将其与 [`ReaderSlots::in_use`]
+    /// 比较，即可发现被泄漏而不是被 drop 的读者。
+    ///
+    /// [`max_readers`](SmrSwapBuilder::max_readers) 的固定槽和
+    /// [`reader_shards`](SmrSwapBuilder::reader_shards) 的分片是预先分配的；分片始终计为使用中。
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use smr_swap::SmrSwap;
+    ///
+    /// let swap = SmrSwap::new(0);
+    /// for _ in 0..100 {
+    ///     let reader = swap.local();
+    ///     assert_eq!(*reader.load(), 0);
+    /// }
+    ///
+    /// // The writer's own reader, plus one slot reused by every short-lived reader.
+    /// let slots = swap.reader_slots();
+    /// assert_eq!(slots.allocated(), 2);
+    /// assert_eq!(slots.in_use(), 1);
+    /// ```
+    #[inline]
+    pub fn reader_slots(&self) -> ReaderSlots {
+        self.cell.reader_slots()
+    }
+
     /// Get a reference to the previously stored value, if any.
     ///
     /// Returns `None` if no previous value exists (i.e., only the initial value has been stored).
@@ -263,9 +668,114 @@ impl<T: 'static> SmrSwap<T> {
     /// 通常不需要，因为垃圾会自动回收。
     #[inline]
     pub fn collect(&mut self) {
+        checks::writer_collecting("collect", self.local.is_pinned());
         self.cell.collect();
     }
 
+    /// Trigger garbage collection, reclaiming at most `max_items` values.
+    ///
+    /// Dropping thousands of retired values at once stalls the writer; a budgeted
+    /// collection reclaims the oldest ones and leaves the rest for a later call. The
+    /// returned [`Collected`] reports how many values were left
+    /// [`pending`](Collected::pending).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use smr_swap::SmrSwap;
+    ///
+    /// let mut swap = SmrSwap::builder().auto_reclaim_threshold(None).build(0);
+    /// for i in 1..=10 {
+    ///     swap.store(i);
+    /// }
+    ///
+    /// let collected = swap.collect_budget(3);
+    /// assert_eq!(collected.reclaimed(), 3);
+    /// assert_eq!(collected.pending(), 5);
+    /// assert_eq!(collected.remaining(), 7);
+    ///
+    /// while !swap.collect_budget(3).is_complete() {}
+    /// assert_eq!(swap.garbage_count(), 2);
+    /// ```
+    ///
+    /// 触发垃圾回收，最多回收 `max_items` 个值。
+    ///
+    /// 一次 drop 成千上万个已退休的值会使写者停顿；受预算限制的回收只回收最旧的值，
+    /// 其余的留给之后的调用。返回的 [`Collected`] 报告有多少个值仍
+    /// [待回收](Collected::pending)。
+    #[inline]
+    pub fn collect_budget(&mut self, max_items: usize) -> Collected {
+        checks::writer_collecting("collect_budget", self.local.is_pinned());
+        self.cell.collect_budget(ReclaimBudget::Items(max_items))
+    }
+
+    /// Trigger garbage collection, reclaiming values until `time` has passed.
+    ///
+    /// The time is checked between values, and at least one value is reclaimed when any
+    /// can be, so repeated calls always make progress. See
+    /// [`collect_budget`](SmrSwap::collect_budget).
+    ///
+    /// 触发垃圾回收，持续回收值直到经过 `time`。
+    ///
+    /// 时间在值与值之间检查，并且只要有可回收的值就至少回收一个，因此重复调用总能取得进展。
+    /// 参见 [`collect_budget`](SmrSwap::collect_budget)。
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn collect_for(&mut self, time: core::time::Duration) -> Collected {
+        checks::writer_collecting("collect_for", self.local.is_pinned());
+        self.cell.collect_budget(ReclaimBudget::Time(time))
+    }
+
+    /// Trigger garbage collection and move every reclaimed value into `sink`.
+    ///
+    /// Reclaimed values are handed to the caller instead of being dropped or kept in the
+    /// recycle pool. Values are yielded oldest first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use smr_swap::SmrSwap;
+    ///
+    /// let mut swap = SmrSwap::builder().auto_reclaim_threshold(None).build(0);
+    /// for i in 1..=5 {
+    ///     swap.store(i);
+    /// }
+    ///
+    /// let mut freed = Vec::new();
+    /// swap.collect_into(&mut freed);
+    /// assert_eq!(freed, [0, 1, 2]);
+    /// ```
+    ///
+    /// 触发垃圾回收，并将所有已回收的值移入 `sink`。
+    ///
+    /// 已回收的值会交给调用者，而不是被 drop 或保留在回收池中。值按从旧到新的顺序产出。
+    #[inline]
+    pub fn collect_into<E>(&mut self, sink: &mut E)
+    where
+        E: Extend<T>,
+    {
+        checks::writer_collecting("collect_into", self.local.is_pinned());
+        self.cell.collect_into(sink);
+    }
+
+    /// Trigger garbage collection and iterate over the reclaimed values with their versions.
+    ///
+    /// Each item is `(version, value)`, where `version` is the last version in which the
+    /// value was current, oldest first. Values that are not consumed before the iterator
+    /// is dropped are released as by [`collect`](SmrSwap::collect).
+    ///
+    /// 触发垃圾回收，并遍历已回收的值及其版本。
+    ///
+    /// 每一项为 `(version, value)`，其中 `version` 是该值作为当前值的最后版本，按从旧到新排列。
+    /// 在迭代器被 drop 之前未被消费的值，会像 [`collect`](SmrSwap::collect) 一样被释放。
+    #[inline]
+    pub fn drain_reclaimed(&mut self) -> Reclaimed<'_, T, A, R> {
+        checks::writer_collecting("drain_reclaimed", self.local.is_pinned());
+        Reclaimed {
+            inner: self.cell.drain_reclaimed(),
+        }
+    }
+
     /// Read the current value with RAII guard.
     ///
     /// Returns a `ReadGuard` that can be dereferenced to access the value.
@@ -276,7 +786,7 @@ impl<T: 'static> SmrSwap<T> {
     /// 返回一个可以解引用来访问值的 `ReadGuard`。
     /// 在守卫被 drop 之前，值是受保护的。
     #[inline]
-    pub fn load(&self) -> ReadGuard<'_, T> {
+    pub fn load(&self) -> ReadGuard<'_, T, R> {
         self.local.load()
     }
 
@@ -320,6 +830,7 @@ impl<T: 'static> SmrSwap<T> {
     where
         T: Clone,
     {
+        let _span = observe::write_span("swap");
         let old_value = self.cell.get().clone();
         self.cell.store(new_value);
         old_value
@@ -335,10 +846,11 @@ impl<T: 'static> SmrSwap<T> {
     /// 闭包接收当前值的引用，返回新值。
     /// 返回新值的守卫。
     #[inline]
-    pub fn update_and_fetch<F>(&mut self, f: F) -> ReadGuard<'_, T>
+    pub fn update_and_fetch<F>(&mut self, f: F) -> ReadGuard<'_, T, R>
     where
         F: FnOnce(&T) -> T,
     {
+        let _span = observe::write_span("update_and_fetch");
         let new_value = f(self.cell.get());
         self.cell.store(new_value);
         self.local.load()
@@ -354,10 +866,11 @@ impl<T: 'static> SmrSwap<T> {
     /// 闭包接收当前值并应返回新值。
     /// 返回旧值（更新前）的守卫。
     #[inline]
-    pub fn fetch_and_update<F>(&mut self, f: F) -> ReadGuard<'_, T>
+    pub fn fetch_and_update<F>(&mut self, f: F) -> ReadGuard<'_, T, R>
     where
         F: FnOnce(&T) -> T,
     {
+        let _span = observe::write_span("fetch_and_update");
         let old_guard = self.local.load();
         let new_value = f(self.cell.get());
         self.cell.store(new_value);
@@ -365,11 +878,322 @@ impl<T: 'static> SmrSwap<T> {
     }
 }
 
+// ============================================================================
+// SmrSwapBuilder implementation
+// ============================================================================
+
+/// Builder for [`SmrSwap`].
+///
+/// 用于 [`SmrSwap`] 的构建器。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::SmrSwap;
+///
+/// let swap = SmrSwap::builder()
+///     .auto_reclaim_threshold(Some(16))
+///     .recycle_capacity(4)
+///     .build(String::from("hello"));
+///
+/// assert_eq!(*swap.load(), "hello");
+/// ```
+pub struct SmrSwapBuilder<T: 'static, R: Reclaimer = Versioned> {
+    config: Config,
+    _marker: PhantomData<fn() -> (T, R)>,
+}
+
+impl<T: 'static, R: Reclaimer> SmrSwapBuilder<T, R> {
+    /// Set the number of retired values that triggers automatic garbage collection.
+    ///
+    /// `None` disables automatic collection; call [`SmrSwap::collect`] manually instead.
+    /// Defaults to `Some(4)`.
+    ///
+    /// 设置触发自动垃圾回收的已退休值数量。
+    ///
+    /// `None` 会禁用自动回收；此时需要手动调用 [`SmrSwap::collect`]。默认为 `Some(4)`。
+    #[inline]
+    pub fn auto_reclaim_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.config.auto_reclaim_threshold = threshold;
+        self
+    }
+
+    /// Bound the work of each automatic collection to `budget`.
+    ///
+    /// With a budget, a store that crosses the
+    /// [`auto_reclaim_threshold`](SmrSwapBuilder::auto_reclaim_threshold) reclaims only
+    /// part of the garbage, keeping store latency bounded; the rest is reclaimed by the
+    /// following stores. Choose a budget above one value per store so the garbage list
+    /// shrinks. Defaults to `None`, which reclaims everything reclaimable.
+    ///
+    /// 将每次自动回收的工作量限制在 `budget` 以内。
+    ///
+    /// 设置预算后，超过 [`auto_reclaim_threshold`](SmrSwapBuilder::auto_reclaim_threshold)
+    /// 的存储只会回收部分垃圾，从而使存储延迟有界；其余的由之后的存储回收。
+    /// 预算应高于每次存储一个值，以便垃圾列表逐渐缩短。默认为 `None`，即回收所有可回收的值。
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use smr_swap::{ReclaimBudget, SmrSwap};
+    ///
+    /// let mut swap = SmrSwap::builder()
+    ///     .auto_reclaim_budget(Some(ReclaimBudget::Items(8)))
+    ///     .build(0);
+    /// for i in 1..=100 {
+    ///     swap.store(i);
+    /// }
+    /// assert!(swap.garbage_count() <= 5);
+    /// ```
+    #[inline]
+    pub fn auto_reclaim_budget(mut self, budget: Option<ReclaimBudget>) -> Self {
+        self.config.auto_reclaim_budget = budget;
+        self
+    }
+
+    /// Set how many reclaimed values are kept for reuse instead of being dropped.
+    ///
+    /// Kept values are handed back by [`SmrSwap::store_with`] and [`SmrSwap::recycle`].
+    /// Defaults to `0`, which drops every reclaimed value.
+    ///
+    /// 设置保留多少个已回收的值以供复用，而不是将其 drop。
+    ///
+    /// 保留的值会通过 [`SmrSwap::store_with`] 和 [`SmrSwap::recycle`] 交还。
+    /// 默认为 `0`，即 drop 所有已回收的值。
+    #[inline]
+    pub fn recycle_capacity(mut self, capacity: usize) -> Self {
+        self.config.recycle_capacity = capacity;
+        self
+    }
+
+    /// Set the version the container starts at.
+    ///
+    /// Useful when restoring a checkpointed container whose readers compare versions.
+    /// Defaults to `0`.
+    ///
+    /// 设置容器的起始版本。
+    ///
+    /// 在恢复读者会比较版本的检查点容器时很有用。默认为 `0`。
+    #[inline]
+    pub fn initial_version(mut self, version: usize) -> Self {
+        self.config.initial_version = version;
+        self
+    }
+
+    /// Set the name of the container.
+    ///
+    /// With the `metrics` feature, the name is attached as the `name` label of every
+    /// metric the container publishes. Unnamed containers are labelled `"unnamed"`.
+    ///
+    /// 设置容器的名称。
+    ///
+    /// 启用 `metrics` feature 时，该名称会作为容器发布的每个指标的 `name` 标签。
+    /// 未命名的容器的标签为 `"unnamed"`。
+    #[inline]
+    pub fn name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.config.name = Some(name.into());
+        self
+    }
+
+    /// Set the memory barrier strategy of the container.
+    ///
+    /// Use [`Strategy::ReadPreferred`] for hot, read-mostly data,
+    /// [`Strategy::WritePreferred`] for frequently written data, and
+    /// [`Strategy::Adaptive`] when the mix changes over time. Defaults to
+    /// `WritePreferred`, or `ReadPreferred` with the `read-preferred` feature.
+    ///
+    /// 设置容器的内存屏障策略。
+    ///
+    /// 对于热点的以读为主的数据使用 [`Strategy::ReadPreferred`]，对于频繁写入的数据使用
+    /// [`Strategy::WritePreferred`]，读写比例随时间变化时使用 [`Strategy::Adaptive`]。
+    /// 默认为 `WritePreferred`，启用 `read-preferred` feature 时默认为 `ReadPreferred`。
+    #[inline]
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.config.strategy = strategy;
+        self
+    }
+
+    /// Spread readers over `shards` shared slots instead of giving each reader its own.
+    ///
+    /// By default every reader registers a slot of its own, so creating a reader takes the
+    /// registry lock and each collection scans every live reader. With shards, readers are
+    /// assigned a shard in turn when created and never touch the registry; a collection
+    /// scans a fixed number of cache lines however many readers exist. Readers that pin the
+    /// same shard share its cache line, so use roughly as many shards as reading threads.
+    ///
+    /// Only the [`Versioned`] backend shards its readers. Defaults to `0`, which disables
+    /// sharding.
+    ///
+    /// 将读者分布在 `shards` 个共享槽上，而不是为每个读者分配各自的槽。
+    ///
+    /// 默认情况下每个读者注册一个自己的槽，因此创建读者需要获取注册表锁，并且每次回收都会扫描
+    /// 所有存活的读者。启用分片后，读者在创建时轮流分配到一个分片，且从不访问注册表；
+    /// 无论存在多少读者，回收都只扫描固定数量的缓存行。pin 同一分片的读者共享其缓存行，
+    /// 因此分片数量应与读线程数量大致相同。
+    ///
+    /// 只有 [`Versioned`] 后端会对读者分片。默认为 `0`，即禁用分片。
+    #[inline]
+    pub fn reader_shards(mut self, shards: usize) -> Self {
+        self.config.reader_shards = shards;
+        self
+    }
+
+    /// Bound the container to `max_readers` readers besides the writer's own.
+    ///
+    /// Reader slots are allocated when the container is built. [`SmrSwap::try_local`]
+    /// returns [`CapacityError`] once they are all in use, and a dropped reader frees its
+    /// slot for the next one. [`SmrSwap::local`] panics instead, so bounded containers
+    /// should create their readers with `try_local`. Pinning never loops: it retries at
+    /// most once, so loads are wait-free and never allocate, which suits real-time threads.
+    ///
+    /// [`QsbrReader`]s take slots too; see [`SmrSwap::try_qsbr_reader`]. Takes precedence
+    /// over [`reader_shards`](Self::reader_shards). Only the [`Versioned`] backend bounds
+    /// its readers.
+    /// Unbounded by default.
+    ///
+    /// 将容器限制为除写者自己的读者之外最多 `max_readers` 个读者。
+    ///
+    /// 读者槽在构建容器时分配。所有槽都被使用后 [`SmrSwap::try_local`] 返回 [`CapacityError`]，
+    /// 被 drop 的读者会为下一个读者释放其槽。[`SmrSwap::local`] 则会 panic，因此有上限的容器
+    /// 应通过 `try_local` 创建读者。pin 从不循环：它最多重试一次，
+    /// 因此加载是无等待的且从不分配内存，适用于实时线程。
+    ///
+    /// [`QsbrReader`] 同样占用槽；参见 [`SmrSwap::try_qsbr_reader`]。
+    /// 优先于 [`reader_shards`](Self::reader_shards)。
+    /// 只有 [`Versioned`] 后端会限制读者数量。默认没有上限。
+    #[inline]
+    pub fn max_readers(mut self, max_readers: usize) -> Self {
+        self.config.max_readers = Some(max_readers);
+        self
+    }
+
+    /// Set the reclamation backend of the container.
+    ///
+    /// Defaults to [`Versioned`]. See the [`backend`] module for how the backends compare.
+    ///
+    /// 设置容器的回收后端。
+    ///
+    /// 默认为 [`Versioned`]。各后端的比较参见 [`backend`] 模块。
+    #[inline]
+    pub fn reclaimer<R2: Reclaimer>(self) -> SmrSwapBuilder<T, R2> {
+        SmrSwapBuilder {
+            config: self.config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Build the container with the given initial value.
+    ///
+    /// 使用给定的初始值构建容器。
+    #[inline]
+    pub fn build(self, initial: T) -> SmrSwap<T, Global, R> {
+        self.build_in(initial, Global)
+    }
+
+    /// Build the container with the given initial value, allocating value nodes with `alloc`.
+    ///
+    /// See [`SmrSwap::new_in`].
+    ///
+    /// 使用给定的初始值构建容器，并使用 `alloc` 分配值节点。
+    ///
+    /// 参见 [`SmrSwap::new_in`]。
+    #[inline]
+    pub fn build_in<A>(self, initial: T, alloc: A) -> SmrSwap<T, A, R>
+    where
+        A: Allocator + Clone + Send + Sync + 'static,
+    {
+        let cell = <R::Writer<T, A> as RawWriter<T, A>>::new(initial, self.config, alloc);
+        let local = LocalReader {
+            inner: cell.local(),
+        };
+        SmrSwap { cell, local }
+    }
+}
+
+impl<T: 'static, R: Reclaimer> Default for SmrSwapBuilder<T, R> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            config: Config::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static, R: Reclaimer> fmt::Debug for SmrSwapBuilder<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmrSwapBuilder")
+            .field(
+                "auto_reclaim_threshold",
+                &self.config.auto_reclaim_threshold,
+            )
+            .field("auto_reclaim_budget", &self.config.auto_reclaim_budget)
+            .field("recycle_capacity", &self.config.recycle_capacity)
+            .field("initial_version", &self.config.initial_version)
+            .field("name", &self.config.name)
+            .field("strategy", &self.config.strategy)
+            .field("reader_shards", &self.config.reader_shards)
+            .field("max_readers", &self.config.max_readers)
+            .finish()
+    }
+}
+
+// ============================================================================
+// Reclaimed implementation
+// ============================================================================
+
+/// Iterator over values reclaimed by [`SmrSwap::drain_reclaimed`].
+///
+/// Yields `(version, value)` pairs, oldest first.
+///
+/// 由 [`SmrSwap::drain_reclaimed`] 回收的值的迭代器。
+///
+/// 按从旧到新的顺序产出 `(version, value)` 对。
+pub struct Reclaimed<
+    'a,
+    T: 'static,
+    A: Allocator + Clone + Send + Sync + 'static = Global,
+    R: Reclaimer = Versioned,
+> {
+    inner: <R::Writer<T, A> as RawWriter<T, A>>::Drain<'a>,
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static, R: Reclaimer> Iterator
+    for Reclaimed<'_, T, A, R>
+{
+    type Item = (usize, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, T)> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static, R: Reclaimer> ExactSizeIterator
+    for Reclaimed<'_, T, A, R>
+{
+}
+
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static, R: Reclaimer> fmt::Debug
+    for Reclaimed<'_, T, A, R>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reclaimed")
+            .field("remaining", &self.inner.len())
+            .finish()
+    }
+}
+
 // ============================================================================
 // LocalReader implementation
 // ============================================================================
 
-impl<T: 'static> LocalReader<T> {
+impl<T: 'static, R: Reclaimer> LocalReader<T, R> {
     /// Read the current value with RAII guard.
     ///
     /// Returns a `ReadGuard` that holds the pin and the reference.
@@ -380,7 +1204,7 @@ impl<T: 'static> LocalReader<T> {
     /// 返回一个持有 pin 和引用的 `ReadGuard`。
     /// 当守卫被 drop 时，pin 会自动释放。
     #[inline]
-    pub fn load(&self) -> ReadGuard<'_, T> {
+    pub fn load(&self) -> ReadGuard<'_, T, R> {
         ReadGuard {
             inner: self.inner.pin(),
         }
@@ -429,7 +1253,7 @@ impl<T: 'static> LocalReader<T> {
     ///
     /// 对当前值应用闭包函数，如果闭包返回 true 则返回 Some。
     #[inline]
-    pub fn filter<F>(&self, f: F) -> Option<ReadGuard<'_, T>>
+    pub fn filter<F>(&self, f: F) -> Option<ReadGuard<'_, T, R>>
     where
         F: FnOnce(&T) -> bool,
     {
@@ -479,7 +1303,7 @@ impl<T: 'static> LocalReader<T> {
     /// `SmrReader` 是 `Sync` + `Clone` 的，充当 `LocalReader` 的工厂。
     /// 这相当于调用 `swap.reader()`，但使用 `LocalReader` 对共享状态的引用。
     #[inline]
-    pub fn share(&self) -> SmrReader<T> {
+    pub fn share(&self) -> SmrReader<T, R> {
         SmrReader {
             inner: self.inner.share(),
         }
@@ -494,14 +1318,25 @@ impl<T: 'static> LocalReader<T> {
     /// 这会消耗 `LocalReader` 并返回一个 `SmrReader`，
     /// 该 `SmrReader` 可以发送到另一个线程以创建新 `LocalReader`。
     #[inline]
-    pub fn into_swmr(self) -> SmrReader<T> {
+    pub fn into_swmr(self) -> SmrReader<T, R> {
         SmrReader {
-            inner: self.inner.into_swmr(),
+            inner: self.inner.share(),
         }
     }
 }
 
-impl<T: 'static> Clone for LocalReader<T> {
+impl<T: 'static, R: Reclaimer> Clone for LocalReader<T, R> {
+    /// Create another reader of the same container.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container bounds its readers and all are in use.
+    ///
+    /// 创建同一容器的另一个读者。
+    ///
+    /// # Panics
+    ///
+    /// 如果容器限制了读者数量且都已被使用，则 panic。
     #[inline]
     fn clone(&self) -> Self {
         LocalReader {
@@ -510,7 +1345,7 @@ impl<T: 'static> Clone for LocalReader<T> {
     }
 }
 
-impl<T: 'static> fmt::Debug for LocalReader<T> {
+impl<T: 'static, R: Reclaimer> fmt::Debug for LocalReader<T, R> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("LocalReader")
@@ -524,7 +1359,7 @@ impl<T: 'static> fmt::Debug for LocalReader<T> {
 // ReadGuard additional implementations
 // ============================================================================
 
-impl<T: 'static> ReadGuard<'_, T> {
+impl<T: 'static, R: Reclaimer> ReadGuard<'_, T, R> {
     /// Get the version that this guard is pinned to.
     ///
     /// 获取此守卫被 pin 到的版本。
@@ -574,14 +1409,14 @@ impl<T: 'static> ReadGuard<'_, T> {
     }
 }
 
-impl<T: 'static> AsRef<T> for ReadGuard<'_, T> {
+impl<T: 'static, R: Reclaimer> AsRef<T> for ReadGuard<'_, T, R> {
     #[inline]
     fn as_ref(&self) -> &T {
         self.deref()
     }
 }
 
-impl<T: fmt::Debug + 'static> fmt::Debug for ReadGuard<'_, T> {
+impl<T: fmt::Debug + 'static, R: Reclaimer> fmt::Debug for ReadGuard<'_, T, R> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ReadGuard")
@@ -596,27 +1431,32 @@ impl<T: fmt::Debug + 'static> fmt::Debug for ReadGuard<'_, T> {
 // 标准 trait 实现
 // ============================================================================
 
-impl<T: Default + 'static> Default for SmrSwap<T> {
+impl<T: Default + 'static, R: Reclaimer> Default for SmrSwap<T, Global, R> {
     /// Create a new SmrSwap with the default value.
     ///
     /// 使用默认值创建一个新的 SmrSwap。
     #[inline]
     fn default() -> Self {
-        Self::new(T::default())
+        SmrSwapBuilder::default().build(T::default())
     }
 }
 
-impl<T: 'static> From<T> for SmrSwap<T> {
+impl<T: 'static, R: Reclaimer> From<T> for SmrSwap<T, Global, R> {
     /// Create a new SmrSwap from a value.
     ///
     /// 从一个值创建一个新的 SmrSwap。
     #[inline]
     fn from(value: T) -> Self {
-        Self::new(value)
+        SmrSwapBuilder::default().build(value)
     }
 }
 
-impl<T: fmt::Debug + 'static> fmt::Debug for SmrSwap<T> {
+impl<T, A, R> fmt::Debug for SmrSwap<T, A, R>
+where
+    T: fmt::Debug + 'static,
+    A: Allocator + Clone + Send + Sync + 'static,
+    R: Reclaimer,
+{
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SmrSwap")
@@ -627,5 +1467,17 @@ impl<T: fmt::Debug + 'static> fmt::Debug for SmrSwap<T> {
     }
 }
 
+#[cfg(feature = "debug-checks")]
+impl<T: 'static, A: Allocator + Clone + Send + Sync + 'static, R: Reclaimer> Drop
+    for SmrSwap<T, A, R>
+{
+    fn drop(&mut self) {
+        // The writer's own reader is dropped after this and is not reported.
+        // 写者自己的读者在此之后才被 drop，不会被报告。
+        let (readers, pinned) = self.cell.live_readers();
+        checks::writer_dropped(readers - 1, pinned);
+    }
+}
+
 #[cfg(test)]
 mod tests;
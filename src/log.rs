@@ -0,0 +1,497 @@
+//! Append-only log with lock-free readers built on [`SmrSwap`].
+//!
+//! 构建在 [`SmrSwap`] 之上、带有无锁读者的只追加日志。
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::Index;
+use core::slice;
+
+use crate::shim::sync::{Arc, AtomicUsize, Ordering};
+use crate::{LocalReader, ReadGuard, SmrReader, SmrSwap};
+
+/// Capacity of the first chunk. Chunk `i` holds `FIRST_CHUNK_CAPACITY << i` entries.
+///
+/// 第一个块的容量。第 `i` 个块容纳 `FIRST_CHUNK_CAPACITY << i` 个条目。
+const FIRST_CHUNK_CAPACITY: usize = 32;
+
+/// A fixed-capacity block of log entries.
+///
+/// Entries are written exactly once by the writer, strictly beyond the length of
+/// every published snapshot, and never moved afterwards.
+///
+/// 固定容量的日志条目块。
+///
+/// 条目只由写者写入一次，且严格位于所有已发布快照的长度之后，之后永远不会移动。
+struct Chunk<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    initialized: AtomicUsize,
+}
+
+// Safety: readers only access initialized slots below the published length, which the
+// writer never touches again; the writer only initializes slots beyond it.
+// 安全性：读者只访问已发布长度以下的已初始化槽，写者不会再触碰它们；写者只初始化长度之后的槽。
+unsafe impl<T: Send + Sync> Sync for Chunk<T> {}
+unsafe impl<T: Send> Send for Chunk<T> {}
+
+impl<T> Chunk<T> {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity)
+                .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+                .collect(),
+            initialized: AtomicUsize::new(0),
+        }
+    }
+
+    /// Get the first `len` entries as a slice.
+    ///
+    /// # Safety
+    ///
+    /// The first `len` slots must be initialized and no longer written to.
+    #[inline]
+    unsafe fn prefix(&self, len: usize) -> &[T] {
+        debug_assert!(len <= self.initialized.load(Ordering::Relaxed));
+        // Safety: `UnsafeCell<MaybeUninit<T>>` has the same layout as `T`.
+        unsafe { slice::from_raw_parts(self.slots.as_ptr() as *const T, len) }
+    }
+
+    /// Initialize the next slot.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the single writer, and the chunk must not be full.
+    #[inline]
+    unsafe fn push(&self, value: T) {
+        let index = self.initialized.load(Ordering::Relaxed);
+        unsafe { (*self.slots[index].get()).write(value) };
+        self.initialized.store(index + 1, Ordering::Release);
+    }
+
+    #[inline]
+    fn is_full(&self) -> bool {
+        self.initialized.load(Ordering::Relaxed) == self.slots.len()
+    }
+}
+
+impl<T> Drop for Chunk<T> {
+    fn drop(&mut self) {
+        let initialized = *self.initialized.get_mut();
+        for slot in &mut self.slots[..initialized] {
+            // Safety: the first `initialized` slots have been written exactly once.
+            unsafe { slot.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+/// Map a log index to its chunk and the offset within that chunk.
+///
+/// 将日志索引映射到它所在的块以及块内偏移。
+#[inline]
+fn locate(index: usize) -> (usize, usize) {
+    let shifted = index + FIRST_CHUNK_CAPACITY;
+    let chunk = (shifted.ilog2() - FIRST_CHUNK_CAPACITY.ilog2()) as usize;
+    (chunk, shifted - (FIRST_CHUNK_CAPACITY << chunk))
+}
+
+/// An immutable prefix of an [`SmrLog`].
+///
+/// Readers obtain a snapshot through `LocalReader::load()`. The entries it exposes never
+/// change or move while the guard is held, even as the writer keeps appending.
+///
+/// The log is stored in chunks of doubling size, so the prefix is exposed as a sequence
+/// of contiguous slices through [`chunks`](LogSnapshot::chunks), and as a whole through
+/// [`get`](LogSnapshot::get), indexing and [`iter`](LogSnapshot::iter).
+///
+/// [`SmrLog`] 的不可变前缀。
+///
+/// 读者通过 `LocalReader::load()` 获取快照。在守卫被持有期间，即使写者继续追加，
+/// 它暴露的条目也不会改变或移动。
+///
+/// 日志存储在容量倍增的块中，因此前缀通过 [`chunks`](LogSnapshot::chunks)
+/// 以一系列连续切片的形式暴露，也可以通过 [`get`](LogSnapshot::get)、索引和
+/// [`iter`](LogSnapshot::iter) 作为整体访问。
+pub struct LogSnapshot<T> {
+    chunks: Arc<[Arc<Chunk<T>>]>,
+    len: usize,
+}
+
+impl<T> LogSnapshot<T> {
+    #[inline]
+    fn empty() -> Self {
+        Self {
+            chunks: Arc::default(),
+            len: 0,
+        }
+    }
+
+    /// Get the number of entries visible in this snapshot.
+    ///
+    /// 获取此快照中可见的条目数量。
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether this snapshot is empty.
+    ///
+    /// 检查此快照是否为空。
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the entry at `index`, if it is part of this snapshot.
+    ///
+    /// 获取 `index` 处的条目（如果它属于此快照）。
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        let (chunk, offset) = locate(index);
+        // Safety: every index below `len` was initialized before this snapshot was published.
+        Some(unsafe { &self.chunks[chunk].prefix(offset + 1)[offset] })
+    }
+
+    /// Get the last entry of this snapshot.
+    ///
+    /// 获取此快照的最后一个条目。
+    #[inline]
+    pub fn last(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Iterate over the visible entries as contiguous slices, in log order.
+    ///
+    /// 按日志顺序以连续切片的形式遍历可见条目。
+    pub fn chunks(&self) -> impl Iterator<Item = &[T]> + '_ {
+        let mut remaining = self.len;
+        self.chunks.iter().map_while(move |chunk| {
+            if remaining == 0 {
+                return None;
+            }
+            let len = remaining.min(chunk.slots.len());
+            remaining -= len;
+            // Safety: the first `len` entries of the chunk are part of this snapshot.
+            Some(unsafe { chunk.prefix(len) })
+        })
+    }
+
+    /// Iterate over the visible entries in log order.
+    ///
+    /// 按日志顺序遍历可见条目。
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.chunks().flatten()
+    }
+
+    /// Copy the visible entries into a `Vec`.
+    ///
+    /// 将可见条目复制到 `Vec` 中。
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut vec = Vec::with_capacity(self.len);
+        for chunk in self.chunks() {
+            vec.extend_from_slice(chunk);
+        }
+        vec
+    }
+}
+
+impl<T> Index<usize> for LogSnapshot<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len, index
+            ),
+        }
+    }
+}
+
+impl<T> Clone for LogSnapshot<T> {
+    /// Cloning a snapshot is cheap: the chunks are shared, no entries are copied.
+    ///
+    /// 克隆快照的开销很小：块是共享的，不复制任何条目。
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            chunks: self.chunks.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LogSnapshot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An append-only log with a single writer and lock-free readers.
+///
+/// Appending writes the new entry in place and publishes a snapshot that only extends
+/// the visible length, so existing entries are never copied. Each reader sees a stable
+/// prefix of the log as of its pin.
+///
+/// Snapshots are retired and reclaimed through the regular [`SmrSwap`] version mechanism.
+/// After [`clear`](SmrLog::clear), the old chunks are freed once the last snapshot that can
+/// reach them has been reclaimed.
+///
+/// 带有单个写者和无锁读者的只追加日志。
+///
+/// 追加操作原地写入新条目，并发布一个只扩展可见长度的快照，因此现有条目永远不会被复制。
+/// 每个读者看到的是 pin 时日志的稳定前缀。
+///
+/// 快照通过常规的 [`SmrSwap`] 版本机制退休和回收。
+/// 在 [`clear`](SmrLog::clear) 之后，旧块会在最后一个能访问它们的快照被回收后释放。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::SmrLog;
+///
+/// let mut log = SmrLog::new();
+/// let local = log.local();
+///
+/// log.push("started");
+/// let before = local.load();
+///
+/// log.extend(["step 1", "step 2"]);
+///
+/// assert_eq!(before.len(), 1);
+/// assert_eq!(local.load().iter().copied().collect::<Vec<_>>(), ["started", "step 1", "step 2"]);
+/// ```
+pub struct SmrLog<T: 'static> {
+    swap: SmrSwap<LogSnapshot<T>>,
+}
+
+impl<T: 'static> SmrLog<T> {
+    /// Create an empty log.
+    ///
+    /// 创建一个空日志。
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            swap: SmrSwap::new(LogSnapshot::empty()),
+        }
+    }
+
+    /// Create a new thread-local reader for this log.
+    ///
+    /// 为此日志创建一个新的线程本地读取者。
+    #[inline]
+    pub fn local(&self) -> LocalReader<LogSnapshot<T>> {
+        self.swap.local()
+    }
+
+    /// Create a new `SmrReader` that can be shared across threads.
+    ///
+    /// 创建一个新的 `SmrReader`，可以在线程之间共享。
+    #[inline]
+    pub fn reader(&self) -> SmrReader<LogSnapshot<T>> {
+        self.swap.reader()
+    }
+
+    /// Read the current snapshot using the writer's internal reader.
+    ///
+    /// 使用写者的内部读取者读取当前快照。
+    #[inline]
+    pub fn load(&self) -> ReadGuard<'_, LogSnapshot<T>> {
+        self.swap.load()
+    }
+
+    /// Get the current snapshot (writer-only, no pinning required).
+    ///
+    /// 获取当前快照（仅写者可用，无需 pin）。
+    #[inline]
+    pub fn snapshot(&self) -> &LogSnapshot<T> {
+        self.swap.get()
+    }
+
+    /// Get the number of entries in the log.
+    ///
+    /// 获取日志中的条目数量。
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.snapshot().len()
+    }
+
+    /// Check whether the log is empty.
+    ///
+    /// 检查日志是否为空。
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.snapshot().is_empty()
+    }
+
+    /// Get the entry at `index` (writer-only, no pinning required).
+    ///
+    /// 获取 `index` 处的条目（仅写者可用，无需 pin）。
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.snapshot().get(index)
+    }
+
+    /// Append an entry and publish it to readers.
+    ///
+    /// 追加一个条目并将其发布给读者。
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        self.extend(core::iter::once(value));
+    }
+
+    /// Remove all entries by publishing an empty snapshot.
+    ///
+    /// Readers holding older snapshots keep seeing their entries until they unpin.
+    ///
+    /// 通过发布空快照移除所有条目。
+    ///
+    /// 持有旧快照的读者在解除 pin 之前仍能看到其中的条目。
+    #[inline]
+    pub fn clear(&mut self) {
+        if !self.is_empty() {
+            self.swap.store(LogSnapshot::empty());
+        }
+    }
+
+    /// Get the current global version.
+    ///
+    /// The version is incremented once per published snapshot.
+    ///
+    /// 获取当前全局版本。
+    ///
+    /// 每发布一个快照，版本增加一次。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.swap.version()
+    }
+
+    /// Get the number of retired snapshots waiting for garbage collection.
+    ///
+    /// 获取等待垃圾回收的已退休快照数量。
+    #[inline]
+    pub fn garbage_count(&self) -> usize {
+        self.swap.garbage_count()
+    }
+
+    /// Manually trigger garbage collection.
+    ///
+    /// 手动触发垃圾回收。
+    #[inline]
+    pub fn collect(&mut self) {
+        self.swap.collect();
+    }
+}
+
+impl<T: 'static> Extend<T> for SmrLog<T> {
+    /// Append all entries from the iterator and publish them as a single snapshot.
+    ///
+    /// 追加迭代器中的所有条目，并作为单个快照发布。
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut appender = Appender {
+            len: self.len(),
+            chunks: None,
+            swap: &mut self.swap,
+        };
+        for value in iter {
+            appender.push(value);
+        }
+    }
+}
+
+/// Writes entries beyond the published length and publishes them when dropped.
+///
+/// Publishing on drop means entries appended before a panicking iterator unwinds are
+/// kept, matching `Vec::extend`.
+///
+/// 在已发布长度之后写入条目，并在 drop 时发布它们。
+///
+/// 在 drop 时发布意味着迭代器 panic 之前追加的条目会被保留，与 `Vec::extend` 一致。
+struct Appender<'a, T: 'static> {
+    swap: &'a mut SmrSwap<LogSnapshot<T>>,
+    chunks: Option<Vec<Arc<Chunk<T>>>>,
+    len: usize,
+}
+
+impl<T: 'static> Appender<'_, T> {
+    fn push(&mut self, value: T) {
+        let (chunk, _) = locate(self.len);
+        let current = &self.swap.get().chunks;
+        if chunk == self.chunks.as_deref().unwrap_or(current).len() {
+            self.chunks
+                .get_or_insert_with(|| current.to_vec())
+                .push(Arc::new(Chunk::with_capacity(
+                    FIRST_CHUNK_CAPACITY << chunk,
+                )));
+        }
+        let table = self.chunks.as_deref().unwrap_or(current);
+        debug_assert!(!table[chunk].is_full());
+        // Safety: we are the only writer and `len` is beyond every published snapshot.
+        unsafe { table[chunk].push(value) };
+        self.len += 1;
+    }
+}
+
+impl<T: 'static> Drop for Appender<'_, T> {
+    fn drop(&mut self) {
+        let current = self.swap.get();
+        if self.len == current.len {
+            return;
+        }
+        let chunks = match self.chunks.take() {
+            Some(chunks) => chunks.into(),
+            None => current.chunks.clone(),
+        };
+        self.swap.store(LogSnapshot {
+            chunks,
+            len: self.len,
+        });
+    }
+}
+
+// ============================================================================
+// Standard Trait Implementations
+// 标准 trait 实现
+// ============================================================================
+
+impl<T: 'static> Default for SmrLog<T> {
+    /// Create an empty log.
+    ///
+    /// 创建一个空日志。
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> FromIterator<T> for SmrLog<T> {
+    /// Create a log from an iterator of entries.
+    ///
+    /// 从条目迭代器创建日志。
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut log = Self::new();
+        log.extend(iter);
+        log
+    }
+}
+
+impl<T: fmt::Debug + 'static> fmt::Debug for SmrLog<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmrLog")
+            .field("entries", self.snapshot())
+            .field("version", &self.version())
+            .field("garbage_count", &self.garbage_count())
+            .finish()
+    }
+}
@@ -0,0 +1,663 @@
+//! Copy-on-write concurrent hash map built on [`SmrSwap`].
+//!
+//! 构建在 [`SmrSwap`] 之上的写时复制并发哈希表。
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::sync::Arc;
+
+use crate::{LocalReader, ReadGuard, SmrReader, SmrSwap};
+
+/// Number of shards a snapshot is split into.
+///
+/// A write only clones the shards it touches, so a single insert copies roughly
+/// `1 / SHARD_COUNT` of the map instead of the whole map. This is not structural sharing:
+/// the copy is still linear in the size of the map.
+///
+/// 快照被拆分成的分片数量。
+///
+/// 写入只会克隆它触及的分片，因此单次插入大约只复制 `1 / SHARD_COUNT` 的数据，而不是整个表。
+/// 这并不是结构共享：复制的开销仍与表的大小成线性关系。
+const SHARD_COUNT: usize = 16;
+
+/// An immutable snapshot of an [`SmrMap`].
+///
+/// Readers obtain a snapshot through `LocalReader::load()`, which returns a
+/// `ReadGuard<MapSnapshot<K, V, S>>`. The snapshot stays valid and unchanged
+/// for as long as the guard is held.
+///
+/// [`SmrMap`] 的不可变快照。
+///
+/// 读者通过 `LocalReader::load()` 获取快照，它返回 `ReadGuard<MapSnapshot<K, V, S>>`。
+/// 只要守卫被持有，快照就保持有效且不变。
+pub struct MapSnapshot<K, V, S = RandomState> {
+    shards: Arc<[Arc<HashMap<K, V, S>>]>,
+    hasher: S,
+    len: usize,
+}
+
+impl<K, V, S> MapSnapshot<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    #[inline]
+    fn shard_of<Q>(&self, key: &Q) -> &HashMap<K, V, S>
+    where
+        Q: Hash + ?Sized,
+    {
+        &self.shards[shard_index(&self.hasher, key)]
+    }
+
+    /// Get a reference to the value corresponding to the key.
+    ///
+    /// 获取键对应值的引用。
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shard_of(key).get(key)
+    }
+
+    /// Get the key-value pair corresponding to the key.
+    ///
+    /// 获取键对应的键值对。
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shard_of(key).get_key_value(key)
+    }
+
+    /// Check whether the snapshot contains the key.
+    ///
+    /// 检查快照是否包含该键。
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shard_of(key).contains_key(key)
+    }
+}
+
+impl<K, V, S> MapSnapshot<K, V, S> {
+    /// Get the number of entries in the snapshot.
+    ///
+    /// 获取快照中的条目数量。
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the snapshot is empty.
+    ///
+    /// 检查快照是否为空。
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over all key-value pairs in arbitrary order.
+    ///
+    /// 以任意顺序遍历所有键值对。
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.shards.iter().flat_map(|shard| shard.iter())
+    }
+
+    /// Iterate over all keys in arbitrary order.
+    ///
+    /// 以任意顺序遍历所有键。
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    /// Iterate over all values in arbitrary order.
+    ///
+    /// 以任意顺序遍历所有值。
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, S: Clone> Clone for MapSnapshot<K, V, S> {
+    /// Cloning a snapshot is cheap: only the shard table is shared, no entries are copied.
+    ///
+    /// 克隆快照的开销很小：只共享分片表，不复制任何条目。
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            shards: self.shards.clone(),
+            hasher: self.hasher.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for MapSnapshot<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[inline]
+fn shard_index<Q, S>(hasher: &S, key: &Q) -> usize
+where
+    Q: Hash + ?Sized,
+    S: BuildHasher,
+{
+    // `HashMap` uses the low bits of the hash for bucket selection and the top bits as
+    // control tags, so mix the hash before taking the shard index to avoid correlating
+    // with either of them.
+    // `HashMap` 使用哈希的低位选择桶、高位作为控制标签，
+    // 因此在取分片索引前先混合哈希，避免与两者相关。
+    let hash = hasher.hash_one(key).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    (hash >> (u64::BITS - SHARD_COUNT.trailing_zeros())) as usize
+}
+
+/// A copy-on-write hash map with lock-free readers.
+///
+/// `SmrMap` is a single-writer, multi-reader map backed by an [`SmrSwap`].
+/// The map is split into 16 shards that are shared between snapshots, so a write
+/// only clones the shards it modifies. Every write method publishes exactly one
+/// new snapshot, and [`batch`](SmrMap::batch) lets several modifications share
+/// a single publish.
+///
+/// Sharding is not structural sharing: a single insert still clones a whole shard,
+/// about 1/16 of the map, so writes stay linear in the size of the map. Group writes
+/// with [`batch`](SmrMap::batch) for large maps that change often.
+///
+/// 带有无锁读者的写时复制哈希表。
+///
+/// `SmrMap` 是由 [`SmrSwap`] 支持的单写多读哈希表。
+/// 哈希表被拆分成 16 个在快照之间共享的分片，因此写入只会克隆它修改的分片。
+/// 每个写方法恰好发布一个新快照，[`batch`](SmrMap::batch) 允许多个修改共享一次发布。
+///
+/// 分片并不是结构共享：单次插入仍会克隆整个分片（约占表的 1/16），因此写入开销仍与表的大小
+/// 成线性关系。对于频繁修改的大型表，请使用 [`batch`](SmrMap::batch) 合并写入。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::SmrMap;
+///
+/// let mut map = SmrMap::new();
+/// let local = map.local();
+///
+/// map.insert("a", 1);
+/// map.extend([("b", 2), ("c", 3)]);
+///
+/// let guard = local.load();
+/// assert_eq!(guard.get("a"), Some(&1));
+/// assert!(guard.contains_key("c"));
+/// assert_eq!(guard.len(), 3);
+/// ```
+pub struct SmrMap<K: 'static, V: 'static, S: 'static = RandomState> {
+    swap: SmrSwap<MapSnapshot<K, V, S>>,
+}
+
+impl<K, V> SmrMap<K, V, RandomState> {
+    /// Create an empty map.
+    ///
+    /// 创建一个空的哈希表。
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K, V, S> SmrMap<K, V, S>
+where
+    S: BuildHasher + Clone,
+{
+    /// Create an empty map which will use the given hash builder.
+    ///
+    /// 创建一个使用给定哈希构建器的空哈希表。
+    pub fn with_hasher(hasher: S) -> Self {
+        let shards: Arc<[_]> = (0..SHARD_COUNT)
+            .map(|_| Arc::new(HashMap::with_hasher(hasher.clone())))
+            .collect();
+        Self {
+            swap: SmrSwap::new(MapSnapshot {
+                shards,
+                hasher,
+                len: 0,
+            }),
+        }
+    }
+}
+
+impl<K, V, S> SmrMap<K, V, S> {
+    /// Create a new thread-local reader for this map.
+    ///
+    /// 为此哈希表创建一个新的线程本地读取者。
+    #[inline]
+    pub fn local(&self) -> LocalReader<MapSnapshot<K, V, S>> {
+        self.swap.local()
+    }
+
+    /// Create a new `SmrReader` that can be shared across threads.
+    ///
+    /// 创建一个新的 `SmrReader`，可以在线程之间共享。
+    #[inline]
+    pub fn reader(&self) -> SmrReader<MapSnapshot<K, V, S>> {
+        self.swap.reader()
+    }
+
+    /// Read the current snapshot using the writer's internal reader.
+    ///
+    /// 使用写者的内部读取者读取当前快照。
+    #[inline]
+    pub fn load(&self) -> ReadGuard<'_, MapSnapshot<K, V, S>> {
+        self.swap.load()
+    }
+
+    /// Get the current snapshot (writer-only, no pinning required).
+    ///
+    /// 获取当前快照（仅写者可用，无需 pin）。
+    #[inline]
+    pub fn snapshot(&self) -> &MapSnapshot<K, V, S> {
+        self.swap.get()
+    }
+
+    /// Get the number of entries in the map.
+    ///
+    /// 获取哈希表中的条目数量。
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.snapshot().len()
+    }
+
+    /// Check whether the map is empty.
+    ///
+    /// 检查哈希表是否为空。
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.snapshot().is_empty()
+    }
+
+    /// Get the current global version.
+    ///
+    /// The version is incremented once per published snapshot.
+    ///
+    /// 获取当前全局版本。
+    ///
+    /// 每发布一个快照，版本增加一次。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.swap.version()
+    }
+
+    /// Get the number of retired snapshots waiting for garbage collection.
+    ///
+    /// 获取等待垃圾回收的已退休快照数量。
+    #[inline]
+    pub fn garbage_count(&self) -> usize {
+        self.swap.garbage_count()
+    }
+
+    /// Manually trigger garbage collection.
+    ///
+    /// 手动触发垃圾回收。
+    #[inline]
+    pub fn collect(&mut self) {
+        self.swap.collect();
+    }
+}
+
+impl<K, V, S> SmrMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Get a reference to the value corresponding to the key (writer-only, no pinning required).
+    ///
+    /// 获取键对应值的引用（仅写者可用，无需 pin）。
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.snapshot().get(key)
+    }
+
+    /// Check whether the map contains the key (writer-only, no pinning required).
+    ///
+    /// 检查哈希表是否包含该键（仅写者可用，无需 pin）。
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.snapshot().contains_key(key)
+    }
+}
+
+impl<K, V, S> SmrMap<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    /// Apply several modifications and publish them as a single snapshot.
+    ///
+    /// Readers observe either none or all of the modifications made inside the closure.
+    /// Shards are cloned at most once per batch, no matter how many entries are touched.
+    ///
+    /// 应用多个修改并作为单个快照发布。
+    ///
+    /// 读者要么看不到闭包中的任何修改，要么看到全部修改。
+    /// 无论触及多少条目，每个分片在一次批处理中最多被克隆一次。
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use smr_swap::SmrMap;
+    ///
+    /// let mut map = SmrMap::new();
+    /// map.insert(1, "one");
+    ///
+    /// let version = map.version();
+    /// map.batch(|batch| {
+    ///     batch.insert(2, "two");
+    ///     batch.remove(&1);
+    /// });
+    ///
+    /// assert_eq!(map.version(), version + 1);
+    /// assert_eq!(map.get(&2), Some(&"two"));
+    /// assert!(!map.contains_key(&1));
+    /// ```
+    pub fn batch<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut MapBatch<K, V, S>) -> R,
+    {
+        let current = self.swap.get();
+        let mut batch = MapBatch {
+            shards: current.shards.to_vec(),
+            hasher: current.hasher.clone(),
+            len: current.len,
+        };
+        let result = f(&mut batch);
+        self.swap.store(batch.into_snapshot());
+        result
+    }
+
+    /// Insert a key-value pair, returning the previous value for the key, if any.
+    ///
+    /// 插入一个键值对，如果该键之前存在值则返回旧值。
+    #[inline]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.batch(|batch| batch.insert(key, value))
+    }
+
+    /// Remove a key, returning its value if it was present.
+    ///
+    /// Nothing is published if the key is absent.
+    ///
+    /// 移除一个键，如果存在则返回其值。
+    ///
+    /// 如果键不存在，则不会发布新快照。
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return None;
+        }
+        self.batch(|batch| batch.remove(key))
+    }
+
+    /// Remove all entries.
+    ///
+    /// 移除所有条目。
+    #[inline]
+    pub fn clear(&mut self) {
+        self.batch(|batch| batch.clear());
+    }
+
+    /// Retain only the entries for which the predicate returns `true`.
+    ///
+    /// 只保留谓词返回 `true` 的条目。
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.batch(|batch| batch.retain(f));
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for SmrMap<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    /// Insert all pairs from the iterator and publish them as a single snapshot.
+    ///
+    /// 插入迭代器中的所有键值对，并作为单个快照发布。
+    #[inline]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.batch(|batch| batch.extend(iter));
+    }
+}
+
+/// A pending set of modifications to an [`SmrMap`].
+///
+/// Created by [`SmrMap::batch`]. Shards are cloned lazily the first time they are
+/// modified, and the result is published when the closure returns.
+///
+/// [`SmrMap`] 的一组待提交修改。
+///
+/// 由 [`SmrMap::batch`] 创建。分片在第一次被修改时才被克隆，闭包返回时发布结果。
+pub struct MapBatch<K, V, S = RandomState> {
+    shards: Vec<Arc<HashMap<K, V, S>>>,
+    hasher: S,
+    len: usize,
+}
+
+impl<K, V, S> MapBatch<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    #[inline]
+    fn shard_mut<Q>(&mut self, key: &Q) -> &mut HashMap<K, V, S>
+    where
+        Q: Hash + ?Sized,
+    {
+        let index = shard_index(&self.hasher, key);
+        Arc::make_mut(&mut self.shards[index])
+    }
+
+    /// Get a reference to the value corresponding to the key, including pending modifications.
+    ///
+    /// 获取键对应值的引用，包括待提交的修改。
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shards[shard_index(&self.hasher, key)].get(key)
+    }
+
+    /// Check whether the key is present, including pending modifications.
+    ///
+    /// 检查键是否存在，包括待提交的修改。
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Insert a key-value pair, returning the previous value for the key, if any.
+    ///
+    /// 插入一个键值对，如果该键之前存在值则返回旧值。
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let old = self.shard_mut(&key).insert(key, value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Remove a key, returning its value if it was present.
+    ///
+    /// 移除一个键，如果存在则返回其值。
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return None;
+        }
+        let old = self.shard_mut(key).remove(key);
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Remove all entries.
+    ///
+    /// 移除所有条目。
+    pub fn clear(&mut self) {
+        for shard in &mut self.shards {
+            if !shard.is_empty() {
+                *shard = Arc::new(HashMap::with_hasher(self.hasher.clone()));
+            }
+        }
+        self.len = 0;
+    }
+
+    /// Retain only the entries for which the predicate returns `true`.
+    ///
+    /// The predicate is called exactly once per entry. Shards in which every entry is
+    /// retained are not cloned.
+    ///
+    /// 只保留谓词返回 `true` 的条目。
+    ///
+    /// 谓词对每个条目恰好调用一次。所有条目都被保留的分片不会被克隆。
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        for shard in &mut self.shards {
+            // The predicate runs once per entry, on the shared shard, and only the keys it
+            // rejects are removed from the clone.
+            // 谓词对每个条目只运行一次（在共享的分片上），只有被其拒绝的键才会从克隆中移除。
+            let removed: Vec<K> = shard
+                .iter()
+                .filter(|(k, v)| !f(k, v))
+                .map(|(k, _)| k.clone())
+                .collect();
+            if removed.is_empty() {
+                continue;
+            }
+            let shard = Arc::make_mut(shard);
+            for key in &removed {
+                shard.remove(key);
+            }
+            self.len -= removed.len();
+        }
+    }
+
+    /// Get the number of entries, including pending modifications.
+    ///
+    /// 获取条目数量，包括待提交的修改。
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the map is empty, including pending modifications.
+    ///
+    /// 检查哈希表是否为空，包括待提交的修改。
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    fn into_snapshot(self) -> MapSnapshot<K, V, S> {
+        MapSnapshot {
+            shards: self.shards.into(),
+            hasher: self.hasher,
+            len: self.len,
+        }
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for MapBatch<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+// ============================================================================
+// Standard Trait Implementations
+// 标准 trait 实现
+// ============================================================================
+
+impl<K, V> Default for SmrMap<K, V, RandomState> {
+    /// Create an empty map.
+    ///
+    /// 创建一个空的哈希表。
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for SmrMap<K, V, RandomState>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    /// Create a map from an iterator of key-value pairs.
+    ///
+    /// 从键值对迭代器创建哈希表。
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for SmrMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmrMap")
+            .field("entries", self.snapshot())
+            .field("version", &self.version())
+            .field("garbage_count", &self.garbage_count())
+            .finish()
+    }
+}
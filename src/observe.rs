@@ -0,0 +1,201 @@
+//! Instrumentation hooks called by the reclamation engine.
+//!
+//! Every hook compiles to nothing unless an instrumentation feature is enabled.
+//!
+//! 回收引擎调用的插桩钩子。
+//!
+//! 除非启用了插桩 feature，否则每个钩子都会被编译为空操作。
+
+#![allow(unused_variables)]
+
+#[cfg(feature = "metrics")]
+use alloc::borrow::Cow;
+
+/// Guard of an entered span; empty when tracing is disabled.
+///
+/// 已进入的 span 的守卫；未启用 tracing 时为空。
+pub(crate) struct SpanGuard {
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+/// Measures how long a collection spends dropping values.
+///
+/// 测量一次回收在 drop 值上花费的时间。
+pub(crate) struct Timer {
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    start: std::time::Instant,
+}
+
+impl Timer {
+    #[inline(always)]
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(any(feature = "tracing", feature = "metrics"))]
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+/// Per-container metric handles, labelled with the container name; empty when metrics are disabled.
+///
+/// 以容器名称为标签的每个容器的指标句柄；未启用 metrics 时为空。
+pub(crate) struct Metrics {
+    #[cfg(feature = "metrics")]
+    handles: MetricHandles,
+}
+
+#[cfg(feature = "metrics")]
+struct MetricHandles {
+    version: metrics::Gauge,
+    garbage: metrics::Gauge,
+    readers: metrics::Gauge,
+    pinned_readers: metrics::Gauge,
+    stores: metrics::Counter,
+    reclaimed: metrics::Counter,
+    reclaim_duration: metrics::Histogram,
+}
+
+/// Name label used when a container was not given a name.
+///
+/// 容器未命名时使用的名称标签。
+#[cfg(feature = "metrics")]
+pub(crate) const DEFAULT_NAME: &str = "unnamed";
+
+impl Metrics {
+    #[cfg(feature = "metrics")]
+    pub(crate) fn new(name: Option<&Cow<'static, str>>, version: usize) -> Self {
+        let name = name.cloned().unwrap_or(Cow::Borrowed(DEFAULT_NAME));
+        let handles = MetricHandles {
+            version: metrics::gauge!("smr_swap_version", "name" => name.clone()),
+            garbage: metrics::gauge!("smr_swap_garbage_count", "name" => name.clone()),
+            readers: metrics::gauge!("smr_swap_readers", "name" => name.clone()),
+            pinned_readers: metrics::gauge!("smr_swap_pinned_readers", "name" => name.clone()),
+            stores: metrics::counter!("smr_swap_stores_total", "name" => name.clone()),
+            reclaimed: metrics::counter!("smr_swap_reclaimed_total", "name" => name.clone()),
+            reclaim_duration: metrics::histogram!(
+                "smr_swap_reclaim_duration_seconds",
+                "name" => name
+            ),
+        };
+        handles.version.set(version as f64);
+        handles.garbage.set(0.0);
+        Self { handles }
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    #[inline(always)]
+    pub(crate) fn new<N>(name: Option<N>, version: usize) -> Self {
+        Self {}
+    }
+}
+
+/// Enter the span of a writer operation (`store`, `update`, ...).
+///
+/// 进入写者操作（`store`、`update` 等）的 span。
+#[inline(always)]
+pub(crate) fn write_span(op: &'static str) -> SpanGuard {
+    SpanGuard {
+        #[cfg(feature = "tracing")]
+        _span: tracing::trace_span!("smr_swap::write", op).entered(),
+    }
+}
+
+/// A new value was published.
+///
+/// 发布了一个新值。
+#[inline(always)]
+pub(crate) fn stored(metrics: &Metrics, old_version: usize, new_version: usize, garbage: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(
+        old_version,
+        new_version,
+        garbage,
+        "smr-swap stored a new value"
+    );
+    #[cfg(feature = "metrics")]
+    {
+        metrics.handles.version.set(new_version as f64);
+        metrics.handles.garbage.set(garbage as f64);
+        metrics.handles.stores.increment(1);
+    }
+}
+
+/// Enter the span of a garbage collection.
+///
+/// 进入垃圾回收的 span。
+#[inline(always)]
+pub(crate) fn collect_span(automatic: bool) -> SpanGuard {
+    SpanGuard {
+        #[cfg(feature = "tracing")]
+        _span: tracing::debug_span!("smr_swap::collect", automatic).entered(),
+    }
+}
+
+/// A garbage collection finished.
+///
+/// 一次垃圾回收已完成。
+#[inline(always)]
+pub(crate) fn collected(metrics: &Metrics, reclaimed: usize, remaining: usize, timer: Timer) {
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    let elapsed = timer.start.elapsed();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        reclaimed,
+        remaining,
+        drop_time = ?elapsed,
+        "smr-swap reclaimed retired values"
+    );
+    #[cfg(feature = "metrics")]
+    {
+        metrics.handles.garbage.set(remaining as f64);
+        metrics.handles.reclaimed.increment(reclaimed as u64);
+        metrics
+            .handles
+            .reclaim_duration
+            .record(elapsed.as_secs_f64());
+    }
+}
+
+/// A collection scanned the reader slots of `readers` live readers.
+///
+/// 一次回收扫描了 `readers` 个存活读者的读者槽。
+#[inline(always)]
+pub(crate) fn readers_scanned(metrics: &Metrics, readers: usize, pinned: usize) {
+    #[cfg(feature = "metrics")]
+    {
+        metrics.handles.readers.set(readers as f64);
+        metrics.handles.pinned_readers.set(pinned as f64);
+    }
+}
+
+/// An adaptive container switched to the `next` algorithm.
+///
+/// 自适应容器切换到了 `next` 算法。
+#[inline(always)]
+pub(crate) fn strategy_switched(metrics: &Metrics, next: crate::Strategy) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(strategy = ?next, "smr-swap switched strategy");
+}
+
+/// A reader was registered.
+///
+/// 注册了一个读者。
+#[inline(always)]
+pub(crate) fn reader_registered(metrics: &Metrics, readers: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(readers, "smr-swap reader registered");
+    #[cfg(feature = "metrics")]
+    metrics.handles.readers.increment(1.0);
+}
+
+/// A reader was dropped; its slot is free for the next reader.
+///
+/// 一个读者被 drop；其槽留给下一个读者。
+#[inline(always)]
+pub(crate) fn reader_deregistered(metrics: &Metrics) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!("smr-swap reader deregistered");
+    #[cfg(feature = "metrics")]
+    metrics.handles.readers.decrement(1.0);
+}
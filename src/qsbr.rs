@@ -0,0 +1,243 @@
+//! Quiescent-state-based reader for [`SmrSwap`](crate::SmrSwap).
+//!
+//! A [`QsbrReader`] announces a version like a pinned [`LocalReader`](crate::LocalReader),
+//! but instead of pinning on every load it stays pinned all the time and moves its
+//! version forward when the thread calls [`quiescent`](QsbrReader::quiescent). The writer
+//! reclaims retired values older than every announced version, exactly as for pinned
+//! readers, so both kinds of readers can share a container.
+//!
+//! Moving the version forward only ever lets the writer reclaim more, never values the
+//! reader can still reach: a stale announcement is older, and therefore more conservative.
+//! No fence is needed on either side, and `load` is a single acquire load.
+//!
+//! [`SmrSwap`](crate::SmrSwap) 的基于静止状态的读者。
+//!
+//! [`QsbrReader`] 像被 pin 的 [`LocalReader`](crate::LocalReader) 一样公布一个版本，
+//! 但它不在每次加载时 pin，而是始终保持 pin，并在线程调用 [`quiescent`](QsbrReader::quiescent)
+//! 时推进其版本。写者回收比所有已公布版本都旧的已退休值，与被 pin 的读者完全相同，
+//! 因此两种读者可以共享同一个容器。
+//!
+//! 推进版本只会让写者回收更多的值，而不会回收读者仍可访问的值：过时的公布版本更旧，因此更保守。
+//! 双方都不需要 fence，`load` 只是一次 acquire 加载。
+
+use core::fmt;
+
+use crate::Strategy;
+use crate::backend::CapacityError;
+use crate::cell::{INACTIVE_VERSION, ReaderSlot, Shared};
+use crate::shim::{Arc, Ordering, light_barrier};
+
+/// Reader that is always pinned and reports quiescent states instead of taking guards.
+///
+/// [`load`](QsbrReader::load) does no atomic work beyond one acquire load and returns a
+/// plain reference. [`quiescent`](QsbrReader::quiescent) takes `&mut self`, so the borrow
+/// checker guarantees that no reference from an earlier load survives it.
+///
+/// Until the thread calls `quiescent`, the writer keeps every value retired since the last
+/// call, so call it at loop boundaries. Drop the reader when the thread stops reading;
+/// a reader that is neither dropped nor quiescent blocks reclamation like a leaked guard.
+///
+/// Create one with [`SmrSwap::qsbr_reader`](crate::SmrSwap::qsbr_reader) or
+/// [`SmrReader::qsbr_reader`](crate::SmrReader::qsbr_reader).
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::SmrSwap;
+///
+/// let mut swap = SmrSwap::new(0);
+/// let mut reader = swap.qsbr_reader();
+///
+/// for i in 1..=3 {
+///     swap.store(i);
+///     // One iteration of a processing loop.
+///     assert_eq!(*reader.load(), i);
+///     reader.quiescent();
+/// }
+/// ```
+///
+/// 始终被 pin 并报告静止状态而不是获取守卫的读者。
+///
+/// [`load`](QsbrReader::load) 除一次 acquire 加载外不做任何原子操作，并返回普通引用。
+/// [`quiescent`](QsbrReader::quiescent) 接收 `&mut self`，因此借用检查器保证之前加载得到的引用
+/// 都不会在其之后存活。
+///
+/// 在线程调用 `quiescent` 之前，写者会保留自上次调用以来退休的所有值，因此应在循环边界调用它。
+/// 线程停止读取时应 drop 读者；既不被 drop 也不报告静止状态的读者会像被泄漏的守卫一样阻塞回收。
+///
+/// 通过 [`SmrSwap::qsbr_reader`](crate::SmrSwap::qsbr_reader) 或
+/// [`SmrReader::qsbr_reader`](crate::SmrReader::qsbr_reader) 创建。
+pub struct QsbrReader<T: 'static> {
+    slot: QsbrSlot,
+    shared: Arc<Shared<T>>,
+}
+
+/// Slot in which a [`QsbrReader`] announces its version.
+///
+/// [`QsbrReader`] 公布其版本的槽。
+enum QsbrSlot {
+    Registered(Arc<ReaderSlot>),
+    Fixed(usize),
+}
+
+impl<T: 'static> QsbrReader<T> {
+    pub(crate) fn new(shared: Arc<Shared<T>>) -> Self {
+        match Self::try_new(shared) {
+            Ok(reader) => reader,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    pub(crate) fn try_new(shared: Arc<Shared<T>>) -> Result<Self, CapacityError> {
+        if shared.is_bounded() {
+            let index = shared.claim_fixed()?;
+            shared.fixed_slot(index).active_version.store(
+                shared.global_version.load(Ordering::Acquire),
+                Ordering::Release,
+            );
+            // There is no lock to order the announcement with scans, so a full fence pairs
+            // with the writer's heavy barrier as in a pin. A scan that missed the
+            // announcement ran before any pointer this reader loads was retired.
+            // 没有锁来使公布与扫描之间有序，因此与 pin 一样使用完整的 fence 与写者的重屏障配对。
+            // 错过此次公布的扫描运行于此读者加载的任何指针退休之前。
+            light_barrier(Strategy::WritePreferred);
+            return Ok(Self {
+                slot: QsbrSlot::Fixed(index),
+                shared,
+            });
+        }
+
+        // The version is read under the registry lock, so a concurrent scan either sees the
+        // slot or finished before this version was current.
+        // 版本在注册表锁内读取，因此并发的扫描要么能看到该槽，要么在此版本成为当前版本之前就已完成。
+        let slot = shared.readers.register(
+            &shared.metrics,
+            || ReaderSlot::new(shared.global_version.load(Ordering::Acquire)),
+            |slot| {
+                slot.active_version.store(
+                    shared.global_version.load(Ordering::Acquire),
+                    Ordering::Relaxed,
+                );
+            },
+        );
+
+        Ok(Self {
+            slot: QsbrSlot::Registered(slot),
+            shared,
+        })
+    }
+
+    /// Slot in which this reader announces its version.
+    ///
+    /// 此读者公布其版本的槽。
+    #[inline(always)]
+    fn slot(&self) -> &ReaderSlot {
+        match &self.slot {
+            QsbrSlot::Registered(slot) => slot,
+            QsbrSlot::Fixed(index) => self.shared.fixed_slot(*index),
+        }
+    }
+
+    /// Read the current value.
+    ///
+    /// The reference stays valid until the next call to [`quiescent`](Self::quiescent).
+    ///
+    /// 读取当前值。
+    ///
+    /// 该引用在下一次调用 [`quiescent`](Self::quiescent) 之前一直有效。
+    #[inline(always)]
+    pub fn load(&self) -> &T {
+        // Safety: the announced version is at most the version of any value this reader
+        // loaded since its last quiescent state, so the writer keeps all of them.
+        // 安全性：公布的版本不大于此读者自上次静止状态以来加载的任何值的版本，因此写者会保留它们。
+        unsafe { &*self.shared.ptr.load(Ordering::Acquire) }
+    }
+
+    /// Load the current value and clone it.
+    ///
+    /// 加载当前值并克隆它。
+    #[inline]
+    pub fn load_cloned(&self) -> T
+    where
+        T: Clone,
+    {
+        self.load().clone()
+    }
+
+    /// Report that this thread holds no reference obtained from this reader.
+    ///
+    /// Lets the writer reclaim every value retired before the current version.
+    ///
+    /// 报告此线程不持有从此读者获得的任何引用。
+    ///
+    /// 允许写者回收当前版本之前退休的所有值。
+    #[inline]
+    pub fn quiescent(&mut self) {
+        let version = self.shared.global_version.load(Ordering::Acquire);
+        // Release: every read of an older value happens before the writer sees this version.
+        // Release：对旧值的每次读取都发生在写者看到此版本之前。
+        self.slot().active_version.store(version, Ordering::Release);
+    }
+
+    /// Get the current global version.
+    ///
+    /// 获取当前全局版本。
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.shared.global_version.load(Ordering::Acquire)
+    }
+
+    /// Get the version announced by the last quiescent state.
+    ///
+    /// Values retired at or after this version are kept for this reader.
+    ///
+    /// 获取上一次静止状态所公布的版本。
+    ///
+    /// 在此版本及之后退休的值会为此读者保留。
+    #[inline]
+    pub fn quiescent_version(&self) -> usize {
+        self.slot().active_version.load(Ordering::Relaxed)
+    }
+}
+
+impl<T: 'static> Clone for QsbrReader<T> {
+    /// Create another QSBR reader of the same container.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container bounds its readers and all are in use.
+    ///
+    /// 创建同一容器的另一个 QSBR 读者。
+    ///
+    /// # Panics
+    ///
+    /// 如果容器限制了读者数量且都已被使用，则 panic。
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.shared.clone())
+    }
+}
+
+impl<T: 'static> Drop for QsbrReader<T> {
+    #[inline]
+    fn drop(&mut self) {
+        // Scans keep reading the slot, so it must be inactive before it is freed.
+        // 扫描会继续读取该槽，因此它在被释放之前必须处于非活动状态。
+        self.slot()
+            .active_version
+            .store(INACTIVE_VERSION, Ordering::Release);
+        match &self.slot {
+            QsbrSlot::Registered(slot) => self.shared.readers.release(&self.shared.metrics, slot),
+            QsbrSlot::Fixed(index) => self.shared.release_fixed(*index),
+        }
+    }
+}
+
+impl<T: 'static> fmt::Debug for QsbrReader<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QsbrReader")
+            .field("quiescent_version", &self.quiescent_version())
+            .field("version", &self.version())
+            .finish()
+    }
+}
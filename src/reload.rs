@@ -0,0 +1,348 @@
+//! Hot reloading of files into an `SmrSwap`.
+//!
+//! 将文件热重载到 `SmrSwap` 中。
+
+use core::fmt;
+use core::hash::Hasher;
+use core::time::Duration;
+use std::boxed::Box;
+use std::error::Error;
+use std::fs;
+use std::hash::DefaultHasher;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::SystemTime;
+
+use crate::{LocalReader, SmrReader, SmrSwap};
+
+type ParseFn<T, E> = Box<dyn Fn(&[u8]) -> Result<T, E> + Send>;
+type ErrorFn<E> = Box<dyn Fn(ReloadError<E>) + Send>;
+
+/// Error produced while reloading a file.
+///
+/// 重载文件时产生的错误。
+#[derive(Debug)]
+pub enum ReloadError<E> {
+    /// The file could not be read.
+    ///
+    /// 无法读取文件。
+    Io(io::Error),
+    /// The file was read but could not be parsed.
+    ///
+    /// 文件已读取但无法解析。
+    Parse(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ReloadError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReloadError::Io(err) => write!(f, "failed to read file: {err}"),
+            ReloadError::Parse(err) => write!(f, "failed to parse file: {err}"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for ReloadError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReloadError::Io(err) => Some(err),
+            ReloadError::Parse(err) => Some(err),
+        }
+    }
+}
+
+/// Identity of a file's contents, used to skip reloads when nothing changed.
+///
+/// 文件内容的标识，用于在没有变化时跳过重载。
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fingerprint {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+/// Watches a file by polling and stores every successfully parsed version into an `SmrSwap`.
+///
+/// Each poll compares the file's modification time and size with the last load; when
+/// either changed, the file is read and its contents are hashed, so touching a file
+/// without changing it does not publish a new value. Parse and I/O errors are passed to
+/// the [`on_error`](FileReloader::on_error) callback and the last good value stays in place.
+///
+/// 通过轮询监视文件，并将每个成功解析的版本存储到 `SmrSwap` 中。
+///
+/// 每次轮询都会将文件的修改时间和大小与上次加载时进行比较；当其中任何一个发生变化时，
+/// 会读取文件并对其内容计算哈希，因此只 touch 文件而不改变内容不会发布新值。
+/// 解析和 I/O 错误会传递给 [`on_error`](FileReloader::on_error) 回调，并保留上一个有效值。
+///
+/// # Example
+///
+/// ```rust
+/// use smr_swap::{FileReloader, SmrSwap};
+/// use std::time::Duration;
+///
+/// let path = std::env::temp_dir().join("smr_swap_reload_doc.conf");
+/// std::fs::write(&path, "42").unwrap();
+///
+/// let handle = FileReloader::new(&path, |bytes| {
+///     std::str::from_utf8(bytes).unwrap_or("").trim().parse::<u32>()
+/// })
+/// .interval(Duration::from_millis(50))
+/// .on_error(|err| eprintln!("config reload failed: {err}"))
+/// .spawn(SmrSwap::new(0));
+///
+/// assert!(handle.reload_now());
+/// assert_eq!(*handle.local().load(), 42);
+///
+/// let swap = handle.stop();
+/// assert_eq!(*swap.get(), 42);
+/// # std::fs::remove_file(&path).ok();
+/// ```
+pub struct FileReloader<T: 'static, E: 'static> {
+    path: PathBuf,
+    parse: ParseFn<T, E>,
+    on_error: ErrorFn<E>,
+    interval: Duration,
+}
+
+impl<T: Send + Sync + 'static, E: 'static> FileReloader<T, E> {
+    /// Create a reloader for `path` that parses the file contents with `parse`.
+    ///
+    /// The default polling interval is one second and errors are ignored.
+    ///
+    /// 为 `path` 创建一个重载器，使用 `parse` 解析文件内容。
+    ///
+    /// 默认轮询间隔为一秒，并且会忽略错误。
+    pub fn new<P, F>(path: P, parse: F) -> Self
+    where
+        P: AsRef<Path>,
+        F: Fn(&[u8]) -> Result<T, E> + Send + 'static,
+    {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            parse: Box::new(parse),
+            on_error: Box::new(|_| {}),
+            interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Set how often the file is polled for changes.
+    ///
+    /// 设置轮询文件变化的频率。
+    #[inline]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the callback that receives read and parse errors.
+    ///
+    /// The callback runs on the reload thread.
+    ///
+    /// 设置接收读取和解析错误的回调。
+    ///
+    /// 回调在重载线程上运行。
+    #[inline]
+    pub fn on_error<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ReloadError<E>) + Send + 'static,
+    {
+        self.on_error = Box::new(f);
+        self
+    }
+
+    /// Move the writer into a background thread that keeps it in sync with the file.
+    ///
+    /// The file is loaded once immediately, then polled at the configured interval.
+    ///
+    /// 将写者移入一个后台线程，使其与文件保持同步。
+    ///
+    /// 文件会立即加载一次，之后按配置的间隔轮询。
+    pub fn spawn(self, swap: SmrSwap<T>) -> ReloadHandle<T> {
+        let reader = swap.reader();
+        let (commands, receiver) = mpsc::channel::<Command>();
+
+        let thread = thread::Builder::new()
+            .name(String::from("smr-swap-reload"))
+            .spawn(move || {
+                let mut state = ReloadState {
+                    reloader: self,
+                    swap,
+                    last: None,
+                };
+                state.poll(false);
+
+                loop {
+                    match receiver.recv_timeout(state.reloader.interval) {
+                        Ok(Command::Reload(reply)) => {
+                            let _ = reply.send(state.poll(true));
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            state.poll(false);
+                        }
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+
+                state.swap
+            })
+            .expect("failed to spawn reload thread");
+
+        ReloadHandle {
+            reader,
+            commands: Some(commands),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl<T: 'static, E: 'static> fmt::Debug for FileReloader<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileReloader")
+            .field("path", &self.path)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+enum Command {
+    Reload(Sender<bool>),
+}
+
+struct ReloadState<T: 'static, E: 'static> {
+    reloader: FileReloader<T, E>,
+    swap: SmrSwap<T>,
+    last: Option<Fingerprint>,
+}
+
+impl<T: 'static, E: 'static> ReloadState<T, E> {
+    /// Reload the file if it changed (or unconditionally when `force` is set).
+    ///
+    /// Returns `true` if a new value was stored.
+    ///
+    /// 如果文件发生了变化（或设置了 `force`），则重载文件。
+    ///
+    /// 如果存储了新值，则返回 `true`。
+    fn poll(&mut self, force: bool) -> bool {
+        match self.try_poll(force) {
+            Ok(stored) => stored,
+            Err(err) => {
+                (self.reloader.on_error)(err);
+                false
+            }
+        }
+    }
+
+    fn try_poll(&mut self, force: bool) -> Result<bool, ReloadError<E>> {
+        let metadata = fs::metadata(&self.reloader.path).map_err(ReloadError::Io)?;
+        let modified = metadata.modified().ok();
+        let len = metadata.len();
+
+        if !force
+            && let Some(last) = self.last
+            && last.modified == modified
+            && last.len == len
+        {
+            return Ok(false);
+        }
+
+        let bytes = fs::read(&self.reloader.path).map_err(ReloadError::Io)?;
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&bytes);
+        let fingerprint = Fingerprint {
+            modified,
+            len,
+            hash: hasher.finish(),
+        };
+
+        if !force && self.last.map(|last| last.hash) == Some(fingerprint.hash) {
+            self.last = Some(fingerprint);
+            return Ok(false);
+        }
+
+        let value = (self.reloader.parse)(&bytes).map_err(ReloadError::Parse)?;
+        self.swap.store(value);
+        self.last = Some(fingerprint);
+        Ok(true)
+    }
+}
+
+/// Handle to a running [`FileReloader`].
+///
+/// Dropping the handle stops the reload thread and drops the container's writer;
+/// use [`stop`](ReloadHandle::stop) to get the writer back instead.
+///
+/// 正在运行的 [`FileReloader`] 的句柄。
+///
+/// drop 该句柄会停止重载线程并 drop 容器的写者；
+/// 使用 [`stop`](ReloadHandle::stop) 可以取回写者。
+pub struct ReloadHandle<T: 'static> {
+    reader: SmrReader<T>,
+    commands: Option<Sender<Command>>,
+    thread: Option<JoinHandle<SmrSwap<T>>>,
+}
+
+impl<T: 'static> ReloadHandle<T> {
+    /// Reload the file now, even if it does not appear to have changed.
+    ///
+    /// Blocks until the reload thread has processed the request. Returns `true` if a new
+    /// value was stored; errors are passed to the `on_error` callback.
+    ///
+    /// 立即重载文件，即使它看起来没有变化。
+    ///
+    /// 阻塞直到重载线程处理完该请求。如果存储了新值则返回 `true`；错误会传递给 `on_error` 回调。
+    pub fn reload_now(&self) -> bool {
+        let (reply, response) = mpsc::channel();
+        let Some(commands) = &self.commands else {
+            return false;
+        };
+        if commands.send(Command::Reload(reply)).is_err() {
+            return false;
+        }
+        response.recv().unwrap_or(false)
+    }
+
+    /// Create a new `SmrReader` for the reloaded container.
+    ///
+    /// 为被重载的容器创建一个新的 `SmrReader`。
+    #[inline]
+    pub fn reader(&self) -> SmrReader<T> {
+        self.reader.clone()
+    }
+
+    /// Create a new thread-local reader for the reloaded container.
+    ///
+    /// 为被重载的容器创建一个新的线程本地读取者。
+    #[inline]
+    pub fn local(&self) -> LocalReader<T> {
+        self.reader.local()
+    }
+
+    /// Stop the reload thread and return the container's writer.
+    ///
+    /// 停止重载线程并返回容器的写者。
+    pub fn stop(mut self) -> SmrSwap<T> {
+        self.commands = None;
+        let thread = self.thread.take().expect("reload thread is running");
+        match thread.join() {
+            Ok(swap) => swap,
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl<T: 'static> Drop for ReloadHandle<T> {
+    fn drop(&mut self) {
+        self.commands = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl<T: 'static> fmt::Debug for ReloadHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReloadHandle").finish()
+    }
+}
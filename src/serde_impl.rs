@@ -0,0 +1,118 @@
+//! `serde` support for checkpointing and restoring containers.
+//!
+//! `SmrSwap<T>` serializes as a struct with the current `value` and its `version`.
+//! Deserializing builds a fresh container; the version is restored when present and
+//! starts at `0` otherwise. Use [`value_only`] to write and read the bare value instead.
+//!
+//! 用于对容器进行检查点和恢复的 `serde` 支持。
+//!
+//! `SmrSwap<T>` 被序列化为包含当前 `value` 及其 `version` 的结构体。
+//! 反序列化会构建一个新的容器；如果存在版本则恢复该版本，否则从 `0` 开始。
+//! 使用 [`value_only`] 可以只写入和读取值本身。
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{ReadGuard, SmrSwap};
+
+#[derive(Serialize)]
+#[serde(rename = "SmrSwap")]
+struct SerializeSwap<'a, T> {
+    value: &'a T,
+    version: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "SmrSwap")]
+struct DeserializeSwap<T> {
+    value: T,
+    #[serde(default)]
+    version: Option<usize>,
+}
+
+impl<T: Serialize + 'static> Serialize for SmrSwap<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeSwap {
+            value: self.get(),
+            version: self.version(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de> + 'static> Deserialize<'de> for SmrSwap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let DeserializeSwap { value, version } = DeserializeSwap::deserialize(deserializer)?;
+        Ok(SmrSwap::builder()
+            .initial_version(version.unwrap_or(0))
+            .build(value))
+    }
+}
+
+impl<T: Serialize + 'static> Serialize for ReadGuard<'_, T> {
+    /// Serialize the pinned value directly, without cloning it.
+    ///
+    /// 直接序列化被 pin 的值，无需克隆。
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+/// Serialize an `SmrSwap<T>` as its bare current value, without the version.
+///
+/// Intended for `#[serde(with = "smr_swap::value_only")]` on struct fields.
+/// Deserializing yields a fresh container at version `0`.
+///
+/// 将 `SmrSwap<T>` 序列化为其当前值本身，不包含版本。
+///
+/// 用于结构体字段上的 `#[serde(with = "smr_swap::value_only")]`。
+/// 反序列化会得到一个版本为 `0` 的新容器。
+///
+/// # Example
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use smr_swap::SmrSwap;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct State {
+///     #[serde(with = "smr_swap::value_only")]
+///     limit: SmrSwap<u32>,
+/// }
+///
+/// let state = State { limit: SmrSwap::new(10) };
+/// let json = serde_json::to_string(&state).unwrap();
+/// assert_eq!(json, r#"{"limit":10}"#);
+///
+/// let restored: State = serde_json::from_str(&json).unwrap();
+/// assert_eq!(*restored.limit.get(), 10);
+/// ```
+pub mod value_only {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::SmrSwap;
+
+    /// Serialize the current value of `swap`.
+    ///
+    /// 序列化 `swap` 的当前值。
+    #[inline]
+    pub fn serialize<T, S>(swap: &SmrSwap<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + 'static,
+        S: Serializer,
+    {
+        swap.get().serialize(serializer)
+    }
+
+    /// Deserialize a value into a fresh container.
+    ///
+    /// 将一个值反序列化为新的容器。
+    #[inline]
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<SmrSwap<T>, D::Error>
+    where
+        T: Deserialize<'de> + 'static,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(SmrSwap::new)
+    }
+}
@@ -0,0 +1,252 @@
+//! Platform abstraction for the reclamation engine.
+//!
+//! Selects between `core`/`std`/`alloc`, `portable-atomic`, `critical-section`, `spin`
+//! and `loom` primitives, and provides the memory barriers used by each [`Strategy`].
+//!
+//! 回收引擎的平台抽象。
+//!
+//! 在 `core`/`std`/`alloc`、`portable-atomic`、`critical-section`、`spin` 和 `loom`
+//! 原语之间进行选择，并提供每种 [`Strategy`] 所使用的内存屏障。
+
+use crate::Strategy;
+
+#[cfg(not(feature = "loom"))]
+pub(crate) use core::cell::Cell;
+#[cfg(feature = "loom")]
+pub(crate) use loom::cell::Cell;
+
+/// Atomics and `Arc` for code that is never model-checked with loom.
+///
+/// With the `portable-atomic` feature these come from `portable-atomic`, which also
+/// works on targets without native compare-and-swap.
+///
+/// 用于从不使用 loom 进行模型检查的代码的原子类型和 `Arc`。
+///
+/// 启用 `portable-atomic` feature 时，这些类型来自 `portable-atomic`，
+/// 它也能在没有原生比较并交换指令的目标上工作。
+pub(crate) mod sync {
+    #[cfg(not(feature = "portable-atomic"))]
+    pub(crate) use alloc::sync::Arc;
+    #[cfg(not(feature = "portable-atomic"))]
+    pub(crate) use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering, fence};
+
+    #[cfg(feature = "portable-atomic")]
+    pub(crate) use portable_atomic::{AtomicBool, AtomicUsize, Ordering, fence};
+
+    // Only the engine uses `AtomicPtr`, and it takes loom's under the `loom` feature.
+    // 只有回收引擎使用 `AtomicPtr`，并且在启用 `loom` feature 时使用 loom 的版本。
+    #[cfg(all(not(feature = "loom"), not(feature = "portable-atomic")))]
+    pub(crate) use core::sync::atomic::AtomicPtr;
+    #[cfg(all(not(feature = "loom"), feature = "portable-atomic"))]
+    pub(crate) use portable_atomic::AtomicPtr;
+    #[cfg(feature = "portable-atomic")]
+    pub(crate) use portable_atomic_util::Arc;
+}
+
+// Atomics (Loom vs Core)
+#[cfg(feature = "loom")]
+pub(crate) use loom::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+#[cfg(not(feature = "loom"))]
+pub(crate) use sync::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+#[cfg(not(feature = "loom"))]
+mod memory {
+    pub(crate) use super::sync::Arc;
+    pub(crate) use alloc::boxed::Box;
+    pub(crate) use alloc::vec::Vec;
+}
+
+#[cfg(feature = "loom")]
+mod memory {
+    pub(crate) use loom::sync::Arc;
+    pub(crate) use std::boxed::Box;
+    pub(crate) use std::vec::Vec;
+}
+
+pub(crate) use memory::*;
+
+// Lock (Mutex) Abstraction
+#[cfg(feature = "loom")]
+mod locks {
+    pub(crate) struct Mutex<T>(loom::sync::Mutex<T>);
+
+    impl<T> Mutex<T> {
+        #[inline]
+        pub(crate) fn new(t: T) -> Self {
+            Self(loom::sync::Mutex::new(t))
+        }
+
+        #[inline]
+        pub(crate) fn lock(&self) -> loom::sync::MutexGuard<'_, T> {
+            self.0.lock().unwrap()
+        }
+    }
+}
+
+#[cfg(all(not(feature = "loom"), feature = "critical-section"))]
+mod locks {
+    use core::cell::UnsafeCell;
+    use core::ops::{Deref, DerefMut};
+
+    /// A mutex that holds a critical section while it is locked.
+    ///
+    /// On single-core targets the critical section masks interrupts, so an interrupt
+    /// handler can never spin on a lock held by the code it interrupted.
+    ///
+    /// 在加锁期间持有临界区的互斥锁。
+    ///
+    /// 在单核目标上，临界区会屏蔽中断，因此中断处理程序永远不会在被其打断的代码所持有的锁上自旋。
+    pub(crate) struct Mutex<T>(UnsafeCell<T>);
+
+    // SAFETY: the value is only reached through a guard, and guards only exist inside
+    // the critical section, which excludes every other context.
+    unsafe impl<T: Send> Sync for Mutex<T> {}
+
+    impl<T> Mutex<T> {
+        #[inline]
+        pub(crate) const fn new(t: T) -> Self {
+            Self(UnsafeCell::new(t))
+        }
+
+        #[inline]
+        pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+            // SAFETY: the state is released exactly once, when the guard is dropped.
+            let state = unsafe { critical_section::acquire() };
+            MutexGuard { mutex: self, state }
+        }
+    }
+
+    pub(crate) struct MutexGuard<'a, T> {
+        mutex: &'a Mutex<T>,
+        state: critical_section::RestoreState,
+    }
+
+    impl<T> Deref for MutexGuard<'_, T> {
+        type Target = T;
+        #[inline]
+        fn deref(&self) -> &T {
+            // SAFETY: the critical section is held for the lifetime of the guard.
+            unsafe { &*self.mutex.0.get() }
+        }
+    }
+
+    impl<T> DerefMut for MutexGuard<'_, T> {
+        #[inline]
+        fn deref_mut(&mut self) -> &mut T {
+            // SAFETY: the critical section is held for the lifetime of the guard.
+            unsafe { &mut *self.mutex.0.get() }
+        }
+    }
+
+    impl<T> Drop for MutexGuard<'_, T> {
+        #[inline]
+        fn drop(&mut self) {
+            // SAFETY: `state` was returned by the matching `acquire` in `Mutex::lock`.
+            unsafe { critical_section::release(self.state) }
+        }
+    }
+}
+
+#[cfg(all(
+    not(feature = "loom"),
+    not(feature = "critical-section"),
+    feature = "std"
+))]
+mod locks {
+    use std::ops::{Deref, DerefMut};
+    use std::sync;
+
+    /// Like `std::sync::Mutex` except that it does not poison itself.
+    pub(crate) struct Mutex<T: ?Sized>(sync::Mutex<T>);
+
+    impl<T> Mutex<T> {
+        #[inline]
+        pub(crate) fn new(t: T) -> Self {
+            Self(sync::Mutex::new(t))
+        }
+    }
+
+    impl<T: ?Sized> Mutex<T> {
+        #[inline]
+        pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+            MutexGuard(self.0.lock().unwrap_or_else(|e| e.into_inner()))
+        }
+    }
+
+    pub(crate) struct MutexGuard<'a, T: ?Sized + 'a>(sync::MutexGuard<'a, T>);
+
+    impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+        type Target = T;
+        #[inline]
+        fn deref(&self) -> &T {
+            self.0.deref()
+        }
+    }
+
+    impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+        #[inline]
+        fn deref_mut(&mut self) -> &mut T {
+            self.0.deref_mut()
+        }
+    }
+}
+
+#[cfg(all(
+    not(feature = "loom"),
+    not(feature = "critical-section"),
+    not(feature = "std"),
+    feature = "spin"
+))]
+mod locks {
+    pub(crate) use spin::Mutex;
+}
+
+#[cfg(any(
+    feature = "loom",
+    feature = "critical-section",
+    feature = "std",
+    feature = "spin"
+))]
+pub(crate) use locks::Mutex;
+
+// Ensure a compile error in no_std environment without a lock feature.
+// 确保在 no_std 且没有锁 feature 的情况下报错
+#[cfg(all(
+    not(feature = "std"),
+    not(feature = "spin"),
+    not(feature = "critical-section"),
+    not(feature = "loom")
+))]
+compile_error!(
+    "To use smr-swap in no_std, you must enable the 'spin', 'critical-section' or 'loom' feature."
+);
+
+#[inline(always)]
+fn fence(ordering: Ordering) {
+    #[cfg(feature = "loom")]
+    loom::sync::atomic::fence(ordering);
+    #[cfg(not(feature = "loom"))]
+    sync::fence(ordering);
+}
+
+/// Barrier executed by the writer before scanning reader slots.
+///
+/// 写者在扫描读者槽之前执行的屏障。
+#[inline(always)]
+pub(crate) fn heavy_barrier(strategy: Strategy) {
+    match strategy {
+        Strategy::WritePreferred => fence(Ordering::SeqCst),
+        Strategy::ReadPreferred | Strategy::Adaptive => swmr_barrier::heavy_barrier(),
+    }
+}
+
+/// Barrier executed by readers between publishing their version and validating it.
+///
+/// 读者在发布其版本和验证版本之间执行的屏障。
+#[inline(always)]
+pub(crate) fn light_barrier(strategy: Strategy) {
+    match strategy {
+        Strategy::WritePreferred => fence(Ordering::SeqCst),
+        Strategy::ReadPreferred | Strategy::Adaptive => swmr_barrier::light_barrier(),
+    }
+}
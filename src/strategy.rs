@@ -0,0 +1,150 @@
+//! Memory barrier strategy of a container.
+//!
+//! 容器的内存屏障策略。
+
+/// How a container splits the cost of synchronization between its readers and its writer.
+///
+/// The strategy is chosen per container with [`SmrSwapBuilder::strategy`](crate::SmrSwapBuilder::strategy)
+/// and shared by every reader of that container, so containers with different strategies
+/// can coexist in one process. The `read-preferred` cargo feature only changes the default.
+///
+/// 容器如何在其读者和写者之间分摊同步开销。
+///
+/// 策略通过 [`SmrSwapBuilder::strategy`](crate::SmrSwapBuilder::strategy) 按容器选择，
+/// 并由该容器的所有读者共享，因此使用不同策略的容器可以在同一进程中共存。
+/// `read-preferred` cargo feature 只改变默认值。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Symmetric barriers: readers and the writer both execute a `SeqCst` fence.
+    ///
+    /// Balanced read and write cost. The default unless the `read-preferred` feature is enabled.
+    ///
+    /// 对称屏障：读者和写者都执行一次 `SeqCst` fence。
+    ///
+    /// 读写开销均衡。除非启用了 `read-preferred` feature，否则为默认值。
+    WritePreferred,
+
+    /// Asymmetric barriers: readers only execute a compiler fence, and the writer issues an
+    /// OS-level barrier (`membarrier` on Linux, `FlushProcessWriteBuffers` on Windows)
+    /// before scanning readers.
+    ///
+    /// Near-zero read cost for read-mostly data, at the price of slower collection. On other
+    /// platforms both sides fall back to `SeqCst` fences.
+    ///
+    /// 非对称屏障：读者只执行编译器 fence，写者在扫描读者之前发出 OS 级屏障
+    /// （Linux 上为 `membarrier`，Windows 上为 `FlushProcessWriteBuffers`）。
+    ///
+    /// 对于以读为主的数据，读取开销几乎为零，代价是回收更慢。
+    /// 在其他平台上，双方都会回退为 `SeqCst` fence。
+    ReadPreferred,
+
+    /// Switch between the two algorithms based on the observed read/write ratio.
+    ///
+    /// Readers count their loads and the writer counts its stores; every collection samples
+    /// the ratio and moves to `ReadPreferred` once reads outnumber writes 8:1, and back to
+    /// `WritePreferred` when they fall below 2:1. The switch happens inside a collection,
+    /// behind a heavy barrier that acts as a grace period for readers still using the old
+    /// algorithm. Containers start out write-preferred, and without collections (automatic
+    /// or manual) they never switch.
+    ///
+    /// 根据观测到的读写比例在两种算法之间切换。
+    ///
+    /// 读者统计其加载次数，写者统计其存储次数；每次回收都会对比例进行采样，当读写比超过 8:1 时
+    /// 切换到 `ReadPreferred`，低于 2:1 时切回 `WritePreferred`。切换发生在回收期间，
+    /// 位于一个重型屏障之后，该屏障为仍在使用旧算法的读者充当宽限期。
+    /// 容器以写优先开始，如果没有回收（自动或手动），则永远不会切换。
+    Adaptive,
+}
+
+impl Default for Strategy {
+    #[inline]
+    fn default() -> Self {
+        if cfg!(feature = "read-preferred") {
+            Strategy::ReadPreferred
+        } else {
+            Strategy::WritePreferred
+        }
+    }
+}
+
+/// Minimum number of loads and stores between two decisions of an adaptive container.
+///
+/// 自适应容器两次决策之间的最小加载与存储次数。
+const ADAPTIVE_WINDOW: usize = 256;
+
+/// Read/write ratio at or above which an adaptive container becomes read-preferred.
+///
+/// 自适应容器切换为读优先的读写比例阈值（含）。
+const READ_PREFERRED_RATIO: usize = 8;
+
+/// Read/write ratio below which an adaptive container becomes write-preferred again.
+///
+/// 自适应容器重新切换为写优先的读写比例阈值（不含）。
+const WRITE_PREFERRED_RATIO: usize = 2;
+
+/// Writer-side bookkeeping of an adaptive container.
+///
+/// 自适应容器的写者端簿记。
+pub(crate) struct Adaptive {
+    current: Strategy,
+    stores: usize,
+    /// Total loads at the last decision.
+    ///
+    /// 上次决策时的总加载次数。
+    loads: usize,
+}
+
+impl Adaptive {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            current: Strategy::WritePreferred,
+            stores: 0,
+            loads: 0,
+        }
+    }
+
+    /// The algorithm currently in use, `WritePreferred` or `ReadPreferred`.
+    ///
+    /// 当前使用的算法，`WritePreferred` 或 `ReadPreferred`。
+    #[inline]
+    pub(crate) fn current(&self) -> Strategy {
+        self.current
+    }
+
+    #[inline]
+    pub(crate) fn stored(&mut self) {
+        self.stores = self.stores.wrapping_add(1);
+    }
+
+    /// Sample the ratio, given the total load count of the reader slots, and return the
+    /// algorithm to switch to, if any.
+    ///
+    /// Slots are never removed and their counts carry over between readers, so the total
+    /// only grows.
+    ///
+    /// 根据读者槽的总加载次数对比例进行采样，并返回需要切换到的算法（如果有）。
+    ///
+    /// 槽永远不会被移除，其计数会在读者之间延续，因此总数只会增长。
+    pub(crate) fn decide(&mut self, total: usize) -> Option<Strategy> {
+        let loads = total.wrapping_sub(self.loads);
+        if loads.saturating_add(self.stores) < ADAPTIVE_WINDOW {
+            return None;
+        }
+
+        let stores = core::mem::take(&mut self.stores);
+        self.loads = total;
+
+        let next = match self.current {
+            Strategy::ReadPreferred if loads < stores.saturating_mul(WRITE_PREFERRED_RATIO) => {
+                Strategy::WritePreferred
+            }
+            Strategy::WritePreferred if loads >= stores.saturating_mul(READ_PREFERRED_RATIO) => {
+                Strategy::ReadPreferred
+            }
+            _ => return None,
+        };
+        self.current = next;
+        Some(next)
+    }
+}
@@ -0,0 +1,608 @@
+//! Model-based property test harness, enabled by the `testing` feature.
+//!
+//! [`run`] executes a sequence of [`Op`]s against a container: the writer runs on the
+//! calling thread and every reader lives on its own thread, holding guards across
+//! operations. After each step the harness compares what readers observed with a
+//! sequential reference model, and it checks that every stored [`Tracked`] value is
+//! dropped exactly once and never while it can still be read.
+//!
+//! Wrappers built on `SmrSwap` can reuse the harness by implementing [`ModelTarget`]
+//! and [`ModelReader`] for their own types instantiated with [`Tracked`].
+//!
+//! 基于模型的属性测试框架，由 `testing` feature 启用。
+//!
+//! [`run`] 对容器执行一系列 [`Op`]：写者在调用线程上运行，每个读者都在自己的线程上，
+//! 并跨操作持有守卫。每一步之后，框架都会将读者观察到的内容与顺序参考模型进行比较，
+//! 并检查每个存储的 [`Tracked`] 值恰好被 drop 一次，且不会在仍可被读取时被 drop。
+//!
+//! 基于 `SmrSwap` 构建的包装类型可以通过为其以 [`Tracked`] 实例化的类型实现
+//! [`ModelTarget`] 和 [`ModelReader`] 来复用该框架。
+//!
+//! # Example
+//!
+//! ```rust
+//! use proptest::prelude::*;
+//! use smr_swap::SmrSwap;
+//! use smr_swap::testing::{self, Tracked};
+//!
+//! proptest!(|(ops in testing::ops(64))| {
+//!     testing::run::<SmrSwap<Tracked>>(&ops);
+//! });
+//! ```
+
+use core::fmt;
+use core::ops::Deref;
+use std::boxed::Box;
+use std::collections::VecDeque;
+use std::string::String;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::vec::Vec;
+
+use proptest::prelude::*;
+
+use crate::{
+    BoxReadGuard, Global, LocalBoxReader, LocalReader, ReadGuard, Reclaimer, SmrSwap, SmrSwapBox,
+};
+
+/// A single step of a generated scenario.
+///
+/// Reader indices are taken modulo the number of live readers; steps that address a
+/// reader when there is none, or release a guard that was never taken, are skipped.
+///
+/// 生成场景中的单个步骤。
+///
+/// 读者索引会对存活读者的数量取模；当没有读者时针对读者的步骤，或释放从未获取的守卫的步骤会被跳过。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    /// Store a fresh value.
+    ///
+    /// 存储一个新值。
+    Store,
+    /// Replace the value with one derived from the current value.
+    ///
+    /// 用从当前值派生的值替换它。
+    Update,
+    /// Create a new reader on its own thread.
+    ///
+    /// 在其自己的线程上创建一个新读者。
+    Local,
+    /// Load on a reader and keep the guard.
+    ///
+    /// 在读者上加载并保留守卫。
+    Load(usize),
+    /// Drop the oldest guard held by a reader.
+    ///
+    /// drop 读者持有的最旧的守卫。
+    DropGuard(usize),
+    /// Drop a reader together with its guards.
+    ///
+    /// drop 一个读者及其守卫。
+    DropReader(usize),
+    /// Run a garbage collection.
+    ///
+    /// 执行一次垃圾回收。
+    Collect,
+}
+
+impl Op {
+    /// Decode a byte string into operations, for use in fuzz targets.
+    ///
+    /// Every byte decodes to one operation, so any input is a valid scenario.
+    ///
+    /// 将字节串解码为操作，用于 fuzz 目标。
+    ///
+    /// 每个字节解码为一个操作，因此任何输入都是有效的场景。
+    pub fn decode(bytes: &[u8]) -> Vec<Op> {
+        bytes
+            .iter()
+            .map(|&byte| {
+                let reader = usize::from(byte >> 3);
+                match byte & 0b111 {
+                    0 => Op::Store,
+                    1 => Op::Update,
+                    2 => Op::Local,
+                    3 | 4 => Op::Load(reader),
+                    5 => Op::DropGuard(reader),
+                    6 => Op::DropReader(reader),
+                    _ => Op::Collect,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Strategy producing a single [`Op`].
+///
+/// Loads and writes are weighted above the operations that discard state.
+///
+/// 产生单个 [`Op`] 的策略。
+///
+/// 加载和写入的权重高于丢弃状态的操作。
+pub fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => Just(Op::Store),
+        2 => Just(Op::Update),
+        2 => Just(Op::Local),
+        5 => any::<usize>().prop_map(Op::Load),
+        4 => any::<usize>().prop_map(Op::DropGuard),
+        1 => any::<usize>().prop_map(Op::DropReader),
+        2 => Just(Op::Collect),
+    ]
+}
+
+/// Strategy producing scenarios of up to `max_len` operations.
+///
+/// 产生最多 `max_len` 个操作的场景的策略。
+pub fn ops(max_len: usize) -> impl Strategy<Value = Vec<Op>> {
+    proptest::collection::vec(op(), 0..=max_len)
+}
+
+/// Drop counts of every [`Tracked`] value created by one run.
+///
+/// 一次运行所创建的每个 [`Tracked`] 值的 drop 次数。
+#[derive(Default)]
+struct Ledger {
+    drops: Mutex<Vec<usize>>,
+}
+
+impl Ledger {
+    fn track(self: &Arc<Self>) -> Tracked {
+        let mut drops = self.drops();
+        drops.push(0);
+        Tracked {
+            id: drops.len() - 1,
+            ledger: Arc::clone(self),
+        }
+    }
+
+    fn drops(&self) -> std::sync::MutexGuard<'_, Vec<usize>> {
+        self.drops.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn drop_count(&self, id: usize) -> usize {
+        self.drops()[id]
+    }
+}
+
+/// A value that records when it is dropped.
+///
+/// Values are only created by the harness; each has a unique [`id`](Tracked::id).
+///
+/// 记录自己何时被 drop 的值。
+///
+/// 值只由框架创建；每个值都有唯一的 [`id`](Tracked::id)。
+pub struct Tracked {
+    id: usize,
+    ledger: Arc<Ledger>,
+}
+
+impl Tracked {
+    /// Get the unique identifier of this value.
+    ///
+    /// 获取此值的唯一标识符。
+    #[inline]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.ledger.drops()[self.id] += 1;
+    }
+}
+
+impl fmt::Debug for Tracked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Tracked").field(&self.id).finish()
+    }
+}
+
+/// Writer side of a container under test.
+///
+/// 被测容器的写者端。
+pub trait ModelTarget: Sized {
+    /// Reader type moved to a reader thread.
+    ///
+    /// 被移动到读者线程的读者类型。
+    type Reader: ModelReader + Send + 'static;
+
+    /// Create a container holding `initial`.
+    ///
+    /// 创建一个持有 `initial` 的容器。
+    fn new(initial: Tracked) -> Self;
+
+    /// Store a new value.
+    ///
+    /// 存储新值。
+    fn store(&mut self, value: Tracked);
+
+    /// Replace the value with `f(current)`.
+    ///
+    /// 将值替换为 `f(current)`。
+    fn update<F>(&mut self, f: F)
+    where
+        F: FnOnce(&Tracked) -> Tracked;
+
+    /// Run a garbage collection.
+    ///
+    /// 执行一次垃圾回收。
+    fn collect(&mut self);
+
+    /// Create a new reader.
+    ///
+    /// 创建一个新读者。
+    fn local(&self) -> Self::Reader;
+
+    /// Current version, if the container exposes one.
+    ///
+    /// 当前版本（如果容器公开了版本）。
+    fn version(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Reader side of a container under test.
+///
+/// 被测容器的读者端。
+pub trait ModelReader {
+    /// Guard returned by [`load`](ModelReader::load).
+    ///
+    /// [`load`](ModelReader::load) 返回的守卫。
+    type Guard<'a>: Deref<Target = Tracked>
+    where
+        Self: 'a;
+
+    /// Pin the current value.
+    ///
+    /// pin 当前值。
+    fn load(&self) -> Self::Guard<'_>;
+
+    /// Version the guard is pinned to, if the container exposes one.
+    ///
+    /// 守卫被 pin 到的版本（如果容器公开了版本）。
+    fn guard_version(guard: &Self::Guard<'_>) -> Option<usize> {
+        let _ = guard;
+        None
+    }
+}
+
+impl<R: Reclaimer> ModelTarget for SmrSwap<Tracked, Global, R>
+where
+    LocalReader<Tracked, R>: Send,
+{
+    type Reader = LocalReader<Tracked, R>;
+
+    fn new(initial: Tracked) -> Self {
+        SmrSwap::builder().reclaimer::<R>().build(initial)
+    }
+
+    fn store(&mut self, value: Tracked) {
+        SmrSwap::store(self, value);
+    }
+
+    fn update<F>(&mut self, f: F)
+    where
+        F: FnOnce(&Tracked) -> Tracked,
+    {
+        SmrSwap::update(self, f);
+    }
+
+    fn collect(&mut self) {
+        SmrSwap::collect(self);
+    }
+
+    fn local(&self) -> Self::Reader {
+        SmrSwap::local(self)
+    }
+
+    fn version(&self) -> Option<usize> {
+        Some(SmrSwap::version(self))
+    }
+}
+
+impl<R: Reclaimer> ModelReader for LocalReader<Tracked, R> {
+    type Guard<'a> = ReadGuard<'a, Tracked, R>;
+
+    fn load(&self) -> Self::Guard<'_> {
+        LocalReader::load(self)
+    }
+
+    fn guard_version(guard: &Self::Guard<'_>) -> Option<usize> {
+        Some(guard.version())
+    }
+}
+
+impl ModelTarget for SmrSwapBox<Tracked> {
+    type Reader = LocalBoxReader<Tracked>;
+
+    fn new(initial: Tracked) -> Self {
+        SmrSwapBox::new(Box::new(initial))
+    }
+
+    fn store(&mut self, value: Tracked) {
+        SmrSwapBox::store(self, Box::new(value));
+    }
+
+    fn update<F>(&mut self, f: F)
+    where
+        F: FnOnce(&Tracked) -> Tracked,
+    {
+        SmrSwapBox::update(self, |current| Box::new(f(current)));
+    }
+
+    fn collect(&mut self) {
+        SmrSwapBox::collect(self);
+    }
+
+    fn local(&self) -> Self::Reader {
+        SmrSwapBox::local(self)
+    }
+
+    fn version(&self) -> Option<usize> {
+        Some(SmrSwapBox::version(self))
+    }
+}
+
+impl ModelReader for LocalBoxReader<Tracked> {
+    type Guard<'a> = BoxReadGuard<'a, Tracked>;
+
+    fn load(&self) -> Self::Guard<'_> {
+        LocalBoxReader::load(self)
+    }
+
+    fn guard_version(guard: &Self::Guard<'_>) -> Option<usize> {
+        Some(guard.version())
+    }
+}
+
+/// Command sent to a reader thread.
+///
+/// 发送给读者线程的命令。
+enum Command {
+    Load,
+    DropGuard,
+}
+
+/// What a reader saw through a guard.
+///
+/// 读者通过守卫看到的内容。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Observation {
+    id: usize,
+    version: Option<usize>,
+}
+
+/// A reader thread and the model of the guards it holds.
+///
+/// 一个读者线程及其所持守卫的模型。
+struct ReaderHandle {
+    commands: Sender<Command>,
+    replies: Receiver<Result<Observation, String>>,
+    thread: JoinHandle<()>,
+    /// Guards held by the reader, oldest first.
+    ///
+    /// 读者持有的守卫，最旧的在前。
+    guards: VecDeque<Observation>,
+    /// Version of the outermost guard, which every nested guard reports.
+    ///
+    /// 最外层守卫的版本，每个嵌套的守卫都会报告该版本。
+    pinned_version: Option<usize>,
+}
+
+impl ReaderHandle {
+    fn spawn<R: ModelReader + Send + 'static>(reader: R, ledger: Arc<Ledger>) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let (sender, replies) = mpsc::channel();
+        let thread = thread::spawn(move || reader_thread(reader, receiver, sender, ledger));
+        Self {
+            commands,
+            replies,
+            thread,
+            guards: VecDeque::new(),
+            pinned_version: None,
+        }
+    }
+
+    fn request(&self, command: Command, step: usize) -> Observation {
+        self.commands
+            .send(command)
+            .unwrap_or_else(|_| panic!("step {step}: reader thread exited"));
+        match self.replies.recv() {
+            Ok(Ok(observation)) => observation,
+            Ok(Err(message)) => panic!("step {step}: {message}"),
+            Err(_) => panic!("step {step}: reader thread exited"),
+        }
+    }
+
+    fn stop(self) {
+        drop(self.commands);
+        if let Err(payload) = self.thread.join() {
+            std::panic::resume_unwind(payload);
+        }
+    }
+}
+
+fn reader_thread<R: ModelReader>(
+    reader: R,
+    commands: Receiver<Command>,
+    replies: Sender<Result<Observation, String>>,
+    ledger: Arc<Ledger>,
+) {
+    let observe = |guard: &R::Guard<'_>, api: &str| {
+        let observation = Observation {
+            id: guard.id(),
+            version: R::guard_version(guard),
+        };
+        match ledger.drop_count(observation.id) {
+            0 => Ok(observation),
+            _ => Err(std::format!(
+                "value {} was dropped while readable through {api}",
+                observation.id
+            )),
+        }
+    };
+
+    let mut guards = VecDeque::new();
+    for command in commands {
+        let reply = match command {
+            Command::Load => {
+                let guard = reader.load();
+                let observation = observe(&guard, "a new guard");
+                guards.push_back(guard);
+                observation
+            }
+            Command::DropGuard => match guards.pop_front() {
+                Some(guard) => observe(&guard, "a held guard"),
+                None => Err(String::from("no guard to drop")),
+            },
+        };
+        if replies.send(reply).is_err() {
+            break;
+        }
+    }
+}
+
+/// Sequential reference model of the container.
+///
+/// 容器的顺序参考模型。
+struct Model {
+    current: usize,
+    version: Option<usize>,
+    readers: Vec<ReaderHandle>,
+}
+
+/// Run `ops` against a fresh `S` and panic on the first divergence from the model.
+///
+/// The panic message names the failing step, so the harness can be used directly inside
+/// `proptest!` (which shrinks the scenario) or a fuzz target (see [`Op::decode`]).
+///
+/// 对一个新的 `S` 执行 `ops`，并在第一次与模型不一致时 panic。
+///
+/// panic 信息会指出失败的步骤，因此可以直接在 `proptest!`（会收缩场景）
+/// 或 fuzz 目标（见 [`Op::decode`]）中使用该框架。
+pub fn run<S: ModelTarget>(ops: &[Op]) {
+    let ledger = Arc::new(Ledger::default());
+    let initial = ledger.track();
+    let mut model = Model {
+        current: initial.id(),
+        version: None,
+        readers: Vec::new(),
+    };
+    let mut target = S::new(initial);
+    model.version = target.version();
+
+    for (step, &op) in ops.iter().enumerate() {
+        match op {
+            Op::Store => {
+                let value = ledger.track();
+                model.current = value.id();
+                target.store(value);
+                model.version = model.version.map(|v| v + 1);
+            }
+            Op::Update => {
+                let value = ledger.track();
+                let id = value.id();
+                let mut seen = None;
+                target.update(|current| {
+                    seen = Some(current.id());
+                    value
+                });
+                assert_eq!(
+                    seen,
+                    Some(model.current),
+                    "step {step}: update saw the wrong value"
+                );
+                model.current = id;
+                model.version = model.version.map(|v| v + 1);
+            }
+            Op::Local => {
+                let reader = ReaderHandle::spawn(target.local(), Arc::clone(&ledger));
+                model.readers.push(reader);
+            }
+            Op::Load(index) => {
+                let Some(reader) = pick(&mut model.readers, index) else {
+                    continue;
+                };
+                let observation = reader.request(Command::Load, step);
+                let expected = Observation {
+                    id: model.current,
+                    version: reader.pinned_version.or(model.version),
+                };
+                assert_eq!(observation, expected, "step {step}: load diverged");
+                reader.pinned_version = expected.version;
+                reader.guards.push_back(observation);
+            }
+            Op::DropGuard(index) => {
+                let Some(reader) = pick(&mut model.readers, index) else {
+                    continue;
+                };
+                let Some(expected) = reader.guards.pop_front() else {
+                    continue;
+                };
+                let observation = reader.request(Command::DropGuard, step);
+                assert_eq!(
+                    observation, expected,
+                    "step {step}: guard changed while held"
+                );
+                if reader.guards.is_empty() {
+                    reader.pinned_version = None;
+                }
+            }
+            Op::DropReader(index) => {
+                if model.readers.is_empty() {
+                    continue;
+                }
+                let index = index % model.readers.len();
+                model.readers.swap_remove(index).stop();
+            }
+            Op::Collect => target.collect(),
+        }
+
+        check_live(&ledger, &model, step);
+    }
+
+    for reader in model.readers.drain(..) {
+        reader.stop();
+    }
+    drop(target);
+
+    let drops = ledger.drops();
+    for (id, &count) in drops.iter().enumerate() {
+        assert_eq!(count, 1, "value {id} was dropped {count} times");
+    }
+}
+
+fn pick(readers: &mut [ReaderHandle], index: usize) -> Option<&mut ReaderHandle> {
+    if readers.is_empty() {
+        return None;
+    }
+    let len = readers.len();
+    readers.get_mut(index % len)
+}
+
+/// Check that no value was dropped twice, and that readable values are not dropped.
+///
+/// 检查没有值被 drop 两次，且可读的值没有被 drop。
+fn check_live(ledger: &Ledger, model: &Model, step: usize) {
+    let drops = ledger.drops();
+    if let Some(id) = drops.iter().position(|&count| count > 1) {
+        panic!("step {step}: value {id} was dropped more than once");
+    }
+    assert_eq!(
+        drops[model.current], 0,
+        "step {step}: current value {} was dropped",
+        model.current
+    );
+    for reader in &model.readers {
+        for guard in &reader.guards {
+            assert_eq!(
+                drops[guard.id], 0,
+                "step {step}: value {} was dropped while a guard held it",
+                guard.id
+            );
+        }
+    }
+}
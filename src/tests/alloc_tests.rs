@@ -0,0 +1,194 @@
+//! Tests for custom allocator support
+//!
+//! Tests that value nodes and writer bookkeeping come from the container's allocator
+
+extern crate std;
+use crate::{Allocator, Global, SmrSwap};
+use allocator_api2::alloc::{AllocError, Layout};
+use core::ptr::NonNull;
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+#[derive(Default)]
+struct Counters {
+    allocs: AtomicUsize,
+    frees: AtomicUsize,
+}
+
+/// An allocator that forwards to `Global` and counts live allocations.
+#[derive(Clone, Default)]
+struct CountingAlloc(Arc<Counters>);
+
+impl CountingAlloc {
+    fn allocs(&self) -> usize {
+        self.0.allocs.load(Ordering::SeqCst)
+    }
+
+    fn live(&self) -> usize {
+        self.allocs() - self.0.frees.load(Ordering::SeqCst)
+    }
+}
+
+unsafe impl Allocator for CountingAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.allocs.fetch_add(1, Ordering::SeqCst);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.0.frees.fetch_add(1, Ordering::SeqCst);
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+/// Test that value nodes are allocated with the custom allocator
+/// 测试值节点使用自定义分配器分配
+#[test]
+fn test_alloc_nodes_from_allocator() {
+    let alloc = CountingAlloc::default();
+    let mut swap = SmrSwap::new_in(String::from("v0"), alloc.clone());
+    assert_eq!(alloc.live(), 1);
+
+    let before = alloc.allocs();
+    swap.store(String::from("v1"));
+    assert!(alloc.allocs() > before);
+    assert_eq!(*swap.load(), "v1");
+    assert_eq!(swap.previous().map(String::as_str), Some("v0"));
+
+    drop(swap);
+    assert_eq!(alloc.live(), 0);
+}
+
+/// Test that every allocation is returned when the container is dropped
+/// 测试容器被 drop 时所有分配都会被归还
+#[test]
+fn test_alloc_freed_on_drop() {
+    let alloc = CountingAlloc::default();
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(2)
+        .build_in(0usize, alloc.clone());
+    let local = swap.local();
+
+    let guard = local.load();
+    for i in 1..=20 {
+        swap.store(i);
+    }
+    assert_eq!(*guard, 0);
+    assert!(alloc.live() > 20);
+    drop(guard);
+
+    swap.collect();
+    drop(swap);
+    // The reader keeps the current node alive.
+    // 读者使当前节点保持存活。
+    assert!(alloc.live() >= 1);
+    assert_eq!(*local.load(), 20);
+
+    drop(local);
+    assert_eq!(alloc.live(), 0);
+}
+
+/// Test that recycled nodes are reused without new allocations
+/// 测试回收的节点在复用时不会产生新的分配
+#[test]
+fn test_alloc_store_with_reuses_nodes() {
+    let alloc = CountingAlloc::default();
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(4)
+        .build_in(Vec::<u8>::with_capacity(16), alloc.clone());
+
+    for _ in 0..4 {
+        swap.store(Vec::with_capacity(16));
+    }
+    swap.collect();
+    assert_eq!(swap.recycled_count(), 2);
+
+    let before = alloc.allocs();
+    swap.store_with(|recycled| {
+        let mut v = recycled.expect("a recycled value");
+        v.clear();
+        v.push(1);
+        v
+    });
+    assert_eq!(alloc.allocs(), before);
+    assert_eq!(swap.recycled_count(), 1);
+    assert_eq!(*swap.load(), [1]);
+
+    drop(swap);
+    assert_eq!(alloc.live(), 0);
+}
+
+/// Test draining reclaimed values with a custom allocator
+/// 测试使用自定义分配器时排出已回收的值
+#[test]
+fn test_alloc_drain_reclaimed() {
+    let alloc = CountingAlloc::default();
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .build_in(0, alloc.clone());
+
+    for i in 1..=5 {
+        swap.store(i);
+    }
+    let drained: Vec<_> = swap.drain_reclaimed().collect();
+    assert_eq!(drained, [(0, 0), (1, 1), (2, 2)]);
+    assert_eq!(swap.garbage_count(), 2);
+
+    // A partially consumed drain still releases the remaining nodes.
+    // 部分消费的 drain 仍会释放剩余节点。
+    for i in 6..=8 {
+        swap.store(i);
+    }
+    let mut drain = swap.drain_reclaimed();
+    assert_eq!(drain.len(), 3);
+    assert_eq!(drain.next(), Some((3, 3)));
+    drop(drain);
+    assert_eq!(swap.garbage_count(), 2);
+
+    drop(swap);
+    assert_eq!(alloc.live(), 0);
+}
+
+/// Test a custom allocator under concurrent reads
+/// 测试并发读取下的自定义分配器
+#[test]
+fn test_alloc_concurrent() {
+    let alloc = CountingAlloc::default();
+    let mut swap = SmrSwap::new_in(0u64, alloc.clone());
+    let reader = swap.reader();
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            let reader = reader.clone();
+            s.spawn(move || {
+                let local = reader.local();
+                let mut last = 0;
+                for _ in 0..1000 {
+                    let value = *local.load();
+                    assert!(value >= last);
+                    last = value;
+                }
+            });
+        }
+        for i in 1..=1000 {
+            swap.store(i);
+        }
+    });
+
+    drop(reader);
+    drop(swap);
+    assert_eq!(alloc.live(), 0);
+}
+
+/// Test that the default allocator is `Global`
+/// 测试默认分配器是 `Global`
+#[test]
+fn test_alloc_default_is_global() {
+    let swap: SmrSwap<i32, Global> = SmrSwap::new(1);
+    let same: SmrSwap<i32> = swap;
+    assert_eq!(*same.load(), 1);
+}
@@ -0,0 +1,227 @@
+//! Tests for pluggable reclamation backends
+//!
+//! Tests the hazard-pointer and reference-counting backends behind the SmrSwap API
+
+extern crate std;
+use super::{DropCounter, build};
+use crate::{HazardPointers, Reclaimer, RefCounted, SmrReader, SmrSwap, Strategy, Versioned};
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+fn check_basic<R: Reclaimer>() {
+    let mut swap = build::<R, _>(0);
+    let local = swap.local();
+    assert_eq!(*local.load(), 0);
+
+    for i in 1..=5 {
+        swap.store(i);
+    }
+    assert_eq!(*local.load(), 5);
+    assert_eq!(local.version(), 5);
+    assert_eq!(swap.previous(), Some(&4));
+    assert_eq!(swap.garbage_count(), 5);
+
+    let mut freed = Vec::new();
+    swap.collect_into(&mut freed);
+    assert_eq!(freed, [0, 1, 2]);
+    assert_eq!(swap.previous(), Some(&4));
+}
+
+/// Test the common API on every backend
+/// 测试每种后端上的通用 API
+#[test]
+fn test_backends_basic() {
+    check_basic::<Versioned>();
+    check_basic::<HazardPointers>();
+    check_basic::<RefCounted>();
+}
+
+/// Test that the new backends report the write-preferred strategy
+/// 测试新后端报告写优先策略
+#[test]
+fn test_backend_strategy() {
+    let swap = SmrSwap::builder()
+        .strategy(Strategy::ReadPreferred)
+        .reclaimer::<HazardPointers>()
+        .build(0);
+    assert_eq!(swap.strategy(), Strategy::WritePreferred);
+    assert_eq!(swap.active_strategy(), Strategy::WritePreferred);
+
+    let swap = build::<RefCounted, _>(0);
+    assert_eq!(swap.active_strategy(), Strategy::WritePreferred);
+}
+
+fn check_bounded_garbage<R: Reclaimer>() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = build::<R, _>(DropCounter(drops.clone()));
+    let local = swap.local();
+    let guard = local.load();
+
+    for _ in 0..10 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    swap.collect();
+
+    // Only the pinned value and the two newest retired values are kept.
+    assert_eq!(swap.garbage_count(), 3);
+    assert_eq!(drops.load(Ordering::SeqCst), 7);
+
+    drop(guard);
+    swap.collect();
+    assert_eq!(swap.garbage_count(), 2);
+    assert_eq!(drops.load(Ordering::SeqCst), 8);
+}
+
+/// Test that a long-lived guard only keeps its own value alive
+/// 测试长期存活的守卫只会保留其自身的值
+#[test]
+fn test_backend_bounded_garbage() {
+    check_bounded_garbage::<HazardPointers>();
+    check_bounded_garbage::<RefCounted>();
+
+    // The versioned backend keeps everything retired after the pinned version.
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = build::<Versioned, _>(DropCounter(drops.clone()));
+    let local = swap.local();
+    let _guard = local.load();
+    for _ in 0..10 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    swap.collect();
+    assert_eq!(swap.garbage_count(), 10);
+}
+
+fn check_nested<R: Reclaimer>() {
+    let mut swap = build::<R, _>(String::from("v0"));
+    let local = swap.local();
+
+    let outer = local.load();
+    swap.store(String::from("v1"));
+    let inner = local.load();
+    let same = local.load();
+    swap.store(String::from("v2"));
+    swap.store(String::from("v3"));
+    swap.collect();
+
+    assert_eq!(*outer, "v0");
+    assert_eq!(*inner, "v1");
+    assert_eq!(*same, "v1");
+    assert_eq!(inner.version(), outer.version());
+
+    drop(outer);
+    let cloned = inner.clone();
+    drop(inner);
+    drop(same);
+    assert!(local.is_pinned());
+    assert_eq!(*cloned, "v1");
+    drop(cloned);
+    assert!(!local.is_pinned());
+}
+
+/// Test that nested loads see the newest value and report the outermost version
+/// 测试嵌套加载看到最新的值并报告最外层的版本
+#[test]
+fn test_backend_nested_guards() {
+    check_nested::<HazardPointers>();
+    check_nested::<RefCounted>();
+}
+
+fn check_writer_dropped<R: Reclaimer>() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = build::<R, _>(DropCounter(drops.clone()));
+    let local = swap.local();
+    let guard = local.load();
+    swap.store(DropCounter(drops.clone()));
+    swap.store(DropCounter(drops.clone()));
+    drop(swap);
+
+    // The retired value behind the guard outlives the writer.
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+    let _ = &*guard;
+    drop(guard);
+    drop(local);
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+}
+
+/// Test that retired values outlive a dropped writer while readers remain
+/// 测试当读者仍然存在时，已退休的值比被 drop 的写者存活更久
+#[test]
+fn test_backend_writer_dropped() {
+    check_writer_dropped::<HazardPointers>();
+    check_writer_dropped::<RefCounted>();
+}
+
+/// Test draining and recycling reclaimed values on the new backends
+/// 测试在新后端上取出和回收已回收的值
+#[test]
+fn test_backend_drain_and_recycle() {
+    let mut swap = SmrSwap::builder()
+        .reclaimer::<HazardPointers>()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(1)
+        .build(vec![0u8]);
+    for i in 1..=4 {
+        swap.store(vec![i]);
+    }
+
+    let mut reclaimed = swap.drain_reclaimed();
+    assert_eq!(reclaimed.len(), 2);
+    assert_eq!(reclaimed.next(), Some((0, vec![0])));
+    drop(reclaimed);
+
+    assert_eq!(swap.recycled_count(), 1);
+    swap.store_with(|recycled| {
+        let mut buf = recycled.unwrap();
+        buf[0] = 5;
+        buf
+    });
+    assert_eq!(*swap.load(), [5]);
+    assert_eq!(swap.recycled_count(), 0);
+}
+
+fn read_concurrently<R: Reclaimer>()
+where
+    SmrReader<usize, R>: Send,
+{
+    let mut swap = SmrSwap::builder().reclaimer::<R>().build(0usize);
+    let reader = swap.reader();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let reader = reader.clone();
+            thread::spawn(move || {
+                let local = reader.local();
+                let mut last = 0;
+                for _ in 0..2000 {
+                    // Value `i` is stored in version `i`.
+                    // 值 `i` 存储于版本 `i`。
+                    let guard = local.load();
+                    let value = *guard;
+                    assert_eq!(guard.version(), value);
+                    assert!(value >= last);
+                    last = value;
+                }
+            })
+        })
+        .collect();
+
+    for i in 1..=2000 {
+        swap.store(i);
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(*swap.load(), 2000);
+}
+
+/// Test concurrent readers on every backend, and that guards report the version of their value
+/// 测试每种后端上的并发读者，以及守卫报告其值的版本
+#[test]
+fn test_backend_concurrent() {
+    read_concurrently::<Versioned>();
+    read_concurrently::<HazardPointers>();
+    read_concurrently::<RefCounted>();
+}
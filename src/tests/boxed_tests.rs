@@ -0,0 +1,111 @@
+//! Tests for SmrSwapBox
+//!
+//! Tests storing and reading unsized values: str, slices and trait objects
+
+extern crate std;
+use crate::SmrSwapBox;
+use std::prelude::v1::*;
+use std::thread;
+
+/// Test storing and reading a boxed str
+/// 测试存储和读取 box 的 str
+#[test]
+fn test_box_str() {
+    let mut swap: SmrSwapBox<str> = SmrSwapBox::new("hello".into());
+    let local = swap.local();
+
+    assert_eq!(&*local.load(), "hello");
+    assert_eq!(swap.get(), "hello");
+
+    swap.store(String::from("world").into_boxed_str());
+    let guard = local.load();
+    assert_eq!(&*guard, "world");
+    assert_eq!(guard.to_uppercase(), "WORLD");
+    assert_eq!(swap.previous(), Some("hello"));
+}
+
+/// Test storing and reading a boxed slice
+/// 测试存储和读取 box 的切片
+#[test]
+fn test_box_slice() {
+    let mut swap: SmrSwapBox<[u32]> = SmrSwapBox::new(vec![1, 2, 3].into_boxed_slice());
+    let local = swap.local();
+
+    swap.update(|old| old.iter().map(|x| x * 10).collect());
+    assert_eq!(&*local.load(), &[10, 20, 30]);
+    assert_eq!(local.map(|s| s.len()), 3);
+}
+
+/// Test storing and calling trait objects
+/// 测试存储和调用 trait 对象
+#[test]
+fn test_box_dyn_trait() {
+    trait Handler: Send + Sync {
+        fn handle(&self, x: i32) -> i32;
+    }
+    struct Add(i32);
+    impl Handler for Add {
+        fn handle(&self, x: i32) -> i32 {
+            x + self.0
+        }
+    }
+    struct Mul(i32);
+    impl Handler for Mul {
+        fn handle(&self, x: i32) -> i32 {
+            x * self.0
+        }
+    }
+
+    let mut swap: SmrSwapBox<dyn Handler> = SmrSwapBox::new(Box::new(Add(1)));
+    let local = swap.local();
+
+    assert_eq!(local.load().handle(5), 6);
+    swap.store(Box::new(Mul(3)));
+    assert_eq!(local.load().handle(5), 15);
+}
+
+/// Test that an old guard keeps its value across stores
+/// 测试旧守卫在存储之后仍保持其值
+#[test]
+fn test_box_guard_holds_value() {
+    let mut swap: SmrSwapBox<str> = SmrSwapBox::new("v1".into());
+    let local = swap.local();
+
+    let guard = local.load();
+    let cloned = guard.clone();
+    for i in 0..20 {
+        swap.store(std::format!("v{}", i + 2).into_boxed_str());
+    }
+    swap.collect();
+
+    assert_eq!(&*guard, "v1");
+    assert_eq!(cloned.as_ref(), "v1");
+    assert_eq!(guard.version(), cloned.version());
+    drop(guard);
+    drop(cloned);
+
+    assert_eq!(&*local.load(), "v21");
+}
+
+/// Test readers created through SmrBoxReader on other threads
+/// 测试在其他线程上通过 SmrBoxReader 创建的读者
+#[test]
+fn test_box_shared_reader_threads() {
+    let mut swap: SmrSwapBox<str> = SmrSwapBox::default();
+    let factory = swap.reader();
+    swap.store("ready".into());
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let factory = factory.clone();
+            thread::spawn(move || {
+                let local = factory.local();
+                assert_eq!(&*local.load(), "ready");
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
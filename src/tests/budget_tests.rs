@@ -0,0 +1,196 @@
+//! Tests for budgeted garbage collection
+//!
+//! Tests that collections bounded by a count or a time budget reclaim part of the garbage
+//! and report what remains
+
+extern crate std;
+use super::{DropCounter, build};
+use crate::{HazardPointers, ReclaimBudget, Reclaimer, RefCounted, SmrSwap, Versioned};
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// A value whose drop takes a while.
+/// drop 需要一段时间的值。
+struct SlowDrop;
+
+impl Drop for SlowDrop {
+    fn drop(&mut self) {
+        std::thread::sleep(Duration::from_millis(2));
+    }
+}
+
+fn check_items<R: Reclaimer>() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = build::<R, _>(DropCounter(drops.clone()));
+    for _ in 0..10 {
+        swap.store(DropCounter(drops.clone()));
+    }
+
+    let collected = swap.collect_budget(3);
+    assert_eq!(collected.reclaimed(), 3);
+    assert_eq!(collected.pending(), 5);
+    assert_eq!(collected.remaining(), 7);
+    assert!(!collected.is_complete());
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+    assert_eq!(swap.garbage_count(), 7);
+
+    let collected = swap.collect_budget(0);
+    assert_eq!((collected.reclaimed(), collected.pending()), (0, 5));
+
+    let collected = swap.collect_budget(100);
+    assert_eq!(collected.reclaimed(), 5);
+    assert!(collected.is_complete());
+    assert_eq!(collected.remaining(), 2);
+    assert_eq!(drops.load(Ordering::SeqCst), 8);
+}
+
+/// Test that a count budget reclaims the oldest values and reports the rest
+/// 测试数量预算回收最旧的值并报告剩余部分
+#[test]
+fn test_collect_budget() {
+    check_items::<Versioned>();
+}
+
+/// Test count budgets with the hazard-pointer backend
+/// 测试危险指针后端的数量预算
+#[test]
+fn test_hazard_collect_budget() {
+    check_items::<HazardPointers>();
+}
+
+/// Test count budgets with the reference-counting backend
+/// 测试引用计数后端的数量预算
+#[test]
+fn test_counted_collect_budget() {
+    check_items::<RefCounted>();
+}
+
+/// Test that values a pinned reader keeps are neither reclaimed nor pending
+/// 测试被 pin 的读者保留的值既不会被回收也不算待回收
+#[test]
+fn test_budget_with_pinned_reader() {
+    let mut swap = build::<Versioned, _>(0);
+    let local = swap.local();
+    for i in 1..=3 {
+        swap.store(i);
+    }
+    let guard = local.load();
+    for i in 4..=8 {
+        swap.store(i);
+    }
+
+    let collected = swap.collect_budget(1);
+    assert_eq!((collected.reclaimed(), collected.pending()), (1, 2));
+    let collected = swap.collect_budget(10);
+    assert_eq!((collected.reclaimed(), collected.pending()), (2, 0));
+    assert_eq!(collected.remaining(), 5);
+
+    drop(guard);
+    assert_eq!(swap.collect_budget(10).remaining(), 2);
+}
+
+/// Test that a time budget stops early and still makes progress
+/// 测试时间预算会提前停止但仍能取得进展
+#[test]
+fn test_collect_for() {
+    let mut swap = build::<Versioned, _>(SlowDrop);
+    for _ in 0..42 {
+        swap.store(SlowDrop);
+    }
+
+    let collected = swap.collect_for(Duration::ZERO);
+    assert_eq!((collected.reclaimed(), collected.pending()), (1, 39));
+
+    let collected = swap.collect_for(Duration::from_millis(5));
+    assert!(collected.reclaimed() >= 1);
+    assert!(collected.reclaimed() < 39);
+    assert_eq!(
+        collected.reclaimed() + collected.pending(),
+        39,
+        "{collected:?}"
+    );
+
+    let collected = swap.collect_for(Duration::MAX);
+    assert!(collected.is_complete());
+    assert_eq!(swap.garbage_count(), 2);
+}
+
+/// Test that automatic collections with a budget bound the drops of each store
+/// 测试带预算的自动回收会限制每次存储的 drop 数量
+#[test]
+fn test_auto_reclaim_budget() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(Some(4))
+        .auto_reclaim_budget(Some(ReclaimBudget::Items(2)))
+        .build(DropCounter(drops.clone()));
+    let local = swap.local();
+
+    // A pinned reader lets the garbage list grow long.
+    // 被 pin 的读者使垃圾列表变长。
+    let guard = local.load();
+    for _ in 0..20 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    drop(guard);
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    let mut before = 0;
+    for _ in 0..20 {
+        swap.store(DropCounter(drops.clone()));
+        let after = drops.load(Ordering::SeqCst);
+        assert!(after - before <= 2);
+        before = after;
+    }
+    assert!(swap.garbage_count() <= 5);
+}
+
+/// Test that budgeted collections release values into the recycle pool
+/// 测试受预算限制的回收会将值释放到回收池中
+#[test]
+fn test_budget_recycles() {
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(8)
+        .build(vec![0u8; 16]);
+    for i in 1..=6 {
+        swap.store(vec![i; 16]);
+    }
+    swap.collect_budget(2);
+    assert_eq!(swap.recycled_count(), 2);
+    assert_eq!(swap.recycle(), Some(vec![1; 16]));
+}
+
+/// Test that values left by a budgeted collection are drained oldest first
+/// 测试受预算限制的回收留下的值会按从旧到新的顺序被取出
+#[test]
+fn test_hazard_pending_order() {
+    let mut swap = build::<HazardPointers, _>(0);
+    let local = swap.local();
+    swap.store(1);
+    let guard = local.load();
+    for i in 2..=6 {
+        swap.store(i);
+    }
+    // Values 0, 2 and 3 are reclaimable; value 1 is protected.
+    // 值 0、2 和 3 可回收；值 1 受保护。
+    assert_eq!(swap.collect_budget(1).pending(), 2);
+
+    drop(guard);
+    let reclaimed: Vec<_> = swap.drain_reclaimed().map(|(_, value)| value).collect();
+    assert_eq!(reclaimed, [1, 2, 3]);
+}
+
+/// Test the configured budget
+/// 测试配置的预算
+#[test]
+fn test_budget_config() {
+    let builder = SmrSwap::<i32>::builder().auto_reclaim_budget(Some(ReclaimBudget::Items(8)));
+    assert!(format!("{builder:?}").contains("auto_reclaim_budget: Some(Items(8))"));
+    assert_eq!(
+        crate::backend::Config::default().auto_reclaim_budget(),
+        None
+    );
+}
@@ -0,0 +1,174 @@
+//! Tests for bounded reader capacity
+//!
+//! Tests that containers built with max_readers hand out a fixed number of reader slots
+
+extern crate std;
+use super::DropCounter;
+use crate::{CapacityError, HazardPointers, SmrSwap};
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+/// Test that readers fail once every slot is in use and reuse freed slots
+/// 测试所有槽都被使用后读者创建失败，并复用被释放的槽
+#[test]
+fn test_capacity_exhausted() {
+    let swap = SmrSwap::with_max_readers(0, 2);
+    let reader = swap.reader();
+    let first = swap.try_local().unwrap();
+    let second = reader.try_local().unwrap();
+
+    let error = swap.try_local().unwrap_err();
+    assert_eq!(error, CapacityError { max_readers: 2 });
+    assert_eq!(error.max_readers(), 2);
+    assert_eq!(error.to_string(), "all 2 reader slots are in use");
+    assert!(reader.try_local().is_err());
+
+    drop(first);
+    let third = reader.try_local().unwrap();
+    assert_eq!(*third.load(), 0);
+    assert_eq!(*second.load(), 0);
+}
+
+/// Test that local panics when every slot is in use
+/// 测试所有槽都被使用时 local 会 panic
+#[test]
+#[should_panic(expected = "all 1 reader slots are in use")]
+fn test_capacity_local_panics() {
+    let swap = SmrSwap::builder().max_readers(1).build(0);
+    let _reader = swap.local();
+    let _ = swap.local();
+}
+
+/// Test that bounded readers hold back reclamation while pinned
+/// 测试有上限的读者在被 pin 时会阻止回收
+#[test]
+fn test_capacity_pin_blocks_reclamation() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = SmrSwap::builder()
+        .max_readers(1)
+        .reader_shards(4)
+        .auto_reclaim_threshold(None)
+        .build(DropCounter(drops.clone()));
+    let local = swap.try_local().unwrap();
+    let guard = local.load();
+
+    for _ in 0..5 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+    assert_eq!(swap.cell.live_readers(), (2, 1));
+
+    drop(guard);
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+    assert_eq!(swap.cell.live_readers(), (2, 0));
+}
+
+/// Test that QSBR readers take slots from the same bound as local readers
+/// 测试 QSBR 读者与本地读者占用同一上限内的槽
+#[test]
+fn test_capacity_qsbr_readers() {
+    let swap = SmrSwap::with_max_readers(0, 2);
+    let reader = swap.reader();
+    let qsbr = swap.try_qsbr_reader().unwrap();
+    let local = reader.try_local().unwrap();
+
+    assert_eq!(
+        swap.try_qsbr_reader().unwrap_err(),
+        CapacityError { max_readers: 2 }
+    );
+    assert!(reader.try_qsbr_reader().is_err());
+    assert!(swap.try_local().is_err());
+    assert_eq!(swap.reader_slots().in_use(), 3);
+
+    drop(qsbr);
+    let qsbr = reader.try_qsbr_reader().unwrap();
+    assert_eq!(*qsbr.load(), *local.load());
+}
+
+/// Test that qsbr_reader panics when every slot is in use
+/// 测试所有槽都被使用时 qsbr_reader 会 panic
+#[test]
+#[should_panic(expected = "all 1 reader slots are in use")]
+fn test_capacity_qsbr_reader_panics() {
+    let swap = SmrSwap::with_max_readers(0, 1);
+    let _reader = swap.local();
+    let _ = swap.qsbr_reader();
+}
+
+/// Test that a QSBR reader in a fixed slot holds back reclamation until it is quiescent
+/// 测试位于固定槽中的 QSBR 读者在报告静止状态之前会阻止回收
+#[test]
+fn test_capacity_qsbr_blocks_reclamation() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = SmrSwap::builder()
+        .max_readers(1)
+        .auto_reclaim_threshold(None)
+        .build(DropCounter(drops.clone()));
+    let mut qsbr = swap.try_qsbr_reader().unwrap();
+
+    for _ in 0..5 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    qsbr.quiescent();
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+
+    drop(qsbr);
+    assert_eq!(swap.reader_slots().in_use(), 1);
+}
+
+/// Test that backends without bounded readers ignore the limit
+/// 测试不限制读者数量的后端会忽略该上限
+#[test]
+fn test_capacity_ignored_by_other_backends() {
+    let swap = SmrSwap::builder()
+        .max_readers(0)
+        .reclaimer::<HazardPointers>()
+        .build(0);
+    let readers: Vec<_> = (0..4).map(|_| swap.try_local().unwrap()).collect();
+    assert_eq!(readers.len(), 4);
+}
+
+/// Test bounded readers on other threads while the writer stores and collects
+/// 测试写者存储和回收时其他线程上的有上限的读者
+#[test]
+fn test_capacity_concurrent() {
+    let mut swap = SmrSwap::with_max_readers(vec![0usize; 16], 4);
+    let reader = swap.reader();
+    let done = Arc::new(AtomicBool::new(false));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let reader = reader.clone();
+            let done = done.clone();
+            thread::spawn(move || {
+                while !done.load(Ordering::Acquire) {
+                    // Slots are released and claimed again across iterations.
+                    let local = reader.try_local().unwrap();
+                    for _ in 0..16 {
+                        let value = local.load();
+                        assert!(value.iter().all(|&x| x == value[0]));
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for i in 1..=2000 {
+        swap.store(vec![i; 16]);
+    }
+    done.store(true, Ordering::Release);
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    swap.collect();
+    assert_eq!(swap.garbage_count(), 2);
+}
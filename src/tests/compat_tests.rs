@@ -0,0 +1,104 @@
+//! Tests for the arc-swap compatibility layer
+//!
+//! Runs the same call sites against `arc_swap::ArcSwap` and `compat::ArcSwap`
+
+extern crate std;
+use crate::compat::{ArcSwap, Guard};
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::thread;
+
+/// Exercise the shared API through a type alias, as a migrating user would
+/// 像迁移中的用户一样，通过类型别名使用共享的 API
+macro_rules! shared_api {
+    ($name:ident, $swap:ty) => {
+        #[test]
+        fn $name() {
+            type Swap = $swap;
+
+            let swap = Swap::from_pointee(1);
+            assert_eq!(**swap.load(), 1);
+
+            swap.store(Arc::new(2));
+            assert_eq!(*swap.load_full(), 2);
+
+            let old = swap.swap(Arc::new(3));
+            assert_eq!(*old, 2);
+
+            let old = swap.rcu(|n| **n + 1);
+            assert_eq!(*old, 3);
+            assert_eq!(**swap.load(), 4);
+
+            let current = swap.load_full();
+            let prev = swap.compare_and_swap(&current, Arc::new(5));
+            assert!(Arc::ptr_eq(&prev, &current));
+            assert_eq!(**swap.load(), 5);
+
+            let prev = swap.compare_and_swap(&current, Arc::new(6));
+            assert_eq!(**prev, 5);
+            assert_eq!(**swap.load(), 5);
+        }
+    };
+}
+
+shared_api!(test_shared_api_arc_swap, arc_swap::ArcSwap<i32>);
+shared_api!(test_shared_api_compat, ArcSwap<i32>);
+
+/// Test that guards stay valid across stores
+/// 测试守卫在存储之后仍然有效
+#[test]
+fn test_guard_outlives_store() {
+    let swap = ArcSwap::from_pointee(String::from("old"));
+    let guard = swap.load();
+    for i in 0..20 {
+        swap.store(Arc::new(format!("new {i}")));
+    }
+    assert_eq!(**guard, "old");
+    assert_eq!(Guard::into_inner(guard).as_str(), "old");
+    assert_eq!(**swap.load(), "new 19");
+}
+
+/// Test that one thread can read several containers
+/// 测试一个线程可以读取多个容器
+#[test]
+fn test_multiple_containers() {
+    let a = ArcSwap::from_pointee(1);
+    let b = ArcSwap::from_pointee(2);
+    assert_eq!(**a.load() + **b.load(), 3);
+    drop(a);
+
+    // Creating a reader for a new container prunes readers of dropped ones.
+    // 为新容器创建读取者时会清理已 drop 容器的读取者。
+    let c = ArcSwap::from_pointee(3);
+    assert_eq!(**b.load() + **c.load(), 5);
+}
+
+/// Test concurrent writers and readers sharing the container by reference
+/// 测试通过引用共享容器的并发写者和读者
+#[test]
+fn test_concurrent_rcu() {
+    let swap = ArcSwap::from_pointee(0usize);
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..250 {
+                    swap.rcu(|n| **n + 1);
+                }
+            });
+        }
+        for _ in 0..2 {
+            s.spawn(|| {
+                let mut last = 0;
+                for _ in 0..1000 {
+                    let now = **swap.load();
+                    assert!(now >= last);
+                    last = now;
+                }
+            });
+        }
+    });
+
+    assert_eq!(**swap.load(), 1000);
+    assert_eq!(*swap.into_inner().get().as_ref(), 1000);
+}
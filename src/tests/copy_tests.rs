@@ -0,0 +1,97 @@
+//! Tests for SmrCopy
+//!
+//! Tests the sequence-lock based container for small Copy values
+
+extern crate std;
+use crate::SmrCopy;
+use std::prelude::v1::*;
+use std::thread;
+
+/// Test basic store, load and versions
+/// 测试基本的存储、加载和版本
+#[test]
+fn test_copy_store_and_load() {
+    let mut swap = SmrCopy::new(10u64);
+    let local = swap.local();
+
+    let guard = local.load();
+    assert_eq!(*guard, 10);
+    assert_eq!(guard.version(), 0);
+
+    swap.store(20);
+    assert_eq!(*swap.get(), 20);
+    assert_eq!(local.get(), 20);
+    assert_eq!(local.version(), 1);
+    assert_eq!(swap.version(), 1);
+
+    // A guard is a copy, so it keeps the old value
+    // 守卫是一个副本，因此它保留旧值
+    assert_eq!(*guard, 10);
+    assert_eq!(swap.load().version(), 1);
+}
+
+/// Test update, swap and map
+/// 测试 update、swap 和 map
+#[test]
+fn test_copy_update_swap_map() {
+    let mut swap = SmrCopy::new((1u32, 2u32));
+    let reader = swap.reader();
+
+    swap.update(|(a, b)| (a + 1, b * 2));
+    assert_eq!(reader.map(|(a, b)| a + b), 6);
+
+    let old = swap.swap((0, 0));
+    assert_eq!(old, (2, 4));
+    assert_eq!(reader.load().into_inner(), (0, 0));
+    assert_eq!(reader.version(), 2);
+}
+
+/// Test that concurrent readers never observe a torn value
+/// 测试并发读者永远不会观察到撕裂的值
+#[test]
+fn test_copy_no_torn_reads() {
+    #[derive(Clone, Copy)]
+    struct Wide([u64; 8]);
+
+    let mut swap = SmrCopy::new(Wide([0; 8]));
+    let readers: Vec<_> = (0..4).map(|_| swap.local()).collect();
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            for i in 1..=20_000u64 {
+                swap.store(Wide([i; 8]));
+            }
+        });
+
+        for reader in readers {
+            s.spawn(move || {
+                let mut last_version = 0;
+                for _ in 0..20_000 {
+                    let guard = reader.load();
+                    let first = guard.0[0];
+                    assert!(guard.0.iter().all(|&x| x == first), "torn read");
+                    assert_eq!(first as usize, guard.version());
+                    assert!(guard.version() >= last_version);
+                    last_version = guard.version();
+                }
+            });
+        }
+    });
+
+    assert_eq!(swap.version(), 20_000);
+}
+
+/// Test the standard trait implementations
+/// 测试标准 trait 实现
+#[test]
+fn test_copy_traits() {
+    let swap: SmrCopy<i32> = SmrCopy::default();
+    assert_eq!(*swap.get(), 0);
+
+    let swap = SmrCopy::from(5i32);
+    let reader = swap.local();
+    let cloned = reader.clone();
+    assert_eq!(cloned.get(), 5);
+    assert!(std::format!("{:?}", swap).contains("SmrCopy"));
+    assert!(std::format!("{:?}", reader.load()).contains("version"));
+}
@@ -0,0 +1,92 @@
+//! Tests for the critical-section feature
+//!
+//! Tests reading and registering readers from inside critical sections, as interrupt
+//! handlers do on single-core targets
+
+extern crate std;
+use crate::{SmrSwap, StaticSwap};
+use std::prelude::v1::*;
+use std::thread;
+
+/// Test loading from inside a critical section while a reader is registered
+/// 测试在临界区内加载，同时已注册读者
+#[test]
+fn test_critical_section_load_inside() {
+    let mut swap = SmrSwap::new(1);
+    let local = swap.local();
+
+    critical_section::with(|_| {
+        assert_eq!(*local.load(), 1);
+    });
+
+    swap.store(2);
+    let value = critical_section::with(|_| *local.load());
+    assert_eq!(value, 2);
+}
+
+/// Test that the reader registry lock nests inside an outer critical section
+/// 测试读者注册表锁可以嵌套在外层临界区内
+#[test]
+fn test_critical_section_registry_nested() {
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .build(String::from("v0"));
+
+    critical_section::with(|_| {
+        let local = swap.local();
+        assert_eq!(*local.load(), "v0");
+        drop(local);
+    });
+
+    for i in 1..=4 {
+        swap.store(i.to_string());
+    }
+    critical_section::with(|_| swap.collect());
+    assert_eq!(swap.garbage_count(), 2);
+}
+
+/// Test concurrent readers with the critical-section registry lock
+/// 测试使用临界区注册表锁时的并发读者
+#[test]
+fn test_critical_section_concurrent() {
+    let mut swap = SmrSwap::new(0usize);
+    let reader = swap.reader();
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            let reader = reader.clone();
+            s.spawn(move || {
+                for _ in 0..100 {
+                    // Readers come and go while the writer collects.
+                    // 读者在写者回收时不断创建和销毁。
+                    let local = reader.local();
+                    let mut last = 0;
+                    for _ in 0..10 {
+                        let value = critical_section::with(|_| *local.load());
+                        assert!(value >= last);
+                        last = value;
+                    }
+                }
+            });
+        }
+        for i in 1..=1000 {
+            swap.store(i);
+        }
+    });
+
+    assert_eq!(*swap.load(), 1000);
+}
+
+/// Test StaticSwap with portable atomics inside a critical section
+/// 测试在临界区内使用 portable atomics 的 StaticSwap
+#[test]
+fn test_critical_section_static_swap() {
+    static SWAP: StaticSwap<u32, 4, 2> = StaticSwap::new(0);
+
+    let mut writer = SWAP.claim_writer().unwrap();
+    let reader = SWAP.local().unwrap();
+
+    writer.try_store(7).unwrap();
+    let value = critical_section::with(|_| *reader.load());
+    assert_eq!(value, 7);
+}
@@ -0,0 +1,129 @@
+//! Tests for the debug-checks feature
+//!
+//! Tests that API misuse is reported with the name of the misused API
+
+extern crate std;
+use crate::SmrSwap;
+use crate::checks::MAX_PIN_DEPTH;
+use std::mem;
+use std::prelude::v1::*;
+
+/// Test that correctly nested guards stay below the limit
+/// 测试正确嵌套的守卫不会超过限制
+#[test]
+fn test_nested_guards_allowed() {
+    let swap = SmrSwap::new(1);
+    let local = swap.local();
+
+    let guards: Vec<_> = (0..MAX_PIN_DEPTH).map(|_| local.load()).collect();
+    assert!(guards.iter().all(|guard| **guard == 1));
+    drop(guards);
+
+    assert!(!local.is_pinned());
+}
+
+/// Test that guards leaked in a loop are caught by the nesting limit
+/// 测试在循环中泄漏的守卫会被嵌套限制捕获
+#[test]
+#[should_panic(expected = "guards returned by `LocalReader::load` are being leaked")]
+fn test_leaked_guards_exceed_depth() {
+    let swap = SmrSwap::new(1);
+    let local = swap.local();
+
+    for _ in 0..=MAX_PIN_DEPTH {
+        mem::forget(local.load());
+    }
+}
+
+/// Test that cloning guards counts towards the nesting limit
+/// 测试克隆守卫也会计入嵌套限制
+#[test]
+#[should_panic(expected = "`ReadGuard`s are pinned on one `LocalReader`")]
+fn test_leaked_guard_clones_exceed_depth() {
+    let swap = SmrSwap::new(1);
+    let local = swap.local();
+    let guard = local.load();
+
+    for _ in 0..MAX_PIN_DEPTH {
+        mem::forget(guard.clone());
+    }
+}
+
+/// Test that dropping a reader with a forgotten guard panics
+/// 测试 drop 带有被 forget 守卫的读者会 panic
+#[test]
+#[should_panic(expected = "`LocalReader` dropped while 1 `ReadGuard`(s) were still pinned")]
+fn test_reader_dropped_with_forgotten_guard() {
+    let swap = SmrSwap::new(1);
+    let local = swap.local();
+    mem::forget(local.load());
+    drop(local);
+}
+
+/// Test that collecting with a forgotten writer guard panics
+/// 测试在写者守卫被 forget 时执行回收会 panic
+#[test]
+#[should_panic(expected = "`SmrSwap::collect` called while a guard returned by `SmrSwap::load`")]
+fn test_collect_with_forgotten_writer_guard() {
+    let mut swap = SmrSwap::new(1);
+    mem::forget(swap.load());
+    swap.collect();
+}
+
+/// Test that collect_into names itself in the report
+/// 测试 collect_into 会在报告中给出自己的名称
+#[test]
+#[should_panic(expected = "`SmrSwap::collect_into` called")]
+fn test_collect_into_with_forgotten_writer_guard() {
+    let mut swap = SmrSwap::new(1);
+    mem::forget(swap.load());
+    swap.collect_into(&mut Vec::new());
+}
+
+/// Test that drain_reclaimed names itself in the report
+/// 测试 drain_reclaimed 会在报告中给出自己的名称
+#[test]
+#[should_panic(expected = "`SmrSwap::drain_reclaimed` called")]
+fn test_drain_reclaimed_with_forgotten_writer_guard() {
+    let mut swap = SmrSwap::new(1);
+    mem::forget(swap.load());
+    swap.drain_reclaimed().for_each(drop);
+}
+
+/// Test that collecting after writer guards were dropped is fine
+/// 测试写者守卫被 drop 后执行回收是正常的
+#[test]
+fn test_collect_after_writer_guard_dropped() {
+    let mut swap = SmrSwap::new(1);
+    {
+        let guard = swap.load();
+        assert_eq!(*guard, 1);
+    }
+    swap.store(2);
+    swap.collect();
+    assert_eq!(*swap.load(), 2);
+}
+
+/// Test the live reader count used to report readers outliving the writer
+/// 测试用于报告比写者存活更久的读者的存活读者计数
+#[test]
+fn test_live_readers() {
+    let swap = SmrSwap::new(1);
+    // The writer's own reader is always registered.
+    // 写者自己的读者总是已注册的。
+    assert_eq!(swap.cell.live_readers(), (1, 0));
+
+    let a = swap.local();
+    let b = swap.local();
+    let guard = a.load();
+    assert_eq!(swap.cell.live_readers(), (3, 1));
+
+    drop(guard);
+    drop(b);
+    assert_eq!(swap.cell.live_readers(), (2, 0));
+
+    // Readers may outlive the writer; this only produces a warning.
+    // 读者可以比写者存活更久；这只会产生警告。
+    drop(swap);
+    assert_eq!(*a.load(), 1);
+}
@@ -0,0 +1,114 @@
+//! Tests for draining reclaimed values
+//!
+//! Tests collect_into and drain_reclaimed
+
+extern crate std;
+use crate::SmrSwap;
+use std::collections::BTreeSet;
+use std::prelude::v1::*;
+
+/// Test that collect_into hands reclaimed values to the sink, oldest first
+/// 测试 collect_into 按从旧到新的顺序将已回收的值交给 sink
+#[test]
+fn test_collect_into_vec() {
+    let mut swap = SmrSwap::builder().auto_reclaim_threshold(None).build(0);
+    for i in 1..=10 {
+        swap.store(i);
+    }
+
+    let mut freed = Vec::new();
+    swap.collect_into(&mut freed);
+    assert_eq!(freed, (0..8).collect::<Vec<_>>());
+    assert_eq!(swap.garbage_count(), 2);
+    assert_eq!(swap.previous(), Some(&9));
+
+    // Nothing new to reclaim.
+    // 没有新的可回收值。
+    swap.collect_into(&mut freed);
+    assert_eq!(freed.len(), 8);
+}
+
+/// Test collect_into with other Extend implementations and readers holding values
+/// 测试使用其他 Extend 实现以及读者持有值时的 collect_into
+#[test]
+fn test_collect_into_respects_readers() {
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .build(String::from("v0"));
+    let local = swap.local();
+
+    swap.store(String::from("v1"));
+    let guard = local.load();
+    for i in 2..=6 {
+        swap.store(format!("v{i}"));
+    }
+
+    let mut freed = BTreeSet::new();
+    swap.collect_into(&mut freed);
+    assert_eq!(freed.into_iter().collect::<Vec<_>>(), ["v0"]);
+    assert_eq!(*guard, "v1");
+    drop(guard);
+
+    let mut freed = Vec::new();
+    swap.collect_into(&mut freed);
+    assert_eq!(freed, ["v1", "v2", "v3"]);
+}
+
+/// Test that collect_into bypasses the recycle pool
+/// 测试 collect_into 绕过回收池
+#[test]
+fn test_collect_into_bypasses_pool() {
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(8)
+        .build(0);
+    for i in 1..=6 {
+        swap.store(i);
+    }
+    let mut freed = Vec::new();
+    swap.collect_into(&mut freed);
+    assert_eq!(freed, [0, 1, 2, 3]);
+    assert_eq!(swap.recycled_count(), 0);
+}
+
+/// Test drain_reclaimed yields versions together with values
+/// 测试 drain_reclaimed 同时产出版本和值
+#[test]
+fn test_drain_reclaimed_versions() {
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .build(String::from("a"));
+    for s in ["b", "c", "d", "e"] {
+        swap.store(String::from(s));
+    }
+
+    let drain = swap.drain_reclaimed();
+    assert_eq!(drain.len(), 2);
+    let items: Vec<_> = drain.collect();
+    assert_eq!(items, [(0, String::from("a")), (1, String::from("b"))]);
+    assert_eq!(swap.garbage_count(), 2);
+}
+
+/// Test that unconsumed items are released when the iterator is dropped
+/// 测试迭代器被 drop 时释放未被消费的项
+#[test]
+fn test_drain_reclaimed_partial() {
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(1)
+        .build(0);
+    for i in 1..=6 {
+        swap.store(i);
+    }
+
+    let mut drain = swap.drain_reclaimed();
+    assert_eq!(drain.next(), Some((0, 0)));
+    assert_eq!(drain.len(), 3);
+    drop(drain);
+
+    // Remaining reclaimed values went through the regular release path.
+    // 剩余的已回收值经过常规释放路径。
+    assert_eq!(swap.garbage_count(), 2);
+    assert_eq!(swap.recycled_count(), 1);
+    assert_eq!(swap.drain_reclaimed().count(), 0);
+}
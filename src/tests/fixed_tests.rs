@@ -0,0 +1,167 @@
+//! Tests for StaticSwap
+//!
+//! Tests the fixed-capacity container with preallocated slots and reader slots
+
+extern crate std;
+use super::DropCounter;
+use crate::StaticSwap;
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Test basic store, load and versions
+/// 测试基本的存储、加载和版本
+#[test]
+fn test_fixed_store_and_load() {
+    let swap: StaticSwap<String, 3> = StaticSwap::new(String::from("v0"));
+    let mut writer = swap.claim_writer().unwrap();
+    let reader = swap.local().unwrap();
+
+    assert_eq!(*reader.load(), "v0");
+    assert_eq!(reader.load().version(), 0);
+
+    writer.try_store(String::from("v1")).unwrap();
+    assert_eq!(writer.get(), "v1");
+    assert_eq!(writer.version(), 1);
+
+    let guard = reader.load();
+    assert_eq!(*guard, "v1");
+    assert_eq!(guard.version(), 1);
+    assert_eq!(reader.map(|s| s.len()), 2);
+}
+
+/// Test that a static container can be used from several threads
+/// 测试静态容器可以在多个线程中使用
+#[test]
+fn test_fixed_in_static() {
+    static COUNTER: StaticSwap<u64, 6, 4> = StaticSwap::new(0);
+
+    let mut writer = COUNTER.claim_writer().unwrap();
+    assert!(COUNTER.claim_writer().is_none());
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                let reader = COUNTER.local().unwrap();
+                let mut last = 0;
+                for _ in 0..1000 {
+                    let guard = reader.load();
+                    assert!(*guard >= last);
+                    assert_eq!(*guard, guard.version() as u64);
+                    last = *guard;
+                }
+            });
+        }
+
+        // N >= R + 2, so every store finds a free slot.
+        // N >= R + 2，因此每次存储都能找到空闲槽。
+        for i in 1..=1000 {
+            writer.try_store(i).unwrap();
+        }
+    });
+
+    assert_eq!(*writer.get(), 1000);
+    drop(writer);
+    assert!(COUNTER.claim_writer().is_some());
+}
+
+/// Test that try_store fails while every other slot is pinned
+/// 测试当所有其他槽都被 pin 时 try_store 会失败
+#[test]
+fn test_fixed_try_store_full() {
+    let swap: StaticSwap<i32, 2, 2> = StaticSwap::new(0);
+    let mut writer = swap.claim_writer().unwrap();
+    let a = swap.local().unwrap();
+    let b = swap.local().unwrap();
+
+    let guard_a = a.load();
+    writer.try_store(1).unwrap();
+    let guard_b = b.load();
+
+    // Slot 0 is pinned by `a` and slot 1 is current.
+    // 槽 0 被 `a` pin，槽 1 是当前槽。
+    assert_eq!(writer.try_store(2), Err(2));
+    assert_eq!((*guard_a, *guard_b), (0, 1));
+
+    drop(guard_a);
+    writer.try_store(2).unwrap();
+    assert_eq!(*guard_b, 1);
+    assert_eq!(*a.load(), 2);
+
+    // Slot 1 is still pinned by `b`, and slot 0 is current.
+    // 槽 1 仍被 `b` pin，槽 0 是当前槽。
+    assert_eq!(writer.try_store(3), Err(3));
+}
+
+/// Test the fixed maximum number of readers
+/// 测试固定的最大读者数量
+#[test]
+fn test_fixed_max_readers() {
+    let swap: StaticSwap<i32, 4, 2> = StaticSwap::new(0);
+    let a = swap.local().unwrap();
+    let b = swap.local().unwrap();
+    assert!(swap.local().is_none());
+
+    drop(a);
+    let c = swap.local().unwrap();
+    assert_eq!(*c.load() + *b.load(), 0);
+}
+
+/// Test that nested loads read the slot of the outermost guard
+/// 测试嵌套加载读取最外层守卫的槽
+#[test]
+fn test_fixed_nested_guards() {
+    let swap: StaticSwap<i32, 3> = StaticSwap::new(1);
+    let mut writer = swap.claim_writer().unwrap();
+    let reader = swap.local().unwrap();
+
+    let outer = reader.load();
+    writer.try_store(2).unwrap();
+    let inner = reader.load();
+    let cloned = inner.clone();
+    assert_eq!((*outer, *inner, *cloned), (1, 1, 1));
+
+    drop(outer);
+    drop(inner);
+    assert!(reader.is_pinned());
+    drop(cloned);
+    assert!(!reader.is_pinned());
+    assert_eq!(*reader.load(), 2);
+}
+
+/// Test that every value is dropped exactly once, by reuse, collect or drop
+/// 测试每个值都恰好被 drop 一次，无论是通过复用、collect 还是 drop
+#[test]
+fn test_fixed_drops() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let swap: StaticSwap<DropCounter, 3, 1> = StaticSwap::new(DropCounter(drops.clone()));
+    let mut writer = swap.claim_writer().unwrap();
+    let reader = swap.local().unwrap();
+
+    for _ in 0..5 {
+        let _ = writer.try_store(DropCounter(drops.clone()));
+    }
+    // Slots are reused round-robin, so two retired values are still held.
+    // 槽按轮转方式复用，因此仍保留两个已退休的值。
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+    assert_eq!(writer.garbage_count(), 2);
+
+    let guard = reader.load();
+    let _ = writer.try_store(DropCounter(drops.clone()));
+    writer.collect();
+    // The pinned value survives collect.
+    // 被 pin 的值在 collect 后仍然存活。
+    assert_eq!(drops.load(Ordering::SeqCst), 5);
+    assert_eq!(writer.garbage_count(), 1);
+
+    drop(guard);
+    writer.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 6);
+    assert_eq!(writer.garbage_count(), 0);
+
+    drop(reader);
+    drop(writer);
+    drop(swap);
+    assert_eq!(drops.load(Ordering::SeqCst), 7);
+}
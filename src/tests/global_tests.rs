@@ -0,0 +1,93 @@
+//! Tests for StaticSmrSwap and smr_static!
+//!
+//! Tests lazy and explicit initialization, the single writer claim and implicit per-thread readers
+
+extern crate std;
+use crate::StaticSmrSwap;
+use std::prelude::v1::*;
+use std::thread;
+
+crate::smr_static! {
+    static LAZY_COUNTER: u64 = 7;
+    static LAZY_NAME: String = String::from("lazy");
+}
+
+/// Test lazy initialization on first read
+/// 测试首次读取时的延迟初始化
+#[test]
+fn test_static_lazy_init() {
+    assert_eq!(*LAZY_COUNTER.load(), 7);
+    assert_eq!(LAZY_NAME.load_cloned(), "lazy");
+    assert!(LAZY_NAME.is_initialized());
+    assert_eq!(LAZY_NAME.map(|s| s.len()), 4);
+    assert_eq!(
+        LAZY_NAME.init(String::from("late")),
+        Err(String::from("late"))
+    );
+}
+
+/// Test explicit initialization and reads before init
+/// 测试显式初始化以及初始化之前的读取
+#[test]
+fn test_static_explicit_init() {
+    static EXPLICIT: StaticSmrSwap<i32> = StaticSmrSwap::new();
+
+    assert!(!EXPLICIT.is_initialized());
+    assert!(EXPLICIT.claim_writer().is_none());
+    assert!(std::panic::catch_unwind(|| EXPLICIT.load_cloned()).is_err());
+
+    assert_eq!(EXPLICIT.init(1), Ok(()));
+    assert_eq!(EXPLICIT.init(2), Err(2));
+    assert_eq!(*EXPLICIT.load(), 1);
+}
+
+/// Test that the writer can be claimed exactly once
+/// 测试写者只能被获取一次
+#[test]
+fn test_static_claim_writer_once() {
+    static ONCE: StaticSmrSwap<u32> = StaticSmrSwap::lazy(|| 0);
+
+    let claims: Vec<_> = (0..8)
+        .map(|_| thread::spawn(|| ONCE.claim_writer()))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|h| h.join().unwrap())
+        .collect();
+    assert_eq!(claims.iter().filter(|w| w.is_some()).count(), 1);
+
+    let mut writer = claims.into_iter().flatten().next().unwrap();
+    writer.store(5);
+    assert_eq!(*ONCE.load(), 5);
+}
+
+/// Test that each thread gets its own implicit reader and sees writer updates
+/// 测试每个线程获得自己的隐式读取者并能看到写者的更新
+#[test]
+fn test_static_implicit_thread_readers() {
+    static SHARED: StaticSmrSwap<Vec<u32>> = StaticSmrSwap::lazy(Vec::new);
+
+    let mut writer = SHARED.claim_writer().unwrap();
+    writer.store(vec![1, 2, 3]);
+
+    let guard = SHARED.load();
+    let nested = SHARED.load();
+    assert_eq!(guard.version(), nested.version());
+
+    writer.store(vec![4, 5, 6]);
+    assert_eq!(*guard, vec![1, 2, 3]);
+    drop(guard);
+    drop(nested);
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..100 {
+                    let sum: u32 = SHARED.map(|v| v.iter().sum());
+                    assert_eq!(sum, 15);
+                }
+            });
+        }
+    });
+
+    assert_eq!(SHARED.version(), writer.version());
+}
@@ -0,0 +1,178 @@
+//! Tests for SmrLog
+//!
+//! Tests appending, stable snapshots, chunk boundaries, reclamation and concurrent readers
+
+extern crate std;
+use crate::SmrLog;
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Test basic push and read through a local reader
+/// 测试基本的追加和通过本地读取者读取
+#[test]
+fn test_log_push_and_read() {
+    let mut log = SmrLog::new();
+    let local = log.local();
+
+    assert!(local.load().is_empty());
+
+    log.push(1);
+    log.push(2);
+
+    let guard = local.load();
+    assert_eq!(guard.len(), 2);
+    assert_eq!(guard.get(0), Some(&1));
+    assert_eq!(guard[1], 2);
+    assert_eq!(guard.get(2), None);
+    assert_eq!(guard.last(), Some(&2));
+}
+
+/// Test that a pinned snapshot keeps a stable prefix while the writer appends
+/// 测试写者追加时已 pin 的快照保持稳定前缀
+#[test]
+fn test_log_stable_prefix() {
+    let mut log = SmrLog::new();
+    let local = log.local();
+    log.extend(0..10);
+
+    let before = local.load();
+    log.extend(10..1000);
+
+    assert_eq!(before.len(), 10);
+    assert_eq!(before.to_vec(), (0..10).collect::<Vec<_>>());
+
+    let after = local.load();
+    assert_eq!(after.len(), 1000);
+    assert!(after.iter().copied().eq(0..1000));
+}
+
+/// Test that chunk slices cover the log in order across chunk boundaries
+/// 测试块切片按顺序覆盖跨块边界的日志
+#[test]
+fn test_log_chunks_cover_prefix() {
+    let log: SmrLog<usize> = (0..100).collect();
+    let snapshot = log.snapshot();
+
+    let chunks: Vec<&[usize]> = snapshot.chunks().collect();
+    assert!(chunks.len() > 1);
+    assert_eq!(chunks.iter().map(|c| c.len()).sum::<usize>(), 100);
+    assert!(chunks.concat().into_iter().eq(0..100));
+
+    for i in 0..100 {
+        assert_eq!(log.get(i), Some(&i));
+    }
+}
+
+/// Test that extend publishes exactly one snapshot and existing entries are not copied
+/// 测试 extend 只发布一个快照且现有条目不会被复制
+#[test]
+fn test_log_extend_single_publish_no_copy() {
+    let mut log = SmrLog::new();
+    let first = Arc::new(0);
+    log.push(first.clone());
+
+    let version = log.version();
+    log.extend((1..50).map(Arc::new));
+    assert_eq!(log.version(), version + 1);
+
+    // The first entry is shared by every snapshot rather than cloned into each one
+    // 第一个条目被所有快照共享，而不是被克隆到每个快照中
+    assert_eq!(Arc::strong_count(&first), 2);
+
+    log.extend(std::iter::empty());
+    assert_eq!(log.version(), version + 1);
+}
+
+/// Test that entries are dropped exactly once after clear and reclamation
+/// 测试 clear 和回收后条目恰好被 drop 一次
+#[test]
+fn test_log_clear_reclaims_entries() {
+    struct Counted(Arc<AtomicUsize>);
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut log = SmrLog::new();
+    let local = log.local();
+    log.extend((0..40).map(|_| Counted(drops.clone())));
+
+    let old = local.load();
+    log.clear();
+    assert!(log.is_empty());
+    assert_eq!(old.len(), 40);
+
+    for _ in 0..8 {
+        log.push(Counted(drops.clone()));
+        log.collect();
+    }
+    assert_eq!(
+        drops.load(Ordering::SeqCst),
+        0,
+        "pinned entries were dropped"
+    );
+
+    drop(old);
+    log.clear();
+    for _ in 0..4 {
+        log.push(Counted(drops.clone()));
+    }
+    log.collect();
+    // Both the 40 entries cleared first and the 8 entries cleared second are reclaimed
+    // 第一次清除的 40 个条目和第二次清除的 8 个条目都被回收
+    assert_eq!(drops.load(Ordering::SeqCst), 48);
+
+    drop(local);
+    drop(log);
+    assert_eq!(drops.load(Ordering::SeqCst), 52);
+}
+
+/// Test that entries appended before a panicking iterator are kept
+/// 测试迭代器 panic 之前追加的条目被保留
+#[test]
+fn test_log_extend_panic_keeps_prefix() {
+    let mut log = SmrLog::new();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        log.extend((0..100).map(|i| if i == 70 { core::panic!("boom") } else { i }));
+    }));
+    assert!(result.is_err());
+    assert_eq!(log.len(), 70);
+
+    log.push(70);
+    assert!(log.snapshot().iter().copied().eq(0..71));
+}
+
+/// Test concurrent readers always see a consistent prefix
+/// 测试并发读者总是看到一致的前缀
+#[test]
+fn test_log_concurrent_readers() {
+    let mut log = SmrLog::new();
+    let readers: Vec<_> = (0..4).map(|_| log.local()).collect();
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            for i in 0..5000usize {
+                log.push(i);
+            }
+        });
+
+        for local in readers {
+            s.spawn(move || {
+                let mut last_len = 0;
+                for _ in 0..500 {
+                    let guard = local.load();
+                    assert!(guard.len() >= last_len);
+                    assert!(guard.iter().copied().eq(0..guard.len()));
+                    last_len = guard.len();
+                }
+            });
+        }
+    });
+
+    assert_eq!(log.len(), 5000);
+}
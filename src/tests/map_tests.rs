@@ -0,0 +1,196 @@
+//! Tests for SmrMap
+//!
+//! Tests copy-on-write map operations, batching, shard sharing and concurrent reads
+
+extern crate std;
+use crate::SmrMap;
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::thread;
+
+/// Test basic insert and read through a local reader
+/// 测试基本的插入和通过本地读取者读取
+#[test]
+fn test_map_insert_and_read() {
+    let mut map = SmrMap::new();
+    let local = map.local();
+
+    assert!(local.load().is_empty());
+
+    assert_eq!(map.insert("a", 1), None);
+    assert_eq!(map.insert("b", 2), None);
+    assert_eq!(map.insert("a", 10), Some(1));
+
+    let guard = local.load();
+    assert_eq!(guard.get("a"), Some(&10));
+    assert_eq!(guard.get("b"), Some(&2));
+    assert_eq!(guard.get("c"), None);
+    assert!(guard.contains_key("b"));
+    assert_eq!(guard.len(), 2);
+}
+
+/// Test remove and that removing a missing key does not publish
+/// 测试移除以及移除不存在的键不会发布新快照
+#[test]
+fn test_map_remove() {
+    let mut map = SmrMap::new();
+    map.insert(1, String::from("one"));
+    map.insert(2, String::from("two"));
+
+    let version = map.version();
+    assert_eq!(map.remove(&3), None);
+    assert_eq!(map.version(), version);
+
+    assert_eq!(map.remove(&1), Some(String::from("one")));
+    assert_eq!(map.version(), version + 1);
+    assert!(!map.contains_key(&1));
+    assert_eq!(map.len(), 1);
+}
+
+/// Test that extend and batch publish exactly one snapshot
+/// 测试 extend 和 batch 只发布一个快照
+#[test]
+fn test_map_batch_single_publish() {
+    let mut map = SmrMap::new();
+    let local = map.local();
+
+    let version = map.version();
+    map.extend((0..100).map(|i| (i, i * 2)));
+    assert_eq!(map.version(), version + 1);
+    assert_eq!(local.load().len(), 100);
+
+    let removed = map.batch(|batch| {
+        batch.insert(1000, 0);
+        assert_eq!(batch.get(&1000), Some(&0));
+        (0..50).filter_map(|i| batch.remove(&i)).count()
+    });
+    assert_eq!(removed, 50);
+    assert_eq!(map.version(), version + 2);
+
+    let guard = local.load();
+    assert_eq!(guard.len(), 51);
+    assert!(!guard.contains_key(&0));
+    assert_eq!(guard.get(&99), Some(&198));
+    assert_eq!(guard.get(&1000), Some(&0));
+}
+
+/// Test that old snapshots stay intact while the writer continues
+/// 测试写者继续写入时旧快照保持不变
+#[test]
+fn test_map_snapshot_isolation() {
+    let mut map = SmrMap::new();
+    let local = map.local();
+    map.insert("k", 1);
+
+    let old = local.load();
+    map.insert("k", 2);
+    map.insert("other", 3);
+    map.clear();
+
+    assert_eq!(old.get("k"), Some(&1));
+    assert_eq!(old.len(), 1);
+    drop(old);
+
+    assert!(local.load().is_empty());
+}
+
+/// Test that unchanged shards are shared between snapshots
+/// 测试未修改的分片在快照之间共享
+#[test]
+fn test_map_structural_sharing() {
+    let mut map = SmrMap::new();
+    let values: Vec<_> = (0..64).map(|i| (i, Arc::new(i))).collect();
+    map.extend(values.iter().cloned());
+
+    let before: usize = values.iter().map(|(_, v)| Arc::strong_count(v)).sum();
+    map.insert(1000, Arc::new(1000));
+    let after: usize = values.iter().map(|(_, v)| Arc::strong_count(v)).sum();
+
+    // Only the touched shard is cloned, so far fewer than 64 values gain a reference
+    // 只有被触及的分片被克隆，因此获得新引用的值远少于 64 个
+    assert!(
+        after - before < 64,
+        "insert cloned {} entries",
+        after - before
+    );
+}
+
+/// Test iteration, retain and the standard trait implementations
+/// 测试迭代、retain 和标准 trait 实现
+#[test]
+fn test_map_iter_retain_traits() {
+    let mut map: SmrMap<u32, u32> = (0..10).map(|i| (i, i)).collect();
+
+    let mut keys: Vec<_> = map.load().keys().copied().collect();
+    keys.sort_unstable();
+    assert_eq!(keys, (0..10).collect::<Vec<_>>());
+
+    map.retain(|_, v| v % 2 == 0);
+    let sum: u32 = map.load().values().sum();
+    assert_eq!(sum, 2 + 4 + 6 + 8);
+    assert_eq!(map.len(), 5);
+
+    let empty: SmrMap<u32, u32> = SmrMap::default();
+    assert!(empty.is_empty());
+    assert!(std::format!("{:?}", empty).contains("SmrMap"));
+}
+
+/// Test that retain calls the predicate once per entry and removes exactly what it rejected
+/// 测试 retain 对每个条目只调用一次谓词，并且恰好移除被拒绝的条目
+#[test]
+fn test_map_retain_calls_once() {
+    let mut map: SmrMap<u32, u32> = (0..100).map(|i| (i, i)).collect();
+
+    // A predicate with state: it rejects every other entry it is shown.
+    // 一个有状态的谓词：它拒绝每隔一个被展示的条目。
+    let mut calls = 0;
+    let mut rejected = Vec::new();
+    map.retain(|&k, _| {
+        calls += 1;
+        let keep = calls % 2 == 0;
+        if !keep {
+            rejected.push(k);
+        }
+        keep
+    });
+
+    assert_eq!(calls, 100);
+    assert_eq!(map.len(), 50);
+    let guard = map.load();
+    assert_eq!(guard.len(), 50);
+    assert!(rejected.iter().all(|k| !guard.contains_key(k)));
+}
+
+/// Test concurrent readers observe complete batches only
+/// 测试并发读者只能观察到完整的批处理
+#[test]
+fn test_map_concurrent_batches() {
+    let mut map = SmrMap::new();
+    let readers: Vec<_> = (0..4).map(|_| map.local()).collect();
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            for round in 0..200u32 {
+                map.batch(|batch| {
+                    for key in 0..8u32 {
+                        batch.insert(key, round);
+                    }
+                });
+            }
+        });
+
+        for local in readers {
+            s.spawn(move || {
+                for _ in 0..1000 {
+                    let guard = local.load();
+                    let mut values = guard.values();
+                    if let Some(first) = values.next() {
+                        assert!(values.all(|v| v == first), "observed a partial batch");
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(map.get(&0), Some(&199));
+}
@@ -0,0 +1,169 @@
+//! Tests for the metrics feature
+//!
+//! Records metrics with the debugging recorder from metrics-util
+
+extern crate std;
+use crate::SmrSwap;
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use std::prelude::v1::*;
+
+/// Run `f` with a debugging recorder and return the metrics it recorded.
+///
+/// The debugging recorder resets values when snapshotted, so each test takes one snapshot.
+///
+/// 使用调试 recorder 运行 `f`，并返回其记录的指标。
+///
+/// 调试 recorder 在快照时会重置值，因此每个测试只获取一次快照。
+fn record(f: impl FnOnce()) -> Vec<(String, String, DebugValue)> {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, f);
+    snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let label = key
+                .key()
+                .labels()
+                .find(|l| l.key() == "name")
+                .map(|l| l.value().to_string())
+                .unwrap_or_default();
+            (key.key().name().to_string(), label, value)
+        })
+        .collect()
+}
+
+fn find<'a>(
+    metrics: &'a [(String, String, DebugValue)],
+    name: &str,
+    label: &str,
+) -> &'a DebugValue {
+    metrics
+        .iter()
+        .find(|(n, l, _)| n == name && l == label)
+        .map(|(_, _, v)| v)
+        .unwrap_or_else(|| panic!("missing metric {name}{{name={label}}}"))
+}
+
+fn gauge(metrics: &[(String, String, DebugValue)], name: &str, label: &str) -> f64 {
+    match find(metrics, name, label) {
+        DebugValue::Gauge(v) => v.into_inner(),
+        other => panic!("{name} is not a gauge: {other:?}"),
+    }
+}
+
+fn counter(metrics: &[(String, String, DebugValue)], name: &str, label: &str) -> u64 {
+    match find(metrics, name, label) {
+        DebugValue::Counter(v) => *v,
+        other => panic!("{name} is not a counter: {other:?}"),
+    }
+}
+
+/// Test that stores update the version, garbage and store metrics
+/// 测试存储会更新版本、垃圾和存储指标
+#[test]
+fn test_metrics_store() {
+    let metrics = record(|| {
+        let mut swap = SmrSwap::builder()
+            .name("config")
+            .auto_reclaim_threshold(None)
+            .build(0);
+        for i in 1..=5 {
+            swap.store(i);
+        }
+    });
+
+    assert_eq!(gauge(&metrics, "smr_swap_version", "config"), 5.0);
+    assert_eq!(gauge(&metrics, "smr_swap_garbage_count", "config"), 5.0);
+    assert_eq!(counter(&metrics, "smr_swap_stores_total", "config"), 5);
+}
+
+/// Test that collections report reclaimed values and their latency
+/// 测试回收会报告已回收的值及其延迟
+#[test]
+fn test_metrics_collect() {
+    let metrics = record(|| {
+        let mut swap = SmrSwap::builder()
+            .name("config")
+            .auto_reclaim_threshold(None)
+            .build(0);
+        for i in 1..=5 {
+            swap.store(i);
+        }
+        swap.collect();
+        swap.collect();
+    });
+
+    assert_eq!(gauge(&metrics, "smr_swap_garbage_count", "config"), 2.0);
+    assert_eq!(counter(&metrics, "smr_swap_reclaimed_total", "config"), 3);
+    match find(&metrics, "smr_swap_reclaim_duration_seconds", "config") {
+        DebugValue::Histogram(samples) => assert_eq!(samples.len(), 2),
+        other => panic!("unexpected histogram: {other:?}"),
+    }
+}
+
+/// Test that reader gauges track live and pinned readers
+/// 测试读者指标跟踪存活和被 pin 的读者
+#[test]
+fn test_metrics_readers() {
+    let metrics = record(|| {
+        let mut swap = SmrSwap::builder().name("readers").build(String::new());
+        let a = swap.local();
+        let b = swap.local();
+        let c = b.clone();
+        drop(b);
+
+        let guard = a.load();
+        swap.collect();
+        drop(c);
+        drop(guard);
+        drop(a);
+        std::mem::forget(swap);
+    });
+
+    // Four readers were registered (including the container's internal one); `b` was
+    // pruned by the scan, and `c` and `a` were dropped afterwards.
+    // 注册了四个读者（包括容器内部的读者）；`b` 在扫描时被移除，`c` 和 `a` 随后被 drop。
+    assert_eq!(gauge(&metrics, "smr_swap_readers", "readers"), 1.0);
+    assert_eq!(gauge(&metrics, "smr_swap_pinned_readers", "readers"), 1.0);
+}
+
+/// Test that the reader gauge counts bounded and sharded readers
+/// 测试读者指标统计有上限的读者和分片读者
+#[test]
+fn test_metrics_bounded_and_sharded_readers() {
+    let metrics = record(|| {
+        let mut bounded = SmrSwap::builder().name("bounded").max_readers(4).build(0);
+        let mut sharded = SmrSwap::builder().name("sharded").reader_shards(2).build(0);
+        let readers = [bounded.local(), bounded.local(), sharded.local()];
+
+        let guard = readers[0].load();
+        bounded.collect();
+        sharded.collect();
+        drop(guard);
+        std::mem::forget((bounded, sharded, readers));
+    });
+
+    // Each count includes the container's internal reader.
+    // 每个计数都包括容器内部的读者。
+    assert_eq!(gauge(&metrics, "smr_swap_readers", "bounded"), 3.0);
+    assert_eq!(gauge(&metrics, "smr_swap_pinned_readers", "bounded"), 1.0);
+    assert_eq!(gauge(&metrics, "smr_swap_readers", "sharded"), 2.0);
+}
+
+/// Test that containers are told apart by their name label
+/// 测试容器通过名称标签区分
+#[test]
+fn test_metrics_names() {
+    let metrics = record(|| {
+        let mut first = SmrSwap::builder().name(String::from("first")).build(0);
+        let mut unnamed = SmrSwap::new(0);
+        first.store(1);
+        unnamed.store(1);
+        unnamed.store(2);
+    });
+
+    assert_eq!(counter(&metrics, "smr_swap_stores_total", "first"), 1);
+    assert_eq!(counter(&metrics, "smr_swap_stores_total", "unnamed"), 2);
+}
@@ -4,7 +4,90 @@
 //! - basic_tests: Basic functionality tests
 //! - concurrent_tests: Concurrent read/write tests
 //! - advanced_tests: Advanced API tests
+//! - alloc_tests: Custom allocator tests
+//! - backend_tests: Reclamation backend tests
+//! - budget_tests: Budgeted garbage collection tests
+//! - capacity_tests: Bounded reader capacity tests
+//! - map_tests: SmrMap tests
+//! - log_tests: SmrLog tests
+//! - boxed_tests: SmrSwapBox tests
+//! - global_tests: StaticSmrSwap tests
+//! - copy_tests: SmrCopy tests
+//! - fixed_tests: StaticSwap tests
+//! - compat_tests: arc-swap compatibility tests
+//! - critical_section_tests: critical-section lock tests
+//! - qsbr_tests: Quiescent-state-based reader tests
+//! - recycle_tests: Allocation recycling tests
+//! - shard_tests: Sharded reader slot tests
+//! - slot_tests: Reader slot reuse tests
+//! - drain_tests: Reclaimed value draining tests
+//! - strategy_tests: Per-container strategy tests
+//! - serde_tests: serde support tests
+//! - reload_tests: FileReloader tests
+//! - tracing_tests: tracing instrumentation tests
+//! - metrics_tests: metrics integration tests
+//! - debug_checks_tests: API misuse detection tests
+//! - model_tests: Model-based property tests
 
 mod advanced_tests;
+mod alloc_tests;
+mod backend_tests;
 mod basic_tests;
+mod boxed_tests;
+#[cfg(feature = "std")]
+mod budget_tests;
+mod capacity_tests;
+#[cfg(feature = "compat")]
+mod compat_tests;
 mod concurrent_tests;
+mod copy_tests;
+#[cfg(feature = "critical-section")]
+mod critical_section_tests;
+#[cfg(feature = "debug-checks")]
+mod debug_checks_tests;
+mod drain_tests;
+mod fixed_tests;
+#[cfg(feature = "std")]
+mod global_tests;
+mod log_tests;
+#[cfg(feature = "std")]
+mod map_tests;
+#[cfg(feature = "metrics")]
+mod metrics_tests;
+#[cfg(feature = "std")]
+mod model_tests;
+mod qsbr_tests;
+mod recycle_tests;
+#[cfg(feature = "std")]
+mod reload_tests;
+#[cfg(feature = "serde")]
+mod serde_tests;
+mod shard_tests;
+mod slot_tests;
+mod strategy_tests;
+#[cfg(feature = "tracing")]
+mod tracing_tests;
+
+extern crate std;
+use crate::{Global, Reclaimer, SmrSwap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A value that counts how many times values of its kind were dropped.
+/// 统计同类值被 drop 次数的值。
+struct DropCounter(Arc<AtomicUsize>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Build a container with the given backend that only collects when asked.
+/// 使用给定后端构建一个仅在被要求时才回收的容器。
+fn build<R: Reclaimer, T: 'static>(initial: T) -> SmrSwap<T, Global, R> {
+    SmrSwap::builder()
+        .reclaimer::<R>()
+        .auto_reclaim_threshold(None)
+        .build(initial)
+}
@@ -0,0 +1,108 @@
+//! Model-based property tests
+//!
+//! Tests SmrSwap on every backend and SmrSwapBox against the sequential reference model in `testing`
+
+extern crate std;
+use crate::testing::{self, Op, Tracked};
+use crate::{Global, HazardPointers, RefCounted, SmrSwap, SmrSwapBox};
+use proptest::prelude::*;
+use std::prelude::v1::*;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    /// Test that SmrSwap matches the model on arbitrary scenarios
+    /// 测试 SmrSwap 在任意场景下与模型一致
+    #[test]
+    fn prop_smr_swap_matches_model(ops in testing::ops(96)) {
+        testing::run::<SmrSwap<Tracked>>(&ops);
+    }
+
+    /// Test that SmrSwap with hazard pointers matches the model on arbitrary scenarios
+    /// 测试使用风险指针的 SmrSwap 在任意场景下与模型一致
+    #[test]
+    fn prop_hazard_pointers_match_model(ops in testing::ops(96)) {
+        testing::run::<SmrSwap<Tracked, Global, HazardPointers>>(&ops);
+    }
+
+    /// Test that SmrSwap with reference counting matches the model on arbitrary scenarios
+    /// 测试使用引用计数的 SmrSwap 在任意场景下与模型一致
+    #[test]
+    fn prop_ref_counted_matches_model(ops in testing::ops(96)) {
+        testing::run::<SmrSwap<Tracked, Global, RefCounted>>(&ops);
+    }
+
+    /// Test that SmrSwapBox matches the model on arbitrary scenarios
+    /// 测试 SmrSwapBox 在任意场景下与模型一致
+    #[test]
+    fn prop_smr_swap_box_matches_model(ops in testing::ops(96)) {
+        testing::run::<SmrSwapBox<Tracked>>(&ops);
+    }
+
+    /// Test that any byte string decodes to a runnable scenario
+    /// 测试任意字节串都能解码为可运行的场景
+    #[test]
+    fn prop_decoded_bytes_match_model(bytes in proptest::collection::vec(any::<u8>(), 0..96)) {
+        testing::run::<SmrSwap<Tracked>>(&Op::decode(&bytes));
+    }
+}
+
+/// Test nested guards held across stores and collections
+/// 测试跨存储和回收持有的嵌套守卫
+#[test]
+fn test_nested_guards_across_collections() {
+    testing::run::<SmrSwap<Tracked>>(&[
+        Op::Local,
+        Op::Load(0),
+        Op::Store,
+        Op::Load(0),
+        Op::Store,
+        Op::Store,
+        Op::Collect,
+        Op::DropGuard(0),
+        Op::Store,
+        Op::Collect,
+        Op::Load(0),
+        Op::DropGuard(0),
+        Op::DropGuard(0),
+        Op::Collect,
+    ]);
+}
+
+/// Test readers dropped while holding guards, and readers outliving collections
+/// 测试持有守卫时被 drop 的读者，以及比回收存活更久的读者
+#[test]
+fn test_readers_dropped_with_guards() {
+    testing::run::<SmrSwap<Tracked>>(&[
+        Op::Local,
+        Op::Local,
+        Op::Load(0),
+        Op::Update,
+        Op::Load(1),
+        Op::Update,
+        Op::DropReader(0),
+        Op::Collect,
+        Op::Store,
+        Op::Collect,
+        Op::Load(0),
+    ]);
+}
+
+/// Test that decoding maps every byte to one operation
+/// 测试解码会将每个字节映射为一个操作
+#[test]
+fn test_decode() {
+    assert_eq!(
+        Op::decode(&[0, 1, 2, 3 | (5 << 3), 4, 5 | (2 << 3), 6, 7]),
+        [
+            Op::Store,
+            Op::Update,
+            Op::Local,
+            Op::Load(5),
+            Op::Load(0),
+            Op::DropGuard(2),
+            Op::DropReader(0),
+            Op::Collect,
+        ]
+    );
+}
@@ -0,0 +1,119 @@
+//! Tests for quiescent-state-based readers
+//!
+//! Tests that QsbrReader holds back reclamation until it reports a quiescent state
+
+extern crate std;
+use super::DropCounter;
+use crate::SmrSwap;
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+/// Test that loads see the newest value without a quiescent state
+/// 测试在没有静止状态时加载也能看到最新的值
+#[test]
+fn test_qsbr_load() {
+    let mut swap = SmrSwap::new(String::from("v0"));
+    let mut reader = swap.qsbr_reader();
+    assert_eq!(reader.load(), "v0");
+
+    swap.store(String::from("v1"));
+    assert_eq!(reader.load(), "v1");
+    assert_eq!(reader.load_cloned(), "v1");
+    assert_eq!(reader.version(), 1);
+    assert_eq!(reader.quiescent_version(), 0);
+
+    reader.quiescent();
+    assert_eq!(reader.quiescent_version(), 1);
+}
+
+/// Test that retired values are kept until the reader is quiescent
+/// 测试已退休的值会一直保留到读者进入静止状态
+#[test]
+fn test_qsbr_blocks_until_quiescent() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .build(DropCounter(drops.clone()));
+    let mut reader = swap.qsbr_reader();
+    let _ = reader.load();
+
+    for _ in 0..5 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+    assert_eq!(swap.garbage_count(), 5);
+
+    reader.quiescent();
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+    assert_eq!(swap.garbage_count(), 2);
+}
+
+/// Test that dropping the reader releases its announcement
+/// 测试 drop 读者会释放其公布的版本
+#[test]
+fn test_qsbr_drop_releases() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .build(DropCounter(drops.clone()));
+    let reader = swap.reader().qsbr_reader();
+    let cloned = reader.clone();
+
+    for _ in 0..4 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    drop(reader);
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    drop(cloned);
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+}
+
+/// Test QSBR readers alongside pinned readers across threads
+/// 测试 QSBR 读者与被 pin 的读者跨线程共存
+#[test]
+fn test_qsbr_concurrent() {
+    let mut swap = SmrSwap::new(vec![0usize; 16]);
+    let reader = swap.reader();
+    let done = Arc::new(AtomicBool::new(false));
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let reader = reader.clone();
+            let done = done.clone();
+            thread::spawn(move || {
+                if i % 2 == 0 {
+                    let mut qsbr = reader.qsbr_reader();
+                    while !done.load(Ordering::Acquire) {
+                        let value = qsbr.load();
+                        assert!(value.iter().all(|&x| x == value[0]));
+                        qsbr.quiescent();
+                    }
+                } else {
+                    let local = reader.local();
+                    while !done.load(Ordering::Acquire) {
+                        let value = local.load();
+                        assert!(value.iter().all(|&x| x == value[0]));
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for i in 1..=2000 {
+        swap.store(vec![i; 16]);
+    }
+    done.store(true, Ordering::Release);
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    swap.collect();
+    assert_eq!(swap.garbage_count(), 2);
+}
@@ -0,0 +1,192 @@
+//! Tests for allocation recycling
+//!
+//! Tests the builder options, store_with and recycle
+
+extern crate std;
+use crate::SmrSwap;
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Test that recycling is disabled by default
+/// 测试默认情况下不启用回收
+#[test]
+fn test_recycle_disabled_by_default() {
+    let mut swap = SmrSwap::new(0);
+    for i in 1..=20 {
+        swap.store_with(|recycled| {
+            assert!(recycled.is_none());
+            i
+        });
+    }
+    swap.collect();
+    assert_eq!(swap.recycled_count(), 0);
+    assert_eq!(swap.recycle(), None);
+    assert_eq!(*swap.get(), 20);
+}
+
+/// Test that store_with reuses the allocation of a reclaimed value
+/// 测试 store_with 复用已回收值的内存分配
+#[test]
+fn test_store_with_reuses_allocation() {
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(4)
+        .build(vec![0u32; 64]);
+
+    for i in 1..=4 {
+        swap.store(vec![i; 64]);
+    }
+    swap.collect();
+    assert!(swap.recycled_count() > 0);
+
+    let before = swap.recycled_count();
+    let mut seen = None;
+    swap.store_with(|recycled| {
+        let buf = recycled.expect("pool is not empty");
+        seen = Some(buf[0]);
+        vec![99; 64]
+    });
+    assert_eq!(swap.recycled_count(), before - 1);
+    // The two most recently retired values are never reclaimed.
+    // 最近退休的两个值永远不会被回收。
+    assert!(seen.unwrap() < 2);
+    assert_eq!(swap.get()[0], 99);
+    assert_eq!(swap.load()[0], 99);
+}
+
+/// Test that the pool never exceeds its capacity and the rest is dropped
+/// 测试回收池永远不会超过其容量，其余的值会被 drop
+#[test]
+fn test_recycle_capacity_limit() {
+    let drops = Arc::new(AtomicUsize::new(0));
+
+    struct Tracked(Arc<AtomicUsize>);
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(2)
+        .build(Tracked(drops.clone()));
+
+    for _ in 0..10 {
+        swap.store(Tracked(drops.clone()));
+    }
+    swap.collect();
+
+    // 10 retired, the two newest kept: 8 reclaimed, 2 pooled, 6 dropped.
+    // 退休 10 个，保留最新的两个：回收 8 个，入池 2 个，drop 6 个。
+    assert_eq!(swap.garbage_count(), 2);
+    assert_eq!(swap.recycled_count(), 2);
+    assert_eq!(drops.load(Ordering::SeqCst), 6);
+
+    let taken = swap.recycle();
+    assert!(taken.is_some());
+    assert_eq!(swap.recycled_count(), 1);
+    drop(taken);
+    assert_eq!(drops.load(Ordering::SeqCst), 7);
+
+    drop(swap);
+    assert_eq!(drops.load(Ordering::SeqCst), 11);
+}
+
+/// Test that values observed by a pinned reader are never recycled
+/// 测试被 pin 的读者观察到的值永远不会被回收
+#[test]
+fn test_recycle_respects_readers() {
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(16)
+        .build(String::from("v0"));
+    let local = swap.local();
+
+    let guard = local.load();
+    for i in 1..=5 {
+        swap.store(format!("v{i}"));
+    }
+    swap.collect();
+    assert_eq!(swap.recycled_count(), 0);
+    assert_eq!(*guard, "v0");
+    drop(guard);
+
+    swap.collect();
+    let mut pooled = Vec::new();
+    while let Some(value) = swap.recycle() {
+        pooled.push(value);
+    }
+    pooled.sort();
+    assert_eq!(pooled, ["v0", "v1", "v2"]);
+}
+
+/// Test that a panicking closure leaves the container usable
+/// 测试 panic 的闭包不会破坏容器
+#[test]
+fn test_store_with_panic() {
+    let mut swap = SmrSwap::builder()
+        .auto_reclaim_threshold(None)
+        .recycle_capacity(4)
+        .build(String::from("initial"));
+    for i in 0..4 {
+        swap.store(format!("value {i}"));
+    }
+    swap.collect();
+    let pooled = swap.recycled_count();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        swap.store_with(|_| core::panic!("builder failed"));
+    }));
+    assert!(result.is_err());
+    assert_eq!(swap.recycled_count(), pooled - 1);
+    assert_eq!(*swap.get(), "value 3");
+
+    swap.store_with(|recycled| {
+        let mut s = recycled.unwrap();
+        s.clear();
+        s.push_str("after");
+        s
+    });
+    assert_eq!(*swap.load(), "after");
+}
+
+/// Test recycling under concurrent readers
+/// 测试并发读者下的回收
+#[test]
+fn test_recycle_concurrent() {
+    let mut swap = SmrSwap::builder()
+        .recycle_capacity(8)
+        .build(vec![0usize; 32]);
+    let reader = swap.reader();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let reader = reader.clone();
+            thread::spawn(move || {
+                let local = reader.local();
+                for _ in 0..2000 {
+                    let guard = local.load();
+                    let first = guard[0];
+                    assert!(guard.iter().all(|&x| x == first));
+                }
+            })
+        })
+        .collect();
+
+    for i in 1..=2000 {
+        swap.store_with(|recycled| {
+            let mut buf = recycled.unwrap_or_default();
+            buf.clear();
+            buf.resize(32, i);
+            buf
+        });
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(swap.get()[0], 2000);
+}
@@ -0,0 +1,137 @@
+//! Tests for FileReloader
+//!
+//! Tests polling, manual reloads, error reporting and stopping
+
+extern crate std;
+use crate::{FileReloader, ReloadError, SmrSwap};
+use std::fs;
+use std::path::PathBuf;
+use std::prelude::v1::*;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("smr_swap_{}_{}", std::process::id(), name))
+}
+
+fn parse_u32(bytes: &[u8]) -> Result<u32, String> {
+    std::str::from_utf8(bytes)
+        .map_err(|e| e.to_string())?
+        .trim()
+        .parse()
+        .map_err(|e: std::num::ParseIntError| e.to_string())
+}
+
+fn wait_until(mut f: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if f() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    false
+}
+
+/// Test that the file is loaded on spawn and that polling picks up changes
+/// 测试启动时加载文件，且轮询能够发现变化
+#[test]
+fn test_reload_polls_changes() {
+    let path = temp_path("poll");
+    fs::write(&path, "1").unwrap();
+
+    let handle = FileReloader::new(&path, parse_u32)
+        .interval(Duration::from_millis(10))
+        .spawn(SmrSwap::new(0));
+    let local = handle.local();
+    assert!(wait_until(|| *local.load() == 1));
+
+    // Size changes so the change is seen even with coarse mtime resolution.
+    // 大小发生变化，因此即使 mtime 精度较粗也能发现变化。
+    fs::write(&path, "1234").unwrap();
+    assert!(wait_until(|| *local.load() == 1234));
+
+    let swap = handle.stop();
+    assert_eq!(*swap.get(), 1234);
+    assert_eq!(swap.version(), 2);
+    fs::remove_file(&path).ok();
+}
+
+/// Test that reload_now forces a reload
+/// 测试 reload_now 强制重载
+#[test]
+fn test_reload_now() {
+    let path = temp_path("now");
+    fs::write(&path, "10").unwrap();
+
+    let handle = FileReloader::new(&path, parse_u32)
+        .interval(Duration::from_secs(3600))
+        .spawn(SmrSwap::new(0));
+    let reader = handle.reader();
+
+    fs::write(&path, "20").unwrap();
+    assert!(handle.reload_now());
+    assert_eq!(*reader.local().load(), 20);
+
+    let swap = handle.stop();
+    assert_eq!(swap.version(), 2);
+    fs::remove_file(&path).ok();
+}
+
+/// Test that parse and I/O errors go to the callback and keep the last good value
+/// 测试解析和 I/O 错误会传递给回调，并保留上一个有效值
+#[test]
+fn test_reload_errors() {
+    let path = temp_path("errors");
+    fs::write(&path, "5").unwrap();
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let sink = errors.clone();
+    let handle = FileReloader::new(&path, parse_u32)
+        .interval(Duration::from_secs(3600))
+        .on_error(move |err| {
+            let kind = match err {
+                ReloadError::Io(_) => "io",
+                ReloadError::Parse(_) => "parse",
+            };
+            sink.lock().unwrap().push(kind);
+        })
+        .spawn(SmrSwap::new(0));
+    let local = handle.local();
+    assert!(wait_until(|| *local.load() == 5));
+
+    fs::write(&path, "not a number").unwrap();
+    assert!(!handle.reload_now());
+
+    fs::remove_file(&path).unwrap();
+    assert!(!handle.reload_now());
+
+    assert_eq!(*errors.lock().unwrap(), ["parse", "io"]);
+    assert_eq!(*local.load(), 5);
+    drop(local);
+    drop(handle);
+}
+
+/// Test that polling skips files whose contents did not change
+/// 测试轮询会跳过内容未变化的文件
+#[test]
+fn test_reload_skips_unchanged_contents() {
+    let path = temp_path("unchanged");
+    fs::write(&path, "7").unwrap();
+
+    let handle = FileReloader::new(&path, parse_u32)
+        .interval(Duration::from_millis(5))
+        .spawn(SmrSwap::new(0));
+    let local = handle.local();
+    assert!(wait_until(|| *local.load() == 7));
+
+    for _ in 0..5 {
+        fs::write(&path, "7").unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let swap = handle.stop();
+    assert_eq!(swap.version(), 1);
+    fs::remove_file(&path).ok();
+}
@@ -0,0 +1,100 @@
+//! Tests for serde support
+//!
+//! Tests serializing and restoring SmrSwap and serializing ReadGuard
+
+extern crate std;
+use crate::SmrSwap;
+use serde::{Deserialize, Serialize};
+use std::prelude::v1::*;
+
+/// Test that serialization writes the value and its version
+/// 测试序列化会写入值及其版本
+#[test]
+fn test_serialize_with_version() {
+    let mut swap = SmrSwap::new(vec![1, 2, 3]);
+    swap.store(vec![4, 5]);
+    swap.store(vec![6]);
+
+    let json = serde_json::to_string(&swap).unwrap();
+    assert_eq!(json, r#"{"value":[6],"version":2}"#);
+}
+
+/// Test that deserialization restores the value and version
+/// 测试反序列化会恢复值和版本
+#[test]
+fn test_round_trip_restores_version() {
+    let mut swap = SmrSwap::new(String::from("a"));
+    for s in ["b", "c", "d"] {
+        swap.store(String::from(s));
+    }
+
+    let json = serde_json::to_string(&swap).unwrap();
+    let mut restored: SmrSwap<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(*restored.get(), "d");
+    assert_eq!(restored.version(), 3);
+    assert_eq!(restored.garbage_count(), 0);
+    assert_eq!(restored.previous(), None);
+
+    let local = restored.local();
+    assert_eq!(local.load().version(), 3);
+    restored.store(String::from("e"));
+    assert_eq!(restored.version(), 4);
+    assert_eq!(*local.load(), "e");
+}
+
+/// Test that a missing version starts the container at zero
+/// 测试缺少版本时容器从零开始
+#[test]
+fn test_deserialize_without_version() {
+    let restored: SmrSwap<u32> = serde_json::from_str(r#"{"value":7}"#).unwrap();
+    assert_eq!(*restored.get(), 7);
+    assert_eq!(restored.version(), 0);
+
+    let restored: SmrSwap<u32> = serde_json::from_str(r#"{"value":7,"version":null}"#).unwrap();
+    assert_eq!(restored.version(), 0);
+
+    assert!(serde_json::from_str::<SmrSwap<u32>>(r#"{"version":1}"#).is_err());
+}
+
+/// Test that ReadGuard serializes the pinned value directly
+/// 测试 ReadGuard 直接序列化被 pin 的值
+#[test]
+fn test_serialize_read_guard() {
+    let mut swap = SmrSwap::new(vec![String::from("x")]);
+    let local = swap.local();
+
+    let guard = local.load();
+    swap.store(vec![String::from("y"), String::from("z")]);
+
+    assert_eq!(serde_json::to_string(&guard).unwrap(), r#"["x"]"#);
+    assert_eq!(
+        serde_json::to_string(&local.load()).unwrap(),
+        r#"["y","z"]"#
+    );
+}
+
+/// Test the value_only field adapter
+/// 测试 value_only 字段适配器
+#[test]
+fn test_value_only() {
+    #[derive(Serialize, Deserialize)]
+    struct Config {
+        name: String,
+        #[serde(with = "crate::value_only")]
+        limit: SmrSwap<u32>,
+    }
+
+    let mut config = Config {
+        name: String::from("svc"),
+        limit: SmrSwap::new(1),
+    };
+    config.limit.store(5);
+
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(json, r#"{"name":"svc","limit":5}"#);
+
+    let restored: Config = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.name, "svc");
+    assert_eq!(*restored.limit.get(), 5);
+    assert_eq!(restored.limit.version(), 0);
+}
@@ -0,0 +1,235 @@
+//! Tests for sharded reader slots
+//!
+//! Tests that readers sharing a shard still hold back reclamation while pinned
+
+extern crate std;
+use super::DropCounter;
+use crate::{SmrSwap, Strategy};
+use std::prelude::v1::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+/// Test that sharded readers load and nest like registered readers
+/// 测试分片读者的加载和嵌套与已注册读者相同
+#[test]
+fn test_sharded_load() {
+    let mut swap = SmrSwap::builder()
+        .reader_shards(4)
+        .build(String::from("v0"));
+    let local = swap.local();
+
+    let outer = local.load();
+    swap.store(String::from("v1"));
+    let inner = local.load();
+    assert_eq!(*outer, "v0");
+    assert_eq!(*inner, "v1");
+    assert_eq!(inner.version(), outer.version());
+    assert!(local.is_pinned());
+
+    drop(outer);
+    drop(inner);
+    assert!(!local.is_pinned());
+    assert_eq!(*local.load(), "v1");
+    assert_eq!(local.load().version(), 1);
+}
+
+/// Test that a pinned shard keeps every value retired after its version
+/// 测试被 pin 的分片会保留其版本之后退休的所有值
+#[test]
+fn test_sharded_pin_blocks_reclamation() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = SmrSwap::builder()
+        .reader_shards(2)
+        .auto_reclaim_threshold(None)
+        .build(DropCounter(drops.clone()));
+    let local = swap.local();
+    let guard = local.load();
+
+    for _ in 0..5 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+    assert_eq!(swap.garbage_count(), 5);
+
+    drop(guard);
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+    assert_eq!(swap.garbage_count(), 2);
+}
+
+/// Test that a reader joining a lagging shard moves it on once the older readers unpin
+/// 测试加入落后分片的读者会在较旧的读者解除 pin 后使其前进
+#[test]
+fn test_shared_shard() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = SmrSwap::builder()
+        .reader_shards(1)
+        .auto_reclaim_threshold(None)
+        .build(DropCounter(drops.clone()));
+    let first = swap.local();
+    let second = first.clone();
+
+    let old = first.load();
+    for _ in 0..5 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    let new = second.load();
+
+    // The shard still announces the version the first reader pinned.
+    // 分片仍然公布第一个读者 pin 的版本。
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    // Only the second reader's version is left once the first reader unpins.
+    // 第一个读者解除 pin 后只剩下第二个读者的版本。
+    drop(old);
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+
+    drop(new);
+    swap.collect();
+    assert_eq!(swap.garbage_count(), 2);
+}
+
+/// Test that overlapping short pins on one shard never hold back reclamation for long
+/// 测试同一分片上相互重叠的短暂 pin 永远不会长时间阻止回收
+#[test]
+fn test_overlapping_pins() {
+    let mut swap = SmrSwap::builder()
+        .reader_shards(1)
+        .auto_reclaim_threshold(None)
+        .build(0);
+    let readers = [swap.local(), swap.local()];
+
+    // The shard is never unpinned: each guard is taken before the previous one is dropped.
+    // 分片从未解除 pin：每个守卫都在上一个守卫被 drop 之前获取。
+    let mut guard = readers[0].load();
+    for i in 1..=100 {
+        swap.store(i);
+        let next = readers[i % 2].load();
+        drop(guard);
+        guard = next;
+        swap.collect();
+        assert!(swap.garbage_count() <= 3, "{}", swap.garbage_count());
+    }
+}
+
+/// Test that garbage stays bounded while threads keep a shard pinned with overlapping guards
+/// 测试线程以相互重叠的守卫使分片保持被 pin 时，垃圾保持有界
+#[test]
+fn test_overlapping_pins_concurrent() {
+    const THREADS: usize = 4;
+    const ROUNDS: usize = 200;
+
+    let mut swap = SmrSwap::builder()
+        .reader_shards(1)
+        .auto_reclaim_threshold(None)
+        .build(0usize);
+    let reader = swap.reader();
+    let barrier = Arc::new(Barrier::new(THREADS + 1));
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let reader = reader.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                let readers = [reader.local(), reader.local()];
+                let mut guard = readers[0].load();
+                for round in 1..=ROUNDS {
+                    // Pin again before unpinning, so every thread always holds a guard.
+                    // 先再次 pin 再解除 pin，因此每个线程始终持有一个守卫。
+                    barrier.wait();
+                    let next = readers[round % 2].load();
+                    assert!(*next >= *guard);
+                    barrier.wait();
+                    drop(guard);
+                    guard = next;
+                    barrier.wait();
+                }
+            })
+        })
+        .collect();
+
+    for round in 1..=ROUNDS {
+        swap.store(round);
+        barrier.wait();
+        barrier.wait();
+        barrier.wait();
+        swap.collect();
+        assert!(swap.garbage_count() <= 3, "{}", swap.garbage_count());
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+/// Test that sharded readers are counted as live and pinned readers
+/// 测试分片读者被统计为存活和被 pin 的读者
+#[test]
+fn test_sharded_live_readers() {
+    let swap = SmrSwap::builder().reader_shards(2).build(0);
+    let reader = swap.reader();
+    let locals: Vec<_> = (0..3).map(|_| reader.local()).collect();
+    let _qsbr = swap.qsbr_reader();
+
+    let guard = locals[0].load();
+    let nested = locals[0].load();
+    let other = locals[1].load();
+    assert_eq!(swap.cell.live_readers(), (5, 3));
+
+    drop((guard, nested, other));
+    drop(locals);
+    assert_eq!(swap.cell.live_readers(), (2, 1));
+}
+
+/// Test sharded readers, registered QSBR readers and an adaptive writer across threads
+/// 测试分片读者、已注册的 QSBR 读者与自适应写者跨线程共存
+#[test]
+fn test_sharded_concurrent() {
+    let mut swap = SmrSwap::builder()
+        .reader_shards(2)
+        .strategy(Strategy::Adaptive)
+        .build(vec![0usize; 16]);
+    let reader = swap.reader();
+    let done = Arc::new(AtomicBool::new(false));
+
+    let handles: Vec<_> = (0..5)
+        .map(|i| {
+            let reader = reader.clone();
+            let done = done.clone();
+            thread::spawn(move || {
+                if i == 0 {
+                    let mut qsbr = reader.qsbr_reader();
+                    while !done.load(Ordering::Acquire) {
+                        let value = qsbr.load();
+                        assert!(value.iter().all(|&x| x == value[0]));
+                        qsbr.quiescent();
+                    }
+                } else {
+                    while !done.load(Ordering::Acquire) {
+                        // Short-lived readers exercise shard assignment as well as pinning.
+                        let local = reader.local();
+                        for _ in 0..16 {
+                            let value = local.load();
+                            assert!(value.iter().all(|&x| x == value[0]));
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for i in 1..=2000 {
+        swap.store(vec![i; 16]);
+    }
+    done.store(true, Ordering::Release);
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    swap.collect();
+    assert_eq!(swap.garbage_count(), 2);
+}
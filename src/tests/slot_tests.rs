@@ -0,0 +1,149 @@
+//! Tests for reader slot reuse
+//!
+//! Tests that dropped readers free their slots and that new readers reuse them
+
+extern crate std;
+use super::{DropCounter, build};
+use crate::{HazardPointers, Reclaimer, RefCounted, SmrSwap, Versioned};
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+fn check_reuse<R: Reclaimer>() {
+    let mut swap = build::<R, _>(0);
+    let slots = swap.reader_slots();
+    assert_eq!((slots.allocated(), slots.in_use(), slots.free()), (1, 1, 0));
+
+    let readers: Vec<_> = (0..3).map(|_| swap.local()).collect();
+    let slots = swap.reader_slots();
+    assert_eq!((slots.allocated(), slots.in_use(), slots.free()), (4, 4, 0));
+
+    drop(readers);
+    let slots = swap.reader_slots();
+    assert_eq!((slots.allocated(), slots.in_use(), slots.free()), (4, 1, 3));
+
+    for i in 1..=100 {
+        let local = swap.local();
+        swap.store(i);
+        assert_eq!(*local.load(), i);
+    }
+    let slots = swap.reader_slots();
+    assert_eq!((slots.allocated(), slots.in_use(), slots.free()), (4, 1, 3));
+}
+
+/// Test that dropped readers free their slots for the next readers
+/// 测试被 drop 的读者会将其槽释放给后续读者
+#[test]
+fn test_slot_reuse() {
+    check_reuse::<Versioned>();
+}
+
+/// Test slot reuse with the hazard-pointer backend
+/// 测试危险指针后端的槽复用
+#[test]
+fn test_hazard_slot_reuse() {
+    check_reuse::<HazardPointers>();
+}
+
+/// Test slot reuse with the reference-counting backend
+/// 测试引用计数后端的槽复用
+#[test]
+fn test_counted_slot_reuse() {
+    check_reuse::<RefCounted>();
+}
+
+/// Test that a reused slot does not hold back reclamation for its previous reader
+/// 测试被复用的槽不会因其之前的读者而阻止回收
+#[test]
+fn test_reused_slot_announces_fresh_version() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = build::<Versioned, _>(DropCounter(drops.clone()));
+
+    let old = swap.local();
+    let guard = old.load();
+    for _ in 0..3 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    drop(guard);
+    drop(old);
+
+    let new = swap.local();
+    let guard = new.load();
+    swap.store(DropCounter(drops.clone()));
+    swap.collect();
+    // Only the values retired after the new reader pinned are kept.
+    // 只保留新读者 pin 之后退休的值。
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+    assert_eq!(swap.reader_slots().allocated(), 2);
+    drop(guard);
+}
+
+/// Test that QSBR readers reuse slots and start from the current version
+/// 测试 QSBR 读者复用槽并从当前版本开始
+#[test]
+fn test_qsbr_slot_reuse() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = build::<Versioned, _>(DropCounter(drops.clone()));
+    drop(swap.qsbr_reader());
+    assert_eq!(swap.reader_slots().in_use(), 1);
+
+    for _ in 0..5 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    let qsbr = swap.qsbr_reader();
+    assert_eq!(qsbr.version(), 5);
+    swap.collect();
+    // The reused slot holds back nothing older than the current value.
+    // 被复用的槽不会保留比当前值更旧的任何值。
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+    assert_eq!(swap.garbage_count(), 2);
+
+    let slots = swap.reader_slots();
+    assert_eq!((slots.allocated(), slots.in_use()), (2, 2));
+}
+
+/// Test that slots stay bounded while reader threads come and go
+/// 测试读者线程反复创建和退出时槽数量保持有界
+#[test]
+fn test_thread_churn() {
+    let mut swap = SmrSwap::new(0usize);
+    let reader = swap.reader();
+
+    for round in 0..50 {
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let reader = reader.clone();
+                thread::spawn(move || {
+                    let local = reader.local();
+                    assert!(*local.load() <= round + 1);
+                })
+            })
+            .collect();
+        swap.store(round + 1);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    let slots = swap.reader_slots();
+    assert!(slots.allocated() <= 5, "{slots:?}");
+    assert_eq!(slots.in_use(), 1);
+}
+
+/// Test that fixed slots and shards are reported as allocated up front
+/// 测试固定槽和分片会被报告为预先分配
+#[test]
+fn test_preallocated_slots() {
+    let swap = SmrSwap::with_max_readers(0, 3);
+    let slots = swap.reader_slots();
+    assert_eq!((slots.allocated(), slots.in_use()), (4, 1));
+    let local = swap.local();
+    assert_eq!(swap.reader_slots().in_use(), 2);
+    drop(local);
+    assert_eq!(swap.reader_slots().in_use(), 1);
+
+    let swap = SmrSwap::builder().reader_shards(2).build(0);
+    let slots = swap.reader_slots();
+    assert_eq!((slots.allocated(), slots.in_use()), (2, 2));
+}
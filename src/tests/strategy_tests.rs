@@ -0,0 +1,319 @@
+//! Tests for per-container memory barrier strategies
+//!
+//! Tests that write-preferred and read-preferred containers coexist in one process
+
+extern crate std;
+use super::DropCounter;
+use crate::{SmrSwap, Strategy};
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Test that the default strategy follows the `read-preferred` feature
+/// 测试默认策略遵循 `read-preferred` feature
+#[test]
+fn test_strategy_default() {
+    let expected = if cfg!(feature = "read-preferred") {
+        Strategy::ReadPreferred
+    } else {
+        Strategy::WritePreferred
+    };
+    assert_eq!(Strategy::default(), expected);
+    assert_eq!(SmrSwap::new(0).strategy(), expected);
+}
+
+/// Test choosing the strategy through the builder
+/// 测试通过构建器选择策略
+#[test]
+fn test_strategy_builder() {
+    for strategy in [Strategy::WritePreferred, Strategy::ReadPreferred] {
+        let builder = SmrSwap::builder().strategy(strategy);
+        assert!(std::format!("{builder:?}").contains(&std::format!("{strategy:?}")));
+
+        let mut swap = builder.build(String::from("v0"));
+        assert_eq!(swap.strategy(), strategy);
+
+        let local = swap.local();
+        swap.store(String::from("v1"));
+        assert_eq!(*local.load(), "v1");
+    }
+}
+
+/// Test that a pinned value survives collection under the read-preferred strategy
+/// 测试在读优先策略下被 pin 的值在回收后仍然存活
+#[test]
+fn test_strategy_read_preferred_protects_guard() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = SmrSwap::builder()
+        .strategy(Strategy::ReadPreferred)
+        .auto_reclaim_threshold(None)
+        .build(DropCounter(drops.clone()));
+    let local = swap.local();
+
+    let guard = local.load();
+    for _ in 0..5 {
+        swap.store(DropCounter(drops.clone()));
+    }
+    swap.collect();
+    // The reader pinned version 0, so every retired value is still reachable.
+    // 读者 pin 了版本 0，因此每个已退休的值仍然可达。
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+    assert_eq!(swap.garbage_count(), 5);
+
+    drop(guard);
+    swap.collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+    assert_eq!(swap.garbage_count(), 2);
+}
+
+/// Test containers with different strategies used concurrently in one process
+/// 测试在同一进程中并发使用不同策略的容器
+#[test]
+fn test_strategy_mixed_concurrent() {
+    let mut config = SmrSwap::builder()
+        .strategy(Strategy::ReadPreferred)
+        .build(vec![0usize; 8]);
+    let mut stats = SmrSwap::builder()
+        .strategy(Strategy::WritePreferred)
+        .build(vec![0usize; 8]);
+    let config_reader = config.reader();
+    let stats_reader = stats.reader();
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            let config_reader = config_reader.clone();
+            let stats_reader = stats_reader.clone();
+            s.spawn(move || {
+                let config = config_reader.local();
+                let stats = stats_reader.local();
+                for _ in 0..2000 {
+                    let a = config.load();
+                    let b = stats.load();
+                    // Every stored vector is uniform, so a torn or freed read would show.
+                    // 每个存储的向量都是均匀的，因此撕裂或已释放的读取会被发现。
+                    assert!(a.iter().all(|&x| x == a[0]));
+                    assert!(b.iter().all(|&x| x == b[0]));
+                }
+            });
+        }
+
+        for i in 1..=200 {
+            config.store(vec![i; 8]);
+            for j in 0..10 {
+                stats.store(vec![i * 10 + j; 8]);
+            }
+        }
+    });
+
+    assert_eq!(config.load()[0], 200);
+    assert_eq!(stats.load()[0], 2009);
+}
+
+/// Test that an adaptive container follows the observed read/write ratio
+/// 测试自适应容器跟随观测到的读写比例
+#[test]
+fn test_strategy_adaptive_switches() {
+    let mut swap = SmrSwap::builder().strategy(Strategy::Adaptive).build(0);
+    let local = swap.local();
+    assert_eq!(swap.strategy(), Strategy::Adaptive);
+    assert_eq!(swap.active_strategy(), Strategy::WritePreferred);
+
+    // Read-mostly: 1000 loads for one store.
+    // 以读为主：每次存储对应 1000 次加载。
+    for _ in 0..1000 {
+        assert!(*local.load() >= 0);
+    }
+    swap.store(1);
+    swap.collect();
+    assert_eq!(swap.active_strategy(), Strategy::ReadPreferred);
+    assert_eq!(*local.load(), 1);
+
+    // Write-heavy: automatic collections sample the ratio and switch back.
+    // 以写为主：自动回收对比例进行采样并切换回来。
+    for i in 2..=300 {
+        swap.store(i);
+    }
+    assert_eq!(swap.active_strategy(), Strategy::WritePreferred);
+    assert_eq!(*local.load(), 300);
+    assert_eq!(swap.strategy(), Strategy::Adaptive);
+}
+
+/// Test that loads of dropped readers still count toward the ratio
+/// 测试已 drop 的读者的加载次数仍会计入比例
+#[test]
+fn test_strategy_adaptive_counts_dropped_readers() {
+    let mut swap = SmrSwap::builder().strategy(Strategy::Adaptive).build(0);
+
+    let reader = swap.reader();
+    thread::spawn(move || {
+        let local = reader.local();
+        for _ in 0..1000 {
+            let _ = *local.load();
+        }
+    })
+    .join()
+    .unwrap();
+
+    swap.store(1);
+    swap.collect();
+    assert_eq!(swap.active_strategy(), Strategy::ReadPreferred);
+}
+
+/// Test that an adaptive container stays correct while switching under concurrent reads
+/// 测试自适应容器在并发读取下切换时仍然保持正确
+#[test]
+fn test_strategy_adaptive_concurrent() {
+    use std::sync::atomic::AtomicBool;
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut swap = SmrSwap::builder()
+        .strategy(Strategy::Adaptive)
+        .build(vec![DropCounter(drops.clone())]);
+    let reader = swap.reader();
+    let reading = AtomicBool::new(true);
+    let done = AtomicBool::new(false);
+    let mut switches = 0;
+    let mut stored = 0;
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            let reader = reader.clone();
+            let (reading, done) = (&reading, &done);
+            s.spawn(move || {
+                let local = reader.local();
+                while !done.load(Ordering::Relaxed) {
+                    if reading.load(Ordering::Relaxed) {
+                        let guard = local.load();
+                        // A reclaimed vector would have been dropped and emptied.
+                        // 被回收的向量会被 drop 并清空。
+                        assert_eq!(guard.len(), 1);
+                    } else {
+                        thread::yield_now();
+                    }
+                }
+            });
+        }
+
+        let mut last = swap.active_strategy();
+        for round in 0..20 {
+            // Alternate between a read phase and a write phase with paused readers.
+            // 在读阶段和读者暂停的写阶段之间交替。
+            let read_phase = round % 2 == 0;
+            reading.store(read_phase, Ordering::Relaxed);
+            let stores = if read_phase { 1 } else { 300 };
+            if read_phase {
+                thread::sleep(std::time::Duration::from_millis(5));
+            }
+            for _ in 0..stores {
+                swap.store(vec![DropCounter(drops.clone())]);
+                stored += 1;
+            }
+            swap.collect();
+
+            let active = swap.active_strategy();
+            if active != last {
+                switches += 1;
+                last = active;
+            }
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+
+    assert!(switches >= 2, "only {switches} switches");
+    drop(reader);
+    drop(swap);
+    assert_eq!(drops.load(Ordering::SeqCst), stored + 1);
+}
+
+/// Test that a value pinned across adaptive switches is never reclaimed
+/// 测试跨越自适应切换被 pin 的值永远不会被回收
+#[test]
+fn test_strategy_adaptive_switch_keeps_pinned_value() {
+    use std::sync::Barrier;
+    use std::sync::atomic::AtomicBool;
+
+    struct Tracked {
+        id: usize,
+        alive: Arc<AtomicBool>,
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.alive.store(false, Ordering::SeqCst);
+        }
+    }
+
+    fn tracked(flags: &mut Vec<Arc<AtomicBool>>, id: usize) -> Tracked {
+        let alive = Arc::new(AtomicBool::new(true));
+        flags.push(alive.clone());
+        Tracked { id, alive }
+    }
+
+    let mut flags = Vec::new();
+
+    let mut swap = SmrSwap::builder()
+        .strategy(Strategy::Adaptive)
+        .build(tracked(&mut flags, 0));
+    let reader = swap.reader();
+    let barrier = Arc::new(Barrier::new(2));
+
+    // A detached thread, so that a failed assertion below fails the test instead of
+    // leaving the reader waiting on the barrier.
+    // 使用分离的线程，使下面失败的断言让测试失败，而不是让读者一直等待屏障。
+    let handle = {
+        let barrier = barrier.clone();
+        thread::spawn(move || {
+            let local = reader.local();
+            for _ in 0..1000 {
+                assert_eq!(local.load().id, 0);
+            }
+            barrier.wait();
+
+            // Pin the value while the container is read-preferred.
+            // 在容器处于读优先模式时 pin 该值。
+            barrier.wait();
+            let guard = local.load();
+            assert_eq!(guard.id, 1);
+            barrier.wait();
+
+            barrier.wait();
+            assert_eq!(guard.id, 1);
+            assert!(guard.alive.load(Ordering::SeqCst));
+            drop(guard);
+            barrier.wait();
+        })
+    };
+
+    barrier.wait();
+    swap.store(tracked(&mut flags, 1));
+    swap.collect();
+    assert_eq!(swap.active_strategy(), Strategy::ReadPreferred);
+    barrier.wait();
+    barrier.wait();
+
+    // Write-heavy: switch to write-preferred with the reader still pinned.
+    // 以写为主：在读者仍然 pin 着时切换到写优先。
+    for i in 2..=300 {
+        swap.store(tracked(&mut flags, i));
+    }
+    assert_eq!(swap.active_strategy(), Strategy::WritePreferred);
+
+    // Read-mostly again: switch back to read-preferred.
+    // 再次以读为主：切换回读优先。
+    let local = swap.local();
+    for _ in 0..1000 {
+        let _ = local.load();
+    }
+    swap.store(tracked(&mut flags, 301));
+    swap.collect();
+    assert_eq!(swap.active_strategy(), Strategy::ReadPreferred);
+    assert!(flags[1].load(Ordering::SeqCst));
+    assert!(!flags[0].load(Ordering::SeqCst));
+
+    barrier.wait();
+    barrier.wait();
+    handle.join().unwrap();
+    swap.collect();
+    assert!(!flags[1].load(Ordering::SeqCst));
+}
@@ -0,0 +1,190 @@
+//! Tests for the tracing feature
+//!
+//! Records spans and events with a minimal subscriber
+
+extern crate std;
+use crate::SmrSwap;
+use std::fmt::Write;
+use std::prelude::v1::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records span names and event fields as strings.
+/// 以字符串形式记录 span 名称和事件字段。
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<String>>>,
+    events: Arc<Mutex<Vec<String>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+struct FieldWriter(String);
+
+impl Visit for FieldWriter {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        write!(self.0, "{}={:?} ", field.name(), value).unwrap();
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+        let mut fields = FieldWriter(format!("{} ", attrs.metadata().name()));
+        attrs.record(&mut fields);
+        self.spans.lock().unwrap().push(fields.0);
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = FieldWriter(String::new());
+        event.record(&mut fields);
+        self.events.lock().unwrap().push(fields.0);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+impl Recorder {
+    fn events_containing(&self, needle: &str) -> Vec<String> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.contains(needle))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Test that store and update emit spans and version events
+/// 测试 store 和 update 会产生 span 和版本事件
+#[test]
+fn test_trace_store_and_update() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut swap = SmrSwap::builder().auto_reclaim_threshold(None).build(0);
+        swap.store(1);
+        swap.update(|v| v + 1);
+    });
+
+    let spans = recorder.spans.lock().unwrap().clone();
+    assert!(
+        spans
+            .iter()
+            .any(|s| s.contains("smr_swap::write op=\"store\""))
+    );
+    assert!(
+        spans
+            .iter()
+            .any(|s| s.contains("smr_swap::write op=\"update\""))
+    );
+
+    let stored = recorder.events_containing("stored a new value");
+    assert_eq!(stored.len(), 2);
+    assert!(stored[0].contains("old_version=0 new_version=1"));
+    assert!(stored[1].contains("old_version=1 new_version=2"));
+}
+
+/// Test that automatic and manual collections report reclaimed counts
+/// 测试自动和手动回收会报告回收数量
+#[test]
+fn test_trace_collect() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut swap = SmrSwap::builder().auto_reclaim_threshold(Some(4)).build(0);
+        for i in 1..=5 {
+            swap.store(i);
+        }
+        for i in 6..=7 {
+            swap.store(i);
+        }
+        swap.collect();
+    });
+
+    let spans = recorder.spans.lock().unwrap().clone();
+    assert!(
+        spans
+            .iter()
+            .any(|s| s.contains("smr_swap::collect automatic=true"))
+    );
+    assert!(
+        spans
+            .iter()
+            .any(|s| s.contains("smr_swap::collect automatic=false"))
+    );
+
+    let collected = recorder.events_containing("reclaimed retired values");
+    assert_eq!(collected.len(), 2);
+    assert!(collected[0].contains("reclaimed=3 remaining=2"));
+    assert!(collected[0].contains("drop_time="));
+    assert!(collected[1].contains("reclaimed=2 remaining=2"));
+}
+
+/// Test that reader registration and deregistration are reported
+/// 测试读者注册和注销会被报告
+#[test]
+fn test_trace_readers() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut swap = SmrSwap::new(0);
+        let local = swap.local();
+        let other = local.clone();
+        drop(local);
+        drop(other);
+        // Takes one of the freed slots.
+        // 获取一个已释放的槽。
+        let _reused = swap.local();
+        swap.collect();
+    });
+
+    let registered = recorder.events_containing("reader registered");
+    // The writer's internal reader, plus three explicit readers.
+    // 写者内部的读者，加上三个显式读者。
+    assert_eq!(registered.len(), 4);
+    assert!(registered[2].contains("readers=3"));
+    assert!(registered[3].contains("readers=2"));
+    // Dropping the container also drops its internal reader.
+    // drop 容器时也会 drop 其内部读者。
+    assert_eq!(recorder.events_containing("reader deregistered").len(), 4);
+    assert!(
+        recorder
+            .events_containing("removed dropped readers")
+            .is_empty()
+    );
+}
+
+/// Test that bounded, sharded and fixed-slot QSBR readers are reported as well
+/// 测试有上限的读者、分片读者和固定槽中的 QSBR 读者同样会被报告
+#[test]
+fn test_trace_bounded_and_sharded_readers() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let bounded = SmrSwap::with_max_readers(0, 2);
+        let local = bounded.local();
+        let qsbr = bounded.qsbr_reader();
+        drop((local, qsbr));
+
+        let sharded = SmrSwap::builder().reader_shards(2).build(0);
+        drop(sharded.local());
+    });
+
+    let registered = recorder.events_containing("reader registered");
+    // Each container's internal reader, plus three explicit readers.
+    // 每个容器内部的读者，加上三个显式读者。
+    assert_eq!(registered.len(), 5);
+    assert!(registered[2].contains("readers=3"));
+    assert!(registered[4].contains("readers=2"));
+    assert_eq!(recorder.events_containing("reader deregistered").len(), 5);
+}
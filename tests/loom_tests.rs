@@ -6,8 +6,43 @@
 
 use loom::model::Builder;
 use loom::sync::Arc;
+use loom::sync::atomic::{AtomicBool, Ordering};
 use loom::thread;
-use smr_swap::SmrSwap;
+use smr_swap::{LocalReader, SmrSwap};
+
+/// A value that records when it is dropped, so readers can check the value they hold.
+/// 在被 drop 时进行记录的值，以便读者检查其持有的值。
+struct Canary {
+    value: usize,
+    alive: Arc<AtomicBool>,
+}
+
+impl Canary {
+    fn new(value: usize) -> Self {
+        Self {
+            value,
+            alive: Arc::new(AtomicBool::new(true)),
+        }
+    }
+}
+
+impl Drop for Canary {
+    fn drop(&mut self) {
+        self.alive.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Pin a value and check that it stays alive and unchanged while the writer runs.
+/// pin 一个值，并检查它在写者运行期间保持存活且不被修改。
+fn spawn_pinned_reader(reader: LocalReader<Canary>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let guard = reader.load();
+        let value = guard.value;
+        thread::yield_now();
+        assert!(guard.alive.load(Ordering::SeqCst));
+        assert_eq!(guard.value, value);
+    })
+}
 
 /// Test: Basic update and read concurrency
 /// 测试：基本的更新和读取并发
@@ -301,3 +336,154 @@ fn loom_into_swmr() {
         t.join().unwrap();
     });
 }
+
+/// Test: Collection never reclaims a pinned value
+/// 测试：回收从不回收被 pin 的值
+#[test]
+fn loom_pin_collect() {
+    let mut builder = Builder::new();
+    builder.preemption_bound = Some(3);
+    builder.check(|| {
+        let mut swap = SmrSwap::builder()
+            .auto_reclaim_threshold(None)
+            .build(Canary::new(0));
+        let t = spawn_pinned_reader(swap.local());
+
+        for i in 1..=3 {
+            swap.store(Canary::new(i));
+        }
+        swap.collect();
+
+        t.join().unwrap();
+        swap.collect();
+        assert_eq!(swap.garbage_count(), 2);
+    });
+}
+
+/// Test: A recycled allocation is never reused while a reader holds it
+/// 测试：读者持有的内存分配永远不会被回收复用
+#[test]
+fn loom_recycled_reclaim() {
+    let mut builder = Builder::new();
+    builder.preemption_bound = Some(3);
+    builder.check(|| {
+        let mut swap = SmrSwap::builder()
+            .auto_reclaim_threshold(None)
+            .recycle_capacity(2)
+            .build(Canary::new(0));
+        let t = spawn_pinned_reader(swap.local());
+
+        for i in 1..=4 {
+            swap.store_with(|old| {
+                let mut canary = old.unwrap_or_else(|| Canary::new(i));
+                canary.value = i;
+                canary
+            });
+            swap.collect();
+        }
+
+        t.join().unwrap();
+    });
+}
+
+/// Test: Budgeted collections release only unpinned values
+/// 测试：受预算限制的回收只释放未被 pin 的值
+#[test]
+fn loom_release_within() {
+    let mut builder = Builder::new();
+    builder.preemption_bound = Some(3);
+    builder.check(|| {
+        let mut swap = SmrSwap::builder()
+            .auto_reclaim_threshold(None)
+            .recycle_capacity(1)
+            .build(Canary::new(0));
+        let t = spawn_pinned_reader(swap.local());
+
+        for i in 1..=4 {
+            swap.store(Canary::new(i));
+        }
+        let collected = swap.collect_budget(1);
+        assert!(collected.reclaimed() <= 1);
+        swap.collect_budget(1);
+
+        t.join().unwrap();
+        while !swap.collect_budget(1).is_complete() {}
+        assert_eq!(swap.garbage_count(), 2);
+    });
+}
+
+/// Test: A bounded reader that retries its pin at most once never sees a reclaimed value
+/// 测试：最多重试一次 pin 的有上限读者永远不会看到已被回收的值
+#[test]
+fn loom_bounded_retry_once() {
+    let mut builder = Builder::new();
+    builder.preemption_bound = Some(3);
+    builder.check(|| {
+        let mut swap = SmrSwap::builder()
+            .max_readers(1)
+            .auto_reclaim_threshold(None)
+            .build(Canary::new(0));
+        let t = spawn_pinned_reader(swap.try_local().unwrap());
+
+        // Each collection may publish a newer `min_active` between the reader's
+        // announcement and its check, which sends the reader down the retry path.
+        // 每次回收都可能在读者公布版本与检查之间发布更新的 `min_active`，
+        // 从而让读者进入重试路径。
+        for i in 1..=3 {
+            swap.store(Canary::new(i));
+            swap.collect();
+        }
+
+        t.join().unwrap();
+        swap.collect();
+        assert_eq!(swap.garbage_count(), 2);
+    });
+}
+
+/// Test: A shard kept pinned by overlapping guards still lets collection move on
+/// 测试：被相互重叠的守卫保持 pin 的分片仍然允许回收继续推进
+#[test]
+fn loom_shard_overlapping_pins() {
+    let mut builder = Builder::new();
+    builder.preemption_bound = Some(2);
+    builder.check(|| {
+        let mut swap = SmrSwap::builder()
+            .reader_shards(1)
+            .auto_reclaim_threshold(None)
+            .build(Canary::new(0));
+        let (first, second) = (swap.local(), swap.local());
+        let stored = Arc::new(AtomicBool::new(false));
+        let moved = Arc::new(AtomicBool::new(false));
+
+        let t = {
+            let stored = stored.clone();
+            let moved = moved.clone();
+            thread::spawn(move || {
+                let old = first.load();
+                while !stored.load(Ordering::SeqCst) {
+                    thread::yield_now();
+                }
+                // The new guard pins the shard before the old one unpins it.
+                // 新守卫在旧守卫解除 pin 之前 pin 该分片。
+                let new = second.load();
+                assert!(old.alive.load(Ordering::SeqCst));
+                drop(old);
+                moved.store(true, Ordering::SeqCst);
+                assert!(new.alive.load(Ordering::SeqCst));
+                assert_eq!(new.value, 5);
+            })
+        };
+
+        for i in 1..=5 {
+            swap.store(Canary::new(i));
+        }
+        stored.store(true, Ordering::SeqCst);
+        while !moved.load(Ordering::SeqCst) {
+            thread::yield_now();
+        }
+        swap.collect();
+        assert_eq!(swap.garbage_count(), 2);
+
+        t.join().unwrap();
+    });
+}